@@ -0,0 +1,90 @@
+//! Timing of both aligners over the fixture scenarios.
+//!
+//! Run with `cargo bench`. Each case reports the median time per iteration,
+//! so successive runs can be compared to catch regressions.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use seq_align::{
+    banded::{
+        banded_needleman_wunsch,
+        vectorized_banded_needleman_wunsch,
+        Band,
+    },
+    fixtures::{batch_scenario, pair_scenarios},
+    global::{needleman_wunsch, GlobalAlignmentConfig},
+    local::{best_smith_waterman, LocalAlignmentConfig},
+    search::{search, SearchConfig, Target},
+};
+
+/// Minimum time spent measuring each case.
+const BUDGET: Duration = Duration::from_millis(500);
+
+/// Runs `routine` repeatedly and prints its median time per iteration.
+fn bench<F, T>(name: &str, mut routine: F)
+where
+    F: FnMut() -> T,
+{
+    let mut samples = Vec::new();
+    let started = Instant::now();
+    while started.elapsed() < BUDGET || samples.len() < 5 {
+        let sample_start = Instant::now();
+        black_box(routine());
+        samples.push(sample_start.elapsed());
+    }
+    samples.sort();
+    let median = samples[samples.len() / 2];
+    println!("{name:<40} {:>12.3?} ({} samples)", median, samples.len());
+}
+
+fn main() {
+    let global_config = GlobalAlignmentConfig::default();
+    let local_config = LocalAlignmentConfig::default();
+
+    for scenario in pair_scenarios(0xbe4c) {
+        bench(&format!("needleman_wunsch/{}", scenario.name), || {
+            needleman_wunsch(
+                &scenario.row_seq,
+                &scenario.column_seq,
+                global_config,
+            )
+        });
+        bench(&format!("best_smith_waterman/{}", scenario.name), || {
+            best_smith_waterman(
+                &scenario.row_seq,
+                &scenario.column_seq,
+                local_config,
+            )
+        });
+        let band = Band { lo: -32, hi: 32 };
+        bench(&format!("banded_needleman_wunsch/{}", scenario.name), || {
+            banded_needleman_wunsch(
+                &scenario.row_seq,
+                &scenario.column_seq,
+                global_config,
+                band,
+            )
+        });
+        bench(
+            &format!("vectorized_banded_needleman_wunsch/{}", scenario.name),
+            || {
+                vectorized_banded_needleman_wunsch(
+                    &scenario.row_seq,
+                    &scenario.column_seq,
+                    global_config,
+                    band,
+                )
+            },
+        );
+    }
+
+    let batch = batch_scenario("search/100x300", 0xba7c, 100, 300);
+    bench(batch.name, || {
+        let targets =
+            batch.targets.iter().map(|seq| Target { id: "target", seq });
+        search(&batch.query, targets, SearchConfig::default()).len()
+    });
+}
@@ -0,0 +1,392 @@
+use crate::{
+    global::{fill_nw_matrix_content, needleman_wunsch, GlobalAlignmentResult},
+    letter::{Letter, GAP},
+    local::{best_smith_waterman, LocalAlignmentResult, LocallyAlignedSeq},
+    matrix::AlignmentMatrix,
+    score::{Score, ScoringScheme},
+};
+
+/// Which parts of the sequences must take part in the alignment.
+///
+/// "Free" overhangs are left out of the alignment without any gap penalty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlignmentMode {
+    /// Both sequences are aligned end to end (Needleman-Wunsch).
+    Global,
+    /// Only the best scoring pair of subsequences is aligned
+    /// (Smith-Waterman).
+    Local,
+    /// Both sequences are aligned end to end, but overhangs at either end of
+    /// either sequence are free.
+    SemiGlobal,
+    /// A suffix of the row sequence is aligned with a prefix of the column
+    /// sequence, as when two fragments overlap (dovetail).
+    Overlap,
+    /// The whole column sequence is aligned within the row sequence, whose
+    /// overhangs are free, as when placing a read on a reference.
+    Fitting,
+    /// Both sequences are aligned end to end, but the chosen overhangs are
+    /// free, covering every dovetail and containment case.
+    Custom(FreeEndGaps),
+}
+
+impl AlignmentMode {
+    /// Overhangs left free by this mode, or `None` in local mode, where
+    /// every overhang is free and the alignment may end anywhere.
+    pub fn free_end_gaps(self) -> Option<FreeEndGaps> {
+        match self {
+            Self::Global => Some(FreeEndGaps::default()),
+            Self::Local => None,
+            Self::SemiGlobal => Some(FreeEndGaps {
+                row_leading: true,
+                row_trailing: true,
+                column_leading: true,
+                column_trailing: true,
+            }),
+            Self::Overlap => Some(FreeEndGaps {
+                row_leading: true,
+                column_trailing: true,
+                ..FreeEndGaps::default()
+            }),
+            Self::Fitting => Some(FreeEndGaps {
+                row_leading: true,
+                row_trailing: true,
+                ..FreeEndGaps::default()
+            }),
+            Self::Custom(free) => Some(free),
+        }
+    }
+}
+
+/// Which end gaps are not penalized, i.e. which overhangs are free.
+///
+/// The default leaves nothing free, as in a global alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FreeEndGaps {
+    /// Leading letters of the row sequence may be skipped.
+    pub row_leading: bool,
+    /// Trailing letters of the row sequence may be skipped.
+    pub row_trailing: bool,
+    /// Leading letters of the column sequence may be skipped.
+    pub column_leading: bool,
+    /// Trailing letters of the column sequence may be skipped.
+    pub column_trailing: bool,
+}
+
+/// An alignment in any mode: the aligned ranges of both sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    /// Mode the alignment was computed with.
+    pub mode: AlignmentMode,
+    /// The aligned range of the row sequence, with gaps.
+    pub aligned_row_seq: LocallyAlignedSeq,
+    /// The aligned range of the column sequence, with gaps.
+    pub aligned_column_seq: LocallyAlignedSeq,
+    /// Total score of the alignment.
+    pub score: Score,
+    /// Numerator of the identity fraction (32-bit).
+    pub identity_numer: u32,
+    /// Denominator of the identity fraction (32-bit).
+    pub identity_denom: u32,
+}
+
+impl Alignment {
+    /// Computes the identity as a percentage.
+    pub fn identity(&self) -> f64 {
+        f64::from(self.identity_numer) / f64::from(self.identity_denom)
+    }
+}
+
+impl From<GlobalAlignmentResult> for Alignment {
+    fn from(result: GlobalAlignmentResult) -> Self {
+        let count = |seq: &[Letter]| {
+            seq.iter().filter(|letter| **letter != GAP).count()
+        };
+        Self {
+            mode: AlignmentMode::Global,
+            aligned_row_seq: LocallyAlignedSeq {
+                start: 0,
+                end: count(&result.aligned_row_seq),
+                data: result.aligned_row_seq,
+            },
+            aligned_column_seq: LocallyAlignedSeq {
+                start: 0,
+                end: count(&result.aligned_column_seq),
+                data: result.aligned_column_seq,
+            },
+            score: result.score,
+            identity_numer: result.identity_numer,
+            identity_denom: result.identity_denom,
+        }
+    }
+}
+
+impl From<LocalAlignmentResult> for Alignment {
+    fn from(result: LocalAlignmentResult) -> Self {
+        Self {
+            mode: AlignmentMode::Local,
+            aligned_row_seq: result.aligned_row_seq,
+            aligned_column_seq: result.aligned_column_seq,
+            score: result.score,
+            identity_numer: result.identity_numer,
+            identity_denom: result.identity_denom,
+        }
+    }
+}
+
+/// Aligns `row_seq` against `column_seq` in the given mode, with the same
+/// scoring parameters whatever the mode.
+///
+/// In local mode, the first of the best alignments is returned, or an empty
+/// alignment with score zero if nothing scores positively.
+pub fn align<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    mode: AlignmentMode,
+    scheme: S,
+) -> Alignment
+where
+    S: ScoringScheme,
+{
+    let free = match mode {
+        AlignmentMode::Global => {
+            return needleman_wunsch(row_seq, column_seq, scheme).into();
+        },
+        AlignmentMode::Local => {
+            return best_smith_waterman(row_seq, column_seq, scheme)
+                .into_iter()
+                .next()
+                .map_or_else(|| empty_alignment(mode), Alignment::from);
+        },
+        AlignmentMode::SemiGlobal
+        | AlignmentMode::Overlap
+        | AlignmentMode::Fitting
+        | AlignmentMode::Custom(_) => mode.free_end_gaps().unwrap_or_default(),
+    };
+    align_free_ends(row_seq, column_seq, &scheme, free, mode)
+}
+
+/// An alignment of nothing against nothing.
+fn empty_alignment(mode: AlignmentMode) -> Alignment {
+    let empty = LocallyAlignedSeq { start: 0, end: 0, data: Vec::new() };
+    Alignment {
+        mode,
+        aligned_row_seq: empty.clone(),
+        aligned_column_seq: empty,
+        score: 0,
+        identity_numer: 0,
+        identity_denom: 1,
+    }
+}
+
+/// Global alignment where the chosen end gaps are not penalized.
+fn align_free_ends<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: &S,
+    free: FreeEndGaps,
+    mode: AlignmentMode,
+) -> Alignment
+where
+    S: ScoringScheme + ?Sized,
+{
+    let matrix = fill_free_ends_matrix(row_seq, column_seq, scheme, free);
+    let height = matrix.height();
+    let width = matrix.width();
+    let mut end = (height - 1, width - 1);
+    if free.row_trailing {
+        for i in 0 .. height {
+            if matrix[[i, width - 1]] > matrix[[end.0, end.1]] {
+                end = (i, width - 1);
+            }
+        }
+    }
+    if free.column_trailing {
+        for j in 0 .. width {
+            if matrix[[height - 1, j]] > matrix[[end.0, end.1]] {
+                end = (height - 1, j);
+            }
+        }
+    }
+    traceback_free_ends(row_seq, column_seq, scheme, free, mode, &matrix, end)
+}
+
+/// Fills the score matrix of a global alignment where the chosen leading end
+/// gaps are not penalized: only the borders differ from Needleman-Wunsch.
+pub(crate) fn fill_free_ends_matrix<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: &S,
+    free: FreeEndGaps,
+) -> AlignmentMatrix
+where
+    S: ScoringScheme + ?Sized,
+{
+    let height = row_seq.len() + 1;
+    let width = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(height, width);
+    for i in 1 .. height {
+        if !free.row_leading {
+            matrix[[i, 0]] = i as Score * scheme.gap_penalty();
+        }
+    }
+    for j in 1 .. width {
+        if !free.column_leading {
+            matrix[[0, j]] = j as Score * scheme.gap_penalty();
+        }
+    }
+    fill_nw_matrix_content(
+        row_seq,
+        column_seq,
+        scheme,
+        &mut matrix,
+        None,
+        None,
+    );
+    matrix
+}
+
+/// Traces back an alignment of a matrix filled by [`fill_free_ends_matrix`],
+/// from the given end cell, tagging the result with the given mode.
+pub(crate) fn traceback_free_ends<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: &S,
+    free: FreeEndGaps,
+    mode: AlignmentMode,
+    matrix: &AlignmentMatrix,
+    end: (usize, usize),
+) -> Alignment
+where
+    S: ScoringScheme + ?Sized,
+{
+    let (mut i, mut j) = end;
+    let mut alignment = empty_alignment(mode);
+    alignment.score = matrix[[i, j]];
+    alignment.identity_denom = 0;
+    let row = &mut alignment.aligned_row_seq;
+    let column = &mut alignment.aligned_column_seq;
+    loop {
+        let done = (i == 0 || (j == 0 && free.row_leading))
+            && (j == 0 || (i == 0 && free.column_leading));
+        if done {
+            break;
+        }
+        let score = matrix[[i, j]];
+        if i > 0
+            && j > 0
+            && score
+                == matrix[[i - 1, j - 1]]
+                    + scheme.score(row_seq[i - 1], column_seq[j - 1])
+        {
+            i -= 1;
+            j -= 1;
+            row.data.push(row_seq[i]);
+            column.data.push(column_seq[j]);
+            alignment.identity_denom += 1;
+            if row_seq[i] == column_seq[j] {
+                alignment.identity_numer += 1;
+            }
+        } else if i > 0
+            && (j == 0 || score == matrix[[i - 1, j]] + scheme.gap_penalty())
+        {
+            i -= 1;
+            row.data.push(row_seq[i]);
+            column.data.push(GAP);
+        } else {
+            j -= 1;
+            row.data.push(GAP);
+            column.data.push(column_seq[j]);
+        }
+    }
+    row.data.reverse();
+    column.data.reverse();
+    row.start = i;
+    row.end = end.0;
+    column.start = j;
+    column.end = end.1;
+    alignment.identity_denom = alignment.identity_denom.max(1);
+    alignment
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        score::SubstitutionMatrix,
+    };
+
+    use super::{align, AlignmentMode, FreeEndGaps};
+
+    #[test]
+    fn modes_choose_free_overhangs() {
+        let config = GlobalAlignmentConfig::default();
+        let reference: Vec<char> = "TTTTGATTACATTTT".chars().collect();
+        let read: Vec<char> = "GATTACA".chars().collect();
+
+        let fitting = align(&reference, &read, AlignmentMode::Fitting, config);
+        assert_eq!(fitting.score, 7);
+        assert_eq!(
+            fitting.aligned_row_seq.start .. fitting.aligned_row_seq.end,
+            4 .. 11
+        );
+        assert_eq!(fitting.aligned_column_seq.start, 0);
+        assert_eq!(fitting.aligned_column_seq.end, 7);
+        assert_eq!(fitting.aligned_row_seq.data, read);
+
+        let left: Vec<char> = "CCCCGATTA".chars().collect();
+        let right: Vec<char> = "GATTAGGGG".chars().collect();
+        let overlap = align(&left, &right, AlignmentMode::Overlap, config);
+        assert_eq!(overlap.score, 5);
+        assert_eq!(overlap.aligned_row_seq.start, 4);
+        assert_eq!(overlap.aligned_column_seq.end, 5);
+
+        let semiglobal =
+            align(&right, &left, AlignmentMode::SemiGlobal, config);
+        assert_eq!(semiglobal.score, 5);
+        let reversed_overlap = FreeEndGaps {
+            column_leading: true,
+            row_trailing: true,
+            ..FreeEndGaps::default()
+        };
+        let custom = align(
+            &right,
+            &left,
+            AlignmentMode::Custom(reversed_overlap),
+            config,
+        );
+        assert_eq!(custom.score, 5);
+        assert_eq!(custom.aligned_column_seq.start, 4);
+        assert_eq!(custom.aligned_row_seq.end, 5);
+        assert_eq!(
+            AlignmentMode::Custom(reversed_overlap).free_end_gaps(),
+            Some(reversed_overlap)
+        );
+
+        let global = align(&reference, &read, AlignmentMode::Global, config);
+        assert_eq!(
+            global.score,
+            needleman_wunsch(&reference, &read, config).score
+        );
+        let local = align(&reference, &read, AlignmentMode::Local, config);
+        assert_eq!(local.score, 7);
+        assert_eq!(local.mode, AlignmentMode::Local);
+    }
+
+    #[test]
+    fn any_scoring_scheme_is_accepted() {
+        let scheme =
+            SubstitutionMatrix::new(-1, -2).with('A', 'G', 2).with('G', 'G', 3);
+        let reference: Vec<char> = "CCAGCC".chars().collect();
+        let read: Vec<char> = "GG".chars().collect();
+
+        let fitting = align(&reference, &read, AlignmentMode::Fitting, &scheme);
+        assert_eq!(fitting.score, 5);
+        assert_eq!(
+            fitting.aligned_row_seq.start .. fitting.aligned_row_seq.end,
+            2 .. 4
+        );
+        let local = align(&reference, &read, AlignmentMode::Local, &scheme);
+        assert_eq!(local.score, 5);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    arena::Arena,
+    global::{
+        fill_nw_matrix,
+        traceback_nw_best_alignment,
+        GlobalAlignmentResult,
+    },
+    letter::GenericLetter,
+    local::{smith_waterman_in, LocalAlignmentResult},
+    matrix::AlignmentMatrix,
+    score::ScoringScheme,
+};
+
+/// Aligner owning an [`Arena`] whose score matrix buffer is reused by every
+/// alignment, so that aligning one query against many targets allocates the
+/// matrix once, growing it only when a larger pair comes.
+#[derive(Debug, Clone, Default)]
+pub struct Aligner {
+    arena: Arena,
+}
+
+impl Aligner {
+    /// Creates an aligner with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an aligner whose buffer already fits a matrix of the given
+    /// number of cells.
+    pub fn with_capacity(cells: usize) -> Self {
+        let mut arena = Arena::new();
+        let buf = Vec::with_capacity(cells);
+        arena.recycle_matrix(AlignmentMatrix::from_zeroed_buf(buf, 1));
+        Self { arena }
+    }
+
+    /// Number of cells the buffer holds without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.arena.matrix_capacity()
+    }
+
+    /// Aligns two sequences globally, like
+    /// [`needleman_wunsch`](crate::global::needleman_wunsch).
+    pub fn align_global<L, S>(
+        &mut self,
+        row_seq: &[L],
+        column_seq: &[L],
+        scheme: S,
+    ) -> GlobalAlignmentResult<L>
+    where
+        L: GenericLetter,
+        S: ScoringScheme<L>,
+    {
+        let mut matrix =
+            self.arena.matrix(row_seq.len() + 1, column_seq.len() + 1);
+        fill_nw_matrix(row_seq, column_seq, &scheme, &mut matrix);
+        let result =
+            traceback_nw_best_alignment(row_seq, column_seq, &scheme, &matrix);
+        self.arena.recycle_matrix(matrix);
+        result
+    }
+
+    /// Computes the best local alignments of two sequences, like
+    /// [`best_smith_waterman`](crate::local::best_smith_waterman).
+    pub fn align_local<L, S>(
+        &mut self,
+        row_seq: &[L],
+        column_seq: &[L],
+        scheme: S,
+    ) -> Vec<LocalAlignmentResult<L>>
+    where
+        L: GenericLetter,
+        S: ScoringScheme<L>,
+    {
+        smith_waterman_in(row_seq, column_seq, scheme, &mut self.arena)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::Aligner;
+
+    #[test]
+    fn reused_buffer_gives_fresh_results() {
+        let mut rng = Rng::new(99);
+        let query = random_dna(40, &mut rng);
+        let targets: Vec<Vec<char>> = [50, 20, 45]
+            .into_iter()
+            .map(|len| random_dna(len, &mut rng))
+            .collect();
+        let global = GlobalAlignmentConfig::default();
+        let local = LocalAlignmentConfig::default();
+
+        let mut aligner = Aligner::new();
+        for target in &targets {
+            assert_eq!(
+                aligner.align_global(&query, target, global),
+                needleman_wunsch(&query, target, global)
+            );
+            assert_eq!(
+                aligner.align_local(&query, target, local),
+                best_smith_waterman(&query, target, local)
+            );
+        }
+        assert!(aligner.capacity() >= 41 * 51);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentConfig, GlobalAlignmentResult},
+    letter::{GenericLetter, Letter, GAP},
+    score::{Score, ScoringScheme},
+};
+
+/// A sequence with an optional per-position annotation track, e.g. secondary
+/// structure (`H` helix, `E` strand, `C` coil) or domain labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnotatedSeq<'a> {
+    /// The sequence itself.
+    pub seq: &'a [Letter],
+    /// One label per letter of the sequence, if annotated. Positions past the
+    /// end of the track are considered unannotated.
+    pub track: Option<&'a [char]>,
+}
+
+impl<'a> AnnotatedSeq<'a> {
+    /// A sequence without annotations.
+    pub fn plain(seq: &'a [Letter]) -> Self {
+        Self { seq, track: None }
+    }
+
+    /// A sequence with a track of labels.
+    pub fn annotated(seq: &'a [Letter], track: &'a [char]) -> Self {
+        Self { seq, track: Some(track) }
+    }
+
+    /// Label of a position, if annotated.
+    pub fn label(&self, position: usize) -> Option<char> {
+        self.track?.get(position).copied()
+    }
+}
+
+/// Penalty/base score system of an annotation-aware global alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnotatedAlignmentConfig {
+    /// Scores of the letters themselves.
+    pub alignment: GlobalAlignmentConfig,
+    /// Added when both letters are annotated with the same label.
+    pub agreement_bonus: Score,
+    /// Added when both letters are annotated with different labels.
+    pub disagreement_penalty: Score,
+}
+
+impl Default for AnnotatedAlignmentConfig {
+    fn default() -> Self {
+        Self {
+            alignment: GlobalAlignmentConfig::default(),
+            agreement_bonus: 1,
+            disagreement_penalty: -1,
+        }
+    }
+}
+
+impl AnnotatedAlignmentConfig {
+    /// Adjustment of a pair score given the labels of both letters. Nothing is
+    /// added unless both letters are annotated.
+    pub fn annotation_score(
+        &self,
+        row_label: Option<char>,
+        column_label: Option<char>,
+    ) -> Score {
+        match (row_label, column_label) {
+            (Some(row_label), Some(column_label))
+                if row_label == column_label =>
+            {
+                self.agreement_bonus
+            },
+            (Some(_), Some(_)) => self.disagreement_penalty,
+            _ => 0,
+        }
+    }
+}
+
+/// A letter together with its label, so that annotated sequences can go
+/// through the generic Needleman-Wunsch.
+///
+/// Only the letters take part in equality, so identities are counted as for
+/// plain letters.
+#[derive(Debug, Clone, Copy)]
+struct AnnotatedLetter {
+    letter: Letter,
+    label: Option<char>,
+}
+
+impl PartialEq for AnnotatedLetter {
+    fn eq(&self, other: &Self) -> bool {
+        self.letter == other.letter
+    }
+}
+
+impl Eq for AnnotatedLetter {}
+
+impl GenericLetter for AnnotatedLetter {
+    const GAP: Self = Self { letter: GAP, label: None };
+
+    fn to_char(self) -> char {
+        self.letter
+    }
+}
+
+impl ScoringScheme<AnnotatedLetter> for AnnotatedAlignmentConfig {
+    fn score(
+        &self,
+        row_letter: AnnotatedLetter,
+        column_letter: AnnotatedLetter,
+    ) -> Score {
+        self.alignment.score(row_letter.letter, column_letter.letter)
+            + self.annotation_score(row_letter.label, column_letter.label)
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.alignment.gap_penalty
+    }
+}
+
+impl<'a> AnnotatedSeq<'a> {
+    /// Pairs every letter with its label.
+    fn letters(&self) -> Vec<AnnotatedLetter> {
+        self.seq
+            .iter()
+            .enumerate()
+            .map(|(i, &letter)| AnnotatedLetter {
+                letter,
+                label: self.label(i),
+            })
+            .collect()
+    }
+}
+
+/// Executes Needleman-Wunsch over annotated sequences, adding the annotation
+/// bonus or penalty to every aligned pair of letters.
+pub fn annotated_needleman_wunsch(
+    row: AnnotatedSeq,
+    column: AnnotatedSeq,
+    config: AnnotatedAlignmentConfig,
+) -> GlobalAlignmentResult {
+    let result = needleman_wunsch(&row.letters(), &column.letters(), config);
+    let unlabel = |seq: Vec<AnnotatedLetter>| {
+        seq.into_iter().map(|letter| letter.letter).collect()
+    };
+    GlobalAlignmentResult {
+        aligned_row_seq: unlabel(result.aligned_row_seq),
+        aligned_column_seq: unlabel(result.aligned_column_seq),
+        score: result.score,
+        identity_numer: result.identity_numer,
+        identity_denom: result.identity_denom,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::needleman_wunsch;
+
+    use super::{
+        annotated_needleman_wunsch,
+        AnnotatedAlignmentConfig,
+        AnnotatedSeq,
+    };
+
+    #[test]
+    fn agreeing_structure_places_the_gap() {
+        let row_seq = ['A', 'L', 'A', 'K'];
+        let column_seq = ['A', 'K'];
+        let config = AnnotatedAlignmentConfig {
+            agreement_bonus: 3,
+            ..AnnotatedAlignmentConfig::default()
+        };
+
+        let plain = annotated_needleman_wunsch(
+            AnnotatedSeq::plain(&row_seq),
+            AnnotatedSeq::plain(&column_seq),
+            config,
+        );
+        assert_eq!(
+            plain,
+            needleman_wunsch(&row_seq, &column_seq, config.alignment)
+        );
+        assert_eq!(plain.score, -2);
+
+        let annotated = annotated_needleman_wunsch(
+            AnnotatedSeq::annotated(&row_seq, &['H', 'C', 'C', 'H']),
+            AnnotatedSeq::annotated(&column_seq, &['H', 'H']),
+            config,
+        );
+        assert_eq!(annotated.aligned_column_seq, vec!['A', '-', '-', 'K']);
+        assert_eq!(annotated.score, 4);
+        assert_eq!(annotated.identity_numer, 2);
+
+        let disagreeing = annotated_needleman_wunsch(
+            AnnotatedSeq::annotated(&column_seq, &['E', 'E']),
+            AnnotatedSeq::annotated(&column_seq, &['H', 'H']),
+            config,
+        );
+        assert_eq!(disagreeing.score, 0);
+        assert_eq!(disagreeing.identity_numer, 2);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::Letter,
+    local::LocalAlignmentResult,
+    matrix::AlignmentMatrix,
+    score::Score,
+};
+
+/// Pool of buffers recycled across alignments.
+///
+/// Matrices and aligned sequences taken from the arena reuse the allocations
+/// of the ones given back to it, so a loop of alignments stops hitting the
+/// global allocator once the arena is warm. Each thread of a server should
+/// own its arena, which avoids contention on the global allocator. This works
+/// on stable Rust; nothing requires the unstable `Allocator` API.
+#[derive(Debug, Clone, Default)]
+pub struct Arena {
+    scores: Vec<Vec<Score>>,
+    letters: Vec<Vec<Letter>>,
+}
+
+impl Arena {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of buffers available for reuse.
+    pub fn available(&self) -> usize {
+        self.scores.len() + self.letters.len()
+    }
+
+    /// Number of cells the largest recycled matrix buffer holds without
+    /// reallocating.
+    pub fn matrix_capacity(&self) -> usize {
+        self.scores.last().map_or(0, Vec::capacity)
+    }
+
+    /// Takes a zeroed matrix of dimensions Height X Width, reusing the largest
+    /// recycled score buffer if any.
+    pub fn matrix(&mut self, height: usize, width: usize) -> AlignmentMatrix {
+        let mut buf = self.scores.pop().unwrap_or_default();
+        buf.clear();
+        buf.resize(height * width, 0);
+        AlignmentMatrix::from_zeroed_buf(buf, width)
+    }
+
+    /// Takes an empty letter buffer with at least the given capacity.
+    pub fn letters(&mut self, capacity: usize) -> Vec<Letter> {
+        let mut buf = self.letters.pop().unwrap_or_default();
+        buf.clear();
+        buf.reserve(capacity);
+        buf
+    }
+
+    /// Gives the buffer of a matrix back to the arena.
+    pub fn recycle_matrix(&mut self, matrix: AlignmentMatrix) {
+        Self::insert_by_capacity(&mut self.scores, matrix.into_buf());
+    }
+
+    /// Gives a letter buffer back to the arena.
+    pub fn recycle_letters(&mut self, letters: Vec<Letter>) {
+        Self::insert_by_capacity(&mut self.letters, letters);
+    }
+
+    /// Gives the aligned sequences of a global result back to the arena.
+    pub fn recycle_global(&mut self, result: GlobalAlignmentResult) {
+        self.recycle_letters(result.aligned_row_seq);
+        self.recycle_letters(result.aligned_column_seq);
+    }
+
+    /// Gives the aligned sequences of a local result back to the arena.
+    pub fn recycle_local(&mut self, result: LocalAlignmentResult) {
+        self.recycle_letters(result.aligned_row_seq.data);
+        self.recycle_letters(result.aligned_column_seq.data);
+    }
+
+    /// Keeps buffers sorted by capacity, so that the largest one is taken
+    /// first.
+    fn insert_by_capacity<T>(pool: &mut Vec<Vec<T>>, buf: Vec<T>) {
+        let index =
+            pool.partition_point(|other| other.capacity() <= buf.capacity());
+        pool.insert(index, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{
+            needleman_wunsch,
+            needleman_wunsch_in,
+            GlobalAlignmentConfig,
+        },
+        local::{best_smith_waterman, smith_waterman_in, LocalAlignmentConfig},
+    };
+
+    use super::Arena;
+
+    #[test]
+    fn recycled_buffers_are_reused() {
+        let config = GlobalAlignmentConfig::default();
+        let row_seq = ['G', 'A', 'T', 'T', 'A', 'C', 'A'];
+        let column_seq = ['G', 'C', 'A', 'T', 'G', 'C', 'A'];
+        let mut arena = Arena::new();
+
+        let first =
+            needleman_wunsch_in(&row_seq, &column_seq, config, &mut arena);
+        assert_eq!(first, needleman_wunsch(&row_seq, &column_seq, config));
+        assert_eq!(arena.available(), 1);
+
+        let row_ptr = first.aligned_row_seq.as_ptr();
+        arena.recycle_global(first);
+        assert_eq!(arena.available(), 3);
+
+        let second =
+            needleman_wunsch_in(&row_seq, &column_seq, config, &mut arena);
+        assert_eq!(arena.available(), 1);
+        assert!(
+            second.aligned_row_seq.as_ptr() == row_ptr
+                || second.aligned_column_seq.as_ptr() == row_ptr
+        );
+
+        let local = LocalAlignmentConfig::default();
+        let capacity = arena.matrix_capacity();
+        assert_eq!(
+            smith_waterman_in(&row_seq, &column_seq, local, &mut arena),
+            best_smith_waterman(&row_seq, &column_seq, local)
+        );
+        assert_eq!(arena.matrix_capacity(), capacity);
+    }
+}
@@ -0,0 +1,609 @@
+use crate::{
+    global::{GlobalAlignmentConfig, GlobalAlignmentResult},
+    index::KmerIndex,
+    letter::{Letter, GAP},
+    score::Score,
+};
+
+/// Range of diagonals `j - i` (column index minus row index) whose cells are
+/// computed, both ends included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Band {
+    /// Lowest diagonal, below the main one when negative.
+    pub lo: isize,
+    /// Highest diagonal, above the main one when positive.
+    pub hi: isize,
+}
+
+impl Band {
+    /// The narrowest band around the given diagonals that still contains both
+    /// corners of the matrix of sequences with the given lengths.
+    pub fn covering(
+        lo: isize,
+        hi: isize,
+        row_len: usize,
+        column_len: usize,
+    ) -> Self {
+        let end_diagonal = column_len as isize - row_len as isize;
+        Self {
+            lo: lo.min(0).min(end_diagonal).max(-(row_len as isize)),
+            hi: hi.max(0).max(end_diagonal).min(column_len as isize),
+        }
+    }
+
+    /// The band of all cells, as in the unbanded algorithm.
+    pub fn full(row_len: usize, column_len: usize) -> Self {
+        Self { lo: -(row_len as isize), hi: column_len as isize }
+    }
+
+    /// Number of diagonals in the band.
+    pub fn width(&self) -> usize {
+        (self.hi - self.lo + 1).max(0) as usize
+    }
+}
+
+/// Parameters of the band estimation from shared k-mers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandEstimateConfig {
+    /// Length of the k-mers shared by both sequences.
+    pub k: usize,
+    /// Diagonals added on both sides of the range of shared k-mers.
+    pub padding: usize,
+    /// Fraction, in percent, of the most extreme k-mer diagonals on each side
+    /// ignored as spurious hits.
+    pub outlier_percent: usize,
+}
+
+impl Default for BandEstimateConfig {
+    fn default() -> Self {
+        Self { k: 8, padding: 8, outlier_percent: 5 }
+    }
+}
+
+/// Chooses a band from the diagonals of the k-mers both sequences share.
+///
+/// The band spans the shared k-mer diagonals (without outliers), padded on
+/// both sides and widened to contain both matrix corners. Without shared
+/// k-mers, the full matrix is used.
+pub fn estimate_band(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: BandEstimateConfig,
+) -> Band {
+    let index = KmerIndex::new(row_seq, config.k);
+    let mut diagonals: Vec<isize> = index
+        .lookup(column_seq, usize::MAX)
+        .iter()
+        .map(|hit| -hit.diagonal())
+        .collect();
+    if diagonals.is_empty() {
+        return Band::full(row_seq.len(), column_seq.len());
+    }
+
+    diagonals.sort_unstable();
+    let skipped = diagonals.len() * config.outlier_percent.min(49) / 100;
+    let lo = diagonals[skipped] - config.padding as isize;
+    let hi = diagonals[diagonals.len() - 1 - skipped] + config.padding as isize;
+    Band::covering(lo, hi, row_seq.len(), column_seq.len())
+}
+
+/// Score of cells outside the band, low enough to never be chosen, yet far
+/// from overflowing when penalties are added.
+const OUTSIDE: Score = Score::MIN / 4;
+
+/// Needleman-Wunsch scores restricted to a band, stored row by row.
+pub(crate) struct BandedMatrix {
+    band: Band,
+    /// Diagonals each row of an adaptive band is shifted by, empty for fixed
+    /// bands.
+    row_shifts: Vec<isize>,
+    column_len: usize,
+    buf: Vec<Score>,
+}
+
+impl BandedMatrix {
+    fn new(band: Band, row_len: usize, column_len: usize) -> Self {
+        Self {
+            band,
+            row_shifts: Vec::new(),
+            column_len,
+            buf: vec![OUTSIDE; (row_len + 1) * band.width()],
+        }
+    }
+
+    /// Lowest diagonal of the band in the given row.
+    fn row_lo(&self, i: usize) -> isize {
+        self.band.lo + self.row_shifts.get(i).copied().unwrap_or(0)
+    }
+
+    /// Bytes of the score buffer.
+    pub(crate) fn memory_bytes(&self) -> usize {
+        self.buf.len() * std::mem::size_of::<Score>()
+    }
+
+    fn slot(&self, i: usize, j: usize) -> Option<usize> {
+        let offset = j as isize - i as isize - self.row_lo(i);
+        if j > self.column_len
+            || offset < 0
+            || offset > self.band.hi - self.band.lo
+        {
+            None
+        } else {
+            Some(i * self.band.width() + offset as usize)
+        }
+    }
+
+    fn get(&self, i: usize, j: usize) -> Score {
+        self.slot(i, j).map_or(OUTSIDE, |slot| self.buf[slot])
+    }
+
+    fn set(&mut self, i: usize, j: usize, score: Score) {
+        if let Some(slot) = self.slot(i, j) {
+            self.buf[slot] = score;
+        }
+    }
+}
+
+/// Executes Needleman-Wunsch computing only the cells inside the band.
+///
+/// The result is the best alignment staying inside the band, which is the
+/// global optimum whenever the optimal path fits in the band. The band is
+/// widened to contain both matrix corners.
+pub fn banded_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    band: Band,
+) -> GlobalAlignmentResult {
+    let (matrix, _) = fill_banded_matrix(row_seq, column_seq, config, band);
+    traceback_banded(row_seq, column_seq, config, &matrix)
+}
+
+/// Fills the cells of a banded Needleman-Wunsch matrix, widening the band to
+/// contain both matrix corners. Returns the matrix and the number of cells
+/// computed.
+pub(crate) fn fill_banded_matrix(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    band: Band,
+) -> (BandedMatrix, u64) {
+    let band =
+        Band::covering(band.lo, band.hi, row_seq.len(), column_seq.len());
+    let mut matrix = BandedMatrix::new(band, row_seq.len(), column_seq.len());
+    let mut cells = 0;
+    for i in 0 ..= row_seq.len() {
+        let first_j = (i as isize + band.lo).max(0) as usize;
+        let last_j = (i as isize + band.hi).min(column_seq.len() as isize);
+        for j in first_j ..= last_j.max(0) as usize {
+            let score = if i == 0 {
+                (j as Score) * config.gap_penalty
+            } else if j == 0 {
+                (i as Score) * config.gap_penalty
+            } else {
+                let pair = if row_seq[i - 1] == column_seq[j - 1] {
+                    config.match_penalty
+                } else {
+                    config.mismatch_penalty
+                };
+                let no_gap_score = matrix.get(i - 1, j - 1) + pair;
+                let best_gap_score =
+                    matrix.get(i - 1, j).max(matrix.get(i, j - 1))
+                        + config.gap_penalty;
+                no_gap_score.max(best_gap_score)
+            };
+            matrix.set(i, j, score);
+            cells += 1;
+        }
+    }
+    (matrix, cells)
+}
+
+/// Executes the banded Needleman-Wunsch like [`banded_needleman_wunsch`],
+/// filling each row of the band with vector-friendly passes instead of cell by
+/// cell. Results are the same, including ties.
+///
+/// A row is first filled from the row above only (diagonal and top moves),
+/// a branch-free pass over contiguous slices that the compiler turns into
+/// SIMD instructions. Left moves are then resolved by a short sequential scan,
+/// which is a single comparison per cell.
+pub fn vectorized_banded_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    band: Band,
+) -> GlobalAlignmentResult {
+    let matrix =
+        fill_banded_matrix_vectorized(row_seq, column_seq, config, band);
+    traceback_banded(row_seq, column_seq, config, &matrix)
+}
+
+/// Fills a banded Needleman-Wunsch matrix row by row, as described in
+/// [`vectorized_banded_needleman_wunsch`].
+fn fill_banded_matrix_vectorized(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    band: Band,
+) -> BandedMatrix {
+    let band =
+        Band::covering(band.lo, band.hi, row_seq.len(), column_seq.len());
+    let mut matrix = BandedMatrix::new(band, row_seq.len(), column_seq.len());
+    let width = band.width();
+    let gap = config.gap_penalty;
+
+    let last_j = band.hi.min(column_seq.len() as isize) as usize;
+    for j in 0 ..= last_j {
+        matrix.set(0, j, j as Score * gap);
+    }
+
+    for i in 1 ..= row_seq.len() {
+        let (above, rest) = matrix.buf.split_at_mut(i * width);
+        let above = &above[(i - 1) * width ..];
+        let current = &mut rest[.. width];
+        let first_j = (i as isize + band.lo).max(0) as usize;
+        let last_j = (i as isize + band.hi).min(column_seq.len() as isize);
+        // offset of column j in the band of row i
+        let offset = |j: usize| (j as isize - i as isize - band.lo) as usize;
+
+        if first_j == 0 {
+            current[offset(0)] = i as Score * gap;
+        }
+        let first_inner = first_j.max(1);
+        if last_j < first_inner as isize {
+            continue;
+        }
+        let last_j = last_j as usize;
+        let start = offset(first_inner);
+        let end = offset(last_j) + 1;
+        let row_letter = row_seq[i - 1];
+        let columns = &column_seq[first_inner - 1 .. last_j];
+
+        // the cell above the last one of the band is outside of it
+        let (inner_end, top_end) =
+            if end == width { (end - 1, true) } else { (end, false) };
+        for (((cell, &diagonal), &top), &column_letter) in current
+            [start .. inner_end]
+            .iter_mut()
+            .zip(&above[start .. inner_end])
+            .zip(&above[start + 1 .. inner_end + 1])
+            .zip(columns)
+        {
+            let pair = if row_letter == column_letter {
+                config.match_penalty
+            } else {
+                config.mismatch_penalty
+            };
+            *cell = (diagonal + pair).max(top + gap);
+        }
+        if top_end {
+            let pair = if row_letter == columns[columns.len() - 1] {
+                config.match_penalty
+            } else {
+                config.mismatch_penalty
+            };
+            current[end - 1] = above[end - 1] + pair;
+        }
+
+        let scan_start = if first_j == 0 { start } else { start + 1 };
+        for k in scan_start .. end {
+            current[k] = current[k].max(current[k - 1] + gap);
+        }
+    }
+    matrix
+}
+
+/// Traces the best alignment back through a filled banded matrix.
+pub(crate) fn traceback_banded(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    matrix: &BandedMatrix,
+) -> GlobalAlignmentResult {
+    let mut current_i = row_seq.len();
+    let mut current_j = column_seq.len();
+    let capacity = current_i + current_j;
+    let mut result = GlobalAlignmentResult {
+        aligned_row_seq: Vec::with_capacity(capacity),
+        aligned_column_seq: Vec::with_capacity(capacity),
+        score: matrix.get(current_i, current_j),
+        identity_numer: 0,
+        identity_denom: 0,
+    };
+    while current_i > 0 || current_j > 0 {
+        let current_score = matrix.get(current_i, current_j);
+        if current_i > 0
+            && current_score
+                == matrix.get(current_i - 1, current_j) + config.gap_penalty
+        {
+            current_i -= 1;
+            result.aligned_row_seq.push(row_seq[current_i]);
+            result.aligned_column_seq.push(GAP);
+        } else if current_j > 0
+            && current_score
+                == matrix.get(current_i, current_j - 1) + config.gap_penalty
+        {
+            current_j -= 1;
+            result.aligned_row_seq.push(GAP);
+            result.aligned_column_seq.push(column_seq[current_j]);
+        } else {
+            current_i -= 1;
+            current_j -= 1;
+            let row_letter = row_seq[current_i];
+            let column_letter = column_seq[current_j];
+            result.aligned_row_seq.push(row_letter);
+            result.aligned_column_seq.push(column_letter);
+            result.identity_denom += 1;
+            if row_letter == column_letter {
+                result.identity_numer += 1;
+            }
+        }
+    }
+    result.aligned_row_seq.reverse();
+    result.aligned_column_seq.reverse();
+    result.identity_denom = result.identity_denom.max(1);
+    result
+}
+
+/// Alignment found by [`adaptive_banded_needleman_wunsch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveBandResult {
+    /// Best alignment staying inside the adaptive band.
+    pub result: GlobalAlignmentResult,
+    /// Whether the alignment runs along an edge of the band, in which case a
+    /// better alignment may lie outside of it. When `false`, the alignment is
+    /// very likely, though not provably, the global optimum.
+    pub may_have_escaped: bool,
+}
+
+/// Executes Needleman-Wunsch in a band of `width` columns per row that
+/// follows the alignment: each row is centered below the best cell of the
+/// row above, instead of around fixed diagonals.
+///
+/// This handles long, mostly similar sequences whose indels drift the path
+/// far from the main diagonal, at the cost of `width` cells per row, but the
+/// optimum may be missed. The band moves at most half its width per row, so
+/// consecutive rows always overlap, and is pulled towards the last matrix
+/// cell early enough to always reach it. When the lengths differ too much for
+/// that, the band is widened until the last cell is reachable.
+pub fn adaptive_banded_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    width: usize,
+) -> AdaptiveBandResult {
+    let matrix = fill_adaptive_matrix(row_seq, column_seq, config, width);
+    let result = traceback_banded(row_seq, column_seq, config, &matrix);
+
+    let width = matrix.band.width() as isize;
+    let column_len = column_seq.len() as isize;
+    let (mut i, mut j) = (0, 0);
+    let mut may_have_escaped = false;
+    for (&row_letter, &column_letter) in
+        result.aligned_row_seq.iter().zip(&result.aligned_column_seq)
+    {
+        i += usize::from(row_letter != GAP);
+        j += isize::from(column_letter != GAP);
+        let first_j = i as isize + matrix.row_lo(i);
+        let last_j = first_j + width - 1;
+        if (j == first_j && first_j > 0) || (j == last_j && last_j < column_len)
+        {
+            may_have_escaped = true;
+        }
+    }
+    AdaptiveBandResult { result, may_have_escaped }
+}
+
+/// Fills an adaptively banded Needleman-Wunsch matrix, as described in
+/// [`adaptive_banded_needleman_wunsch`].
+fn fill_adaptive_matrix(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    width: usize,
+) -> BandedMatrix {
+    let mut width = width.clamp(1, column_seq.len() + 1);
+    // widened until moving at most half its width per row, the band reaches
+    // the last column by the last row
+    while row_seq.len() * (width / 2).max(1) + width < column_seq.len() + 1 {
+        width += 1;
+    }
+    let band = Band { lo: 0, hi: width as isize - 1 };
+    let mut matrix = BandedMatrix::new(band, row_seq.len(), column_seq.len());
+    matrix.row_shifts = vec![0; row_seq.len() + 1];
+    for j in 0 .. width {
+        matrix.set(0, j, j as Score * config.gap_penalty);
+    }
+
+    // the band may move this many columns per row and still overlap
+    let step = (width / 2).max(1) as isize;
+    let last_start = (column_seq.len() + 1 - width) as isize;
+    let mut start = 0;
+    for i in 1 ..= row_seq.len() {
+        let best_j = (start .. start + width)
+            .max_by_key(|&j| (matrix.get(i - 1, j), std::cmp::Reverse(j)))
+            .unwrap_or(start);
+        let centered = (best_j + 1) as isize - (width / 2) as isize;
+        let pulled = last_start - (row_seq.len() - i) as isize * step;
+        // the widening above keeps the pulled start within one step
+        start = centered
+            .max(pulled)
+            .min(start as isize + step)
+            .clamp(0, last_start) as usize;
+        matrix.row_shifts[i] = start as isize - i as isize;
+
+        let row_letter = row_seq[i - 1];
+        for j in start .. start + width {
+            let score = if j == 0 {
+                i as Score * config.gap_penalty
+            } else {
+                let pair = if row_letter == column_seq[j - 1] {
+                    config.match_penalty
+                } else {
+                    config.mismatch_penalty
+                };
+                let no_gap_score = matrix.get(i - 1, j - 1) + pair;
+                let best_gap_score =
+                    matrix.get(i - 1, j).max(matrix.get(i, j - 1))
+                        + config.gap_penalty;
+                no_gap_score.max(best_gap_score)
+            };
+            matrix.set(i, j, score);
+        }
+    }
+    matrix
+}
+
+/// Estimates a band from shared k-mers, then runs the banded Needleman-Wunsch
+/// in it.
+pub fn auto_banded_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    estimate: BandEstimateConfig,
+) -> GlobalAlignmentResult {
+    let band = estimate_band(row_seq, column_seq, estimate);
+    banded_needleman_wunsch(row_seq, column_seq, config, band)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng, DNA},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        mutate::{mutate, MutationRates},
+        testing::{rescore, ungapped},
+    };
+
+    use super::{
+        adaptive_banded_needleman_wunsch,
+        auto_banded_needleman_wunsch,
+        banded_needleman_wunsch,
+        estimate_band,
+        vectorized_banded_needleman_wunsch,
+        Band,
+        BandEstimateConfig,
+    };
+
+    #[test]
+    fn estimated_band_keeps_the_optimum() {
+        let mut rng = Rng::new(21);
+        let row_seq = random_dna(400, &mut rng);
+        let rates = MutationRates {
+            substitution: 0.03,
+            insertion: 0.01,
+            deletion: 0.01,
+        };
+        let column_seq = mutate(&row_seq, rates, DNA, &mut rng).seq;
+        let config = GlobalAlignmentConfig::default();
+        let estimate = BandEstimateConfig::default();
+
+        let band = estimate_band(&row_seq, &column_seq, estimate);
+        assert!(band.width() < 100, "band {band:?} too wide");
+        assert_eq!(
+            auto_banded_needleman_wunsch(
+                &row_seq,
+                &column_seq,
+                config,
+                estimate
+            ),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+    }
+
+    #[test]
+    fn vectorized_fill_matches_scalar_fill() {
+        let mut rng = Rng::new(102);
+        let config = GlobalAlignmentConfig::default();
+        for (row_len, column_len) in [(120, 130), (90, 60), (1, 7), (0, 3)] {
+            let row_seq = random_dna(row_len, &mut rng);
+            let column_seq = random_dna(column_len, &mut rng);
+            for band in [
+                Band { lo: -4, hi: 4 },
+                Band { lo: 0, hi: 0 },
+                Band { lo: -30, hi: 2 },
+                Band::full(row_len, column_len),
+            ] {
+                assert_eq!(
+                    vectorized_banded_needleman_wunsch(
+                        &row_seq,
+                        &column_seq,
+                        config,
+                        band
+                    ),
+                    banded_needleman_wunsch(
+                        &row_seq,
+                        &column_seq,
+                        config,
+                        band
+                    ),
+                    "lengths {row_len}x{column_len}, band {band:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_band_follows_drifting_path() {
+        let mut rng = Rng::new(131);
+        let config = GlobalAlignmentConfig::default();
+        let row_seq = random_dna(1000, &mut rng);
+        // deletions only, so the path drifts far below the main diagonal
+        let rates = MutationRates {
+            substitution: 0.02,
+            insertion: 0.0,
+            deletion: 0.05,
+        };
+        let column_seq = mutate(&row_seq, rates, DNA, &mut rng).seq;
+        assert!(row_seq.len() - column_seq.len() > 30);
+
+        let adaptive =
+            adaptive_banded_needleman_wunsch(&row_seq, &column_seq, config, 16);
+        assert!(!adaptive.may_have_escaped);
+        assert_eq!(
+            adaptive.result.score,
+            needleman_wunsch(&row_seq, &column_seq, config).score
+        );
+
+        // a long insertion does not fit in a single row of the band
+        let inserted = random_dna(40, &mut rng);
+        let column_seq =
+            [&row_seq[.. 500], &inserted, &row_seq[500 ..]].concat();
+        let escaped =
+            adaptive_banded_needleman_wunsch(&row_seq, &column_seq, config, 16);
+        assert!(escaped.may_have_escaped);
+        assert!(
+            escaped.result.score
+                < needleman_wunsch(&row_seq, &column_seq, config).score
+        );
+    }
+
+    #[test]
+    fn adaptive_band_reaches_the_corner_of_unequal_lengths() {
+        let config = GlobalAlignmentConfig::default();
+        let short: Vec<char> = "ACG".chars().collect();
+        let long: Vec<char> =
+            ["T".repeat(40), "ACG".to_owned()].concat().chars().collect();
+        for (row_seq, column_seq) in [(&short, &long), (&long, &short)] {
+            let result = adaptive_banded_needleman_wunsch(
+                row_seq, column_seq, config, 4,
+            )
+            .result;
+            assert_eq!(ungapped(&result.aligned_row_seq), *row_seq);
+            assert_eq!(ungapped(&result.aligned_column_seq), *column_seq);
+            assert_eq!(
+                rescore(
+                    &result.aligned_row_seq,
+                    &result.aligned_column_seq,
+                    config.match_penalty,
+                    config.mismatch_penalty,
+                    config.gap_penalty,
+                ),
+                result.score
+            );
+        }
+        let empty =
+            adaptive_banded_needleman_wunsch(&[], &long, config, 4).result;
+        assert_eq!(empty.score, -86);
+    }
+}
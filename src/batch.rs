@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use crate::letter::Letter;
+
+/// Aligns every pair of distinct sequences with `align`, called with the
+/// `i`-th sequence as the row and the `j`-th as the column, for `i < j`.
+/// Results are keyed by `(i, j)`.
+///
+/// With the `parallel` feature, pairs are spread over one thread per
+/// available core.
+pub fn all_vs_all<F, R>(
+    seqs: &[&[Letter]],
+    align: F,
+) -> BTreeMap<(usize, usize), R>
+where
+    F: Fn(&[Letter], &[Letter]) -> R + Sync,
+    R: Send,
+{
+    let pairs: Vec<(usize, usize)> = (0 .. seqs.len())
+        .flat_map(|i| (i + 1 .. seqs.len()).map(move |j| (i, j)))
+        .collect();
+    let results = map_items(&pairs, |&(i, j)| align(seqs[i], seqs[j]));
+    pairs.into_iter().zip(results).collect()
+}
+
+/// Aligns a query, as the row, against every target, as the column. Results
+/// are in the order of the targets.
+///
+/// With the `parallel` feature, targets are spread over one thread per
+/// available core.
+pub fn one_vs_all<F, R>(
+    query: &[Letter],
+    targets: &[&[Letter]],
+    align: F,
+) -> Vec<R>
+where
+    F: Fn(&[Letter], &[Letter]) -> R + Sync,
+    R: Send,
+{
+    map_items(targets, |target| align(query, target))
+}
+
+/// Maps every item in order, sequentially.
+#[cfg(not(feature = "parallel"))]
+fn map_items<T, F, R>(items: &[T], map: F) -> Vec<R>
+where
+    T: Sync,
+    F: Fn(&T) -> R + Sync,
+    R: Send,
+{
+    items.iter().map(map).collect()
+}
+
+/// Maps every item in order, splitting the items into one contiguous chunk
+/// per available core, each mapped by a scoped thread.
+#[cfg(feature = "parallel")]
+fn map_items<T, F, R>(items: &[T], map: F) -> Vec<R>
+where
+    T: Sync,
+    F: Fn(&T) -> R + Sync,
+    R: Send,
+{
+    let workers = thread::available_parallelism().map_or(1, usize::from);
+    let chunk_len = items.len().div_ceil(workers).max(1);
+    let map = &map;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_len)
+            .map(|chunk| {
+                scope.spawn(move || chunk.iter().map(map).collect::<Vec<R>>())
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(payload) => std::panic::resume_unwind(payload),
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+    };
+
+    use super::{all_vs_all, one_vs_all};
+
+    #[test]
+    fn every_pair_is_aligned_once() {
+        let mut rng = Rng::new(97);
+        let seqs: Vec<Vec<char>> =
+            (0 .. 6).map(|_| random_dna(30, &mut rng)).collect();
+        let seqs: Vec<&[char]> = seqs.iter().map(Vec::as_slice).collect();
+        let config = GlobalAlignmentConfig::default();
+
+        let results = all_vs_all(&seqs, |row_seq, column_seq| {
+            needleman_wunsch(row_seq, column_seq, config)
+        });
+        assert_eq!(results.len(), 15);
+        assert_eq!(
+            results[&(1, 4)],
+            needleman_wunsch(seqs[1], seqs[4], config)
+        );
+        assert!(!results.contains_key(&(4, 1)));
+
+        let scores = one_vs_all(seqs[0], &seqs[1 ..], |row_seq, column_seq| {
+            needleman_wunsch(row_seq, column_seq, config).score
+        });
+        let expected: Vec<_> =
+            (1 .. 6).map(|j| results[&(0, j)].score).collect();
+        assert_eq!(scores, expected);
+    }
+}
@@ -0,0 +1,24 @@
+use std::{env, io, process};
+
+use seq_align::daemon;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.as_slice() {
+        [] => daemon::serve(io::stdin().lock(), &mut io::stdout().lock()),
+        #[cfg(unix)]
+        [flag, path] if flag == "--socket" => daemon::serve_unix_socket(path),
+        #[cfg(feature = "http")]
+        [flag, address] if flag == "--http" => {
+            seq_align::http::serve_http(address.as_str())
+        },
+        _ => {
+            eprintln!("usage: daemon [--socket PATH | --http ADDRESS]");
+            process::exit(2);
+        },
+    };
+    if let Err(error) = result {
+        eprintln!("daemon: {error}");
+        process::exit(1);
+    }
+}
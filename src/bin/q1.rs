@@ -1,6 +1,8 @@
 use seq_align::{
-    global::{needleman_wunsch, GlobalAlignmentConfig, PrettyPrint},
+    aligner::Aligner,
+    global::{GlobalAlignmentConfig, PrettyPrint},
     letter::Letter,
+    report::PrettyStyle,
 };
 
 fn main() {
@@ -17,19 +19,27 @@ fn main() {
     let human_name = "Homo Sapiens";
     let human_sequence = HOMO_SAPIENS;
 
+    let mut aligner = Aligner::new();
+    let mut distances = Vec::with_capacity(candidates.len());
     for (candidate_name, candidate_sequence) in candidates {
         let result =
-            needleman_wunsch(human_sequence, candidate_sequence, CONFIG);
+            aligner.align_global(human_sequence, candidate_sequence, CONFIG);
+        distances.push((candidate_name, 1.0 - result.identity()));
         println!(
             "{}",
             PrettyPrint {
                 row_seq_name: human_name,
                 column_seq_name: candidate_name,
                 max_width: 80,
+                style: PrettyStyle::default(),
                 result: &result,
             }
         );
     }
+
+    for (candidate_name, distance) in distances {
+        println!("distance({human_name}, {candidate_name}) = {distance:.4}");
+    }
 }
 
 const CONFIG: GlobalAlignmentConfig = GlobalAlignmentConfig {
@@ -1,6 +1,7 @@
 use seq_align::{
     global::{needleman_wunsch, GlobalAlignmentConfig, PrettyPrint},
     letter::Letter,
+    report::PrettyStyle,
 };
 
 fn main() {
@@ -11,6 +12,7 @@ fn main() {
             row_seq_name: "<row sequence>",
             column_seq_name: "<column sequence>",
             max_width: 80,
+            style: PrettyStyle::default(),
             result: &result,
         }
     );
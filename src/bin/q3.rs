@@ -1,6 +1,12 @@
 use seq_align::{
     letter::Letter,
-    local::{best_smith_waterman, LocalAlignmentConfig, PrettyPrintMany},
+    local::{
+        best_smith_waterman,
+        LocalAlignmentConfig,
+        PrettyPrintMany,
+        ResultOrder,
+    },
+    report::PrettyStyle,
 };
 
 fn main() {
@@ -11,6 +17,10 @@ fn main() {
             row_seq_name: "<row sequence>",
             column_seq_name: "<column sequence>",
             max_width: 80,
+            style: PrettyStyle::default(),
+            order: ResultOrder::Score,
+            dedup: true,
+            limit: None,
             results: &results,
         }
     );
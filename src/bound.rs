@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    global::GlobalAlignmentConfig,
+    letter::Letter,
+    local::LocalAlignmentConfig,
+    score::Score,
+};
+
+/// Counts the maximum number of matches any alignment of both sequences can
+/// have: for each letter, the smallest of its occurrence counts.
+pub fn max_matches(row_seq: &[Letter], column_seq: &[Letter]) -> usize {
+    let mut composition = BTreeMap::new();
+    for &letter in row_seq {
+        *composition.entry(letter).or_insert(0usize) += 1;
+    }
+    let mut matches = 0;
+    for letter in column_seq {
+        if let Some(count) = composition.get_mut(letter) {
+            if *count > 0 {
+                *count -= 1;
+                matches += 1;
+            }
+        }
+    }
+    matches
+}
+
+/// Upper bound of the score of the best local alignment, computed without
+/// filling any matrix.
+///
+/// When mismatches and gaps are penalties (not bonuses), no alignment can score
+/// more than the maximum number of matches times the match score.
+pub fn local_score_upper_bound(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: LocalAlignmentConfig,
+) -> Score {
+    let bound = if config.mismatch_penalty <= 0 && config.gap_penalty <= 0 {
+        max_matches(row_seq, column_seq) as Score * config.match_penalty
+    } else {
+        fallback_upper_bound(
+            row_seq,
+            column_seq,
+            config.match_penalty,
+            config.mismatch_penalty,
+            config.gap_penalty,
+        )
+    };
+    bound.max(0)
+}
+
+/// Upper bound of the score of the global alignment, computed without filling
+/// any matrix.
+///
+/// Besides the maximum number of matches, a global alignment needs at least as
+/// many gaps as the difference of lengths.
+pub fn global_score_upper_bound(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+) -> Score {
+    if config.mismatch_penalty <= 0 && config.gap_penalty <= 0 {
+        let matches = max_matches(row_seq, column_seq) as Score;
+        let length_diff = row_seq.len().abs_diff(column_seq.len()) as Score;
+        matches * config.match_penalty.max(0) + length_diff * config.gap_penalty
+    } else {
+        fallback_upper_bound(
+            row_seq,
+            column_seq,
+            config.match_penalty,
+            config.mismatch_penalty,
+            config.gap_penalty,
+        )
+    }
+}
+
+/// Bound valid for any scoring: an alignment has at most one column per input
+/// letter, and no column scores more than the best of all penalties.
+fn fallback_upper_bound(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    match_penalty: Score,
+    mismatch_penalty: Score,
+    gap_penalty: Score,
+) -> Score {
+    let best_column = match_penalty.max(mismatch_penalty).max(gap_penalty);
+    (row_seq.len() + column_seq.len()) as Score * best_column.max(0)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{global_score_upper_bound, local_score_upper_bound};
+
+    #[test]
+    fn bounds_hold_for_real_alignments() {
+        let row_seq: Vec<char> = "GGTTGACTA".chars().collect();
+        let column_seq: Vec<char> = "TGTTACGG".chars().collect();
+        let local_config = LocalAlignmentConfig {
+            match_penalty: 3,
+            mismatch_penalty: -3,
+            gap_penalty: -2,
+        };
+        let global_config = GlobalAlignmentConfig::default();
+
+        let local_bound =
+            local_score_upper_bound(&row_seq, &column_seq, local_config);
+        let global_bound =
+            global_score_upper_bound(&row_seq, &column_seq, global_config);
+
+        assert_eq!(local_bound, 24);
+        assert!(
+            best_smith_waterman(&row_seq, &column_seq, local_config)[0].score
+                <= local_bound
+        );
+        assert_eq!(global_bound, 6);
+        assert!(
+            needleman_wunsch(&row_seq, &column_seq, global_config).score
+                <= global_bound
+        );
+    }
+}
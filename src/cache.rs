@@ -0,0 +1,192 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentConfig, GlobalAlignmentResult},
+    letter::Letter,
+    local::{best_smith_waterman, LocalAlignmentConfig, LocalAlignmentResult},
+};
+
+/// Key of a cached alignment: hashes of both sequences and the scheme.
+type CacheKey<C> = (u64, u64, C);
+
+/// A cached result, with the sequences it was computed for, so that hash
+/// collisions are detected instead of returning a wrong result.
+#[derive(Debug, Clone)]
+struct CacheEntry<R> {
+    row_seq: Vec<Letter>,
+    column_seq: Vec<Letter>,
+    result: R,
+}
+
+/// Hashes a sequence.
+fn seq_hash(seq: &[Letter]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seq.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Looks a pair up in a cache table, computing and storing its result on a
+/// miss. A colliding entry is replaced.
+fn get_or_compute<C, R, F>(
+    table: &mut HashMap<CacheKey<C>, CacheEntry<R>>,
+    stats: &mut CacheStats,
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: C,
+    compute: F,
+) -> R
+where
+    C: Hash + Eq,
+    R: Clone,
+    F: FnOnce() -> R,
+{
+    let key = (seq_hash(row_seq), seq_hash(column_seq), config);
+    if let Some(entry) = table.get(&key) {
+        if entry.row_seq == row_seq && entry.column_seq == column_seq {
+            stats.hits += 1;
+            return entry.result.clone();
+        }
+    }
+    stats.misses += 1;
+    let result = compute();
+    table.insert(
+        key,
+        CacheEntry {
+            row_seq: row_seq.to_vec(),
+            column_seq: column_seq.to_vec(),
+            result: result.clone(),
+        },
+    );
+    result
+}
+
+/// Counters of cache lookups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that had to compute the alignment.
+    pub misses: u64,
+}
+
+/// Memoizes alignments, for workloads where identical pairs are aligned many
+/// times, e.g. deduplicated reads or repeated queries.
+///
+/// Results are keyed by the hashes of both sequences and the scheme. The
+/// cache grows without bound until [`AlignmentCache::clear`] is called.
+#[derive(Debug, Clone, Default)]
+pub struct AlignmentCache {
+    global: HashMap<
+        CacheKey<GlobalAlignmentConfig>,
+        CacheEntry<GlobalAlignmentResult>,
+    >,
+    local: HashMap<
+        CacheKey<LocalAlignmentConfig>,
+        CacheEntry<Vec<LocalAlignmentResult>>,
+    >,
+    stats: CacheStats,
+}
+
+impl AlignmentCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Executes Needleman-Wunsch like [`needleman_wunsch`], unless the same
+    /// pair was already aligned with the same scheme.
+    pub fn needleman_wunsch(
+        &mut self,
+        row_seq: &[Letter],
+        column_seq: &[Letter],
+        config: GlobalAlignmentConfig,
+    ) -> GlobalAlignmentResult {
+        get_or_compute(
+            &mut self.global,
+            &mut self.stats,
+            row_seq,
+            column_seq,
+            config,
+            || needleman_wunsch(row_seq, column_seq, config),
+        )
+    }
+
+    /// Computes the best Smith-Waterman alignments like
+    /// [`best_smith_waterman`], unless the same pair was already aligned with
+    /// the same scheme.
+    pub fn best_smith_waterman(
+        &mut self,
+        row_seq: &[Letter],
+        column_seq: &[Letter],
+        config: LocalAlignmentConfig,
+    ) -> Vec<LocalAlignmentResult> {
+        get_or_compute(
+            &mut self.local,
+            &mut self.stats,
+            row_seq,
+            column_seq,
+            config,
+            || best_smith_waterman(row_seq, column_seq, config),
+        )
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.global.len() + self.local.len()
+    }
+
+    /// Tests whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counters of lookups since creation.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Drops every cached result, keeping the counters.
+    pub fn clear(&mut self) {
+        self.global.clear();
+        self.local.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::LocalAlignmentConfig,
+    };
+
+    use super::{AlignmentCache, CacheStats};
+
+    #[test]
+    fn repeated_pairs_are_served_from_cache() {
+        let row_seq = ['G', 'A', 'T', 'T', 'A', 'C', 'A'];
+        let column_seq = ['G', 'A', 'T', 'C', 'A'];
+        let config = GlobalAlignmentConfig::default();
+        let other_config = GlobalAlignmentConfig { gap_penalty: -1, ..config };
+        let mut cache = AlignmentCache::new();
+
+        let first = cache.needleman_wunsch(&row_seq, &column_seq, config);
+        let second = cache.needleman_wunsch(&row_seq, &column_seq, config);
+        assert_eq!(first, needleman_wunsch(&row_seq, &column_seq, config));
+        assert_eq!(first, second);
+        cache.needleman_wunsch(&row_seq, &column_seq, other_config);
+        cache.needleman_wunsch(&column_seq, &row_seq, config);
+        cache.best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+        );
+
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 4 });
+        assert_eq!(cache.len(), 4);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use crate::letter::{Letter, GAP};
+
+/// Operation of a CIGAR string, taking the sequence associated with the row
+/// display as the reference and the one associated with the column display as
+/// the query, as in SAM files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CigarOp {
+    /// `M`: both letters aligned, whether equal or not.
+    Match,
+    /// `=`: both letters aligned and equal.
+    Equal,
+    /// `X`: both letters aligned but different.
+    Mismatch,
+    /// `I`: query letter aligned with a gap in the reference.
+    Insertion,
+    /// `D`: reference letter aligned with a gap in the query.
+    Deletion,
+}
+
+impl CigarOp {
+    /// Symbol of this operation in a CIGAR string.
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Match => 'M',
+            Self::Equal => '=',
+            Self::Mismatch => 'X',
+            Self::Insertion => 'I',
+            Self::Deletion => 'D',
+        }
+    }
+}
+
+/// Run-length encoded alignment, displayed as a CIGAR string, e.g. `3M1I4M`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Cigar {
+    ops: Vec<(usize, CigarOp)>,
+}
+
+impl Cigar {
+    /// Encodes the columns of two aligned sequences. If `extended` is set,
+    /// aligned letters are told apart with `=` and `X`, otherwise they are
+    /// all `M`. Columns with two gaps are skipped.
+    pub fn from_aligned(
+        aligned_row: &[Letter],
+        aligned_col: &[Letter],
+        extended: bool,
+    ) -> Self {
+        let mut cigar = Self::default();
+        for (&row_letter, &col_letter) in aligned_row.iter().zip(aligned_col) {
+            let op = match (row_letter == GAP, col_letter == GAP) {
+                (true, true) => continue,
+                (true, false) => CigarOp::Insertion,
+                (false, true) => CigarOp::Deletion,
+                (false, false) if !extended => CigarOp::Match,
+                (false, false) if row_letter == col_letter => CigarOp::Equal,
+                (false, false) => CigarOp::Mismatch,
+            };
+            cigar.push(op, 1);
+        }
+        cigar
+    }
+
+    /// Appends `len` repetitions of an operation, merging it with the last
+    /// run when they are equal.
+    pub fn push(&mut self, op: CigarOp, len: usize) {
+        if len == 0 {
+            return;
+        }
+        match self.ops.last_mut() {
+            Some((last_len, last_op)) if *last_op == op => *last_len += len,
+            _ => self.ops.push((len, op)),
+        }
+    }
+
+    /// Runs of operations, with their lengths.
+    pub fn ops(&self) -> &[(usize, CigarOp)] {
+        &self.ops
+    }
+
+    /// Number of reference letters spanned by the alignment.
+    pub fn reference_len(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| *op != CigarOp::Insertion)
+            .map(|(len, _)| len)
+            .sum()
+    }
+
+    /// Number of query letters spanned by the alignment.
+    pub fn query_len(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| *op != CigarOp::Deletion)
+            .map(|(len, _)| len)
+            .sum()
+    }
+}
+
+impl fmt::Display for Cigar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ops.is_empty() {
+            return write!(f, "*");
+        }
+        for (len, op) in &self.ops {
+            write!(f, "{len}{}", op.symbol())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{Cigar, CigarOp};
+
+    #[test]
+    fn alignments_are_encoded_as_cigar_strings() {
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GATCTTCA".chars().collect();
+        let result = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+        let cigar = result.cigar();
+        assert_eq!(cigar.reference_len(), row_seq.len());
+        assert_eq!(cigar.query_len(), column_seq.len());
+        assert_eq!(
+            Cigar::from_aligned(
+                &result.aligned_row_seq,
+                &result.aligned_column_seq,
+                false,
+            ),
+            cigar
+        );
+
+        let row_seq: Vec<char> = "CCGATTACACC".chars().collect();
+        let column_seq: Vec<char> = "GATCACA".chars().collect();
+        let results = best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+        );
+        assert_eq!(results[0].cigar().to_string(), "7M");
+        assert_eq!(results[0].extended_cigar().to_string(), "3=1X3=");
+
+        let gapped: Vec<char> = "GA-TT".chars().collect();
+        let other: Vec<char> = "GAC-T".chars().collect();
+        let cigar = Cigar::from_aligned(&gapped, &other, true);
+        assert_eq!(cigar.to_string(), "2=1I1D1=");
+        assert_eq!(cigar.ops()[1], (1, CigarOp::Insertion));
+        assert_eq!(Cigar::default().to_string(), "*");
+    }
+}
@@ -0,0 +1,198 @@
+use std::ops::Range;
+
+use crate::{local::LocalAlignmentResult, score::Score};
+
+/// Orientation of the column sequence in a local hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strand {
+    /// The column sequence was aligned as given.
+    Forward,
+    /// The reverse complement of the column sequence was aligned; column
+    /// positions refer to the reverse complement.
+    Reverse,
+}
+
+/// A local hit and the orientation it was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrandedHit<'a> {
+    /// Orientation of the column sequence.
+    pub strand: Strand,
+    /// The hit itself.
+    pub result: &'a LocalAlignmentResult,
+}
+
+impl StrandedHit<'_> {
+    /// Diagonal (column minus row position) where the hit starts.
+    fn diagonal(&self) -> isize {
+        self.result.aligned_column_seq.start as isize
+            - self.result.aligned_row_seq.start as isize
+    }
+}
+
+/// Thresholds deciding whether hits belong to the same cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClusterConfig {
+    /// Largest difference between the diagonals of chained hits, i.e. the
+    /// largest net indel between them.
+    pub max_diagonal_distance: usize,
+    /// Largest distance between the end of a cluster and the start of the
+    /// next hit, on either sequence.
+    pub max_gap: usize,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self { max_diagonal_distance: 50, max_gap: 1000 }
+    }
+}
+
+/// A group of hits along nearby diagonals, in the same orientation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitCluster {
+    /// Orientation shared by all hits.
+    pub strand: Strand,
+    /// Indices of the hits in the input, ordered by row start.
+    pub members: Vec<usize>,
+    /// Sum of the scores of the hits.
+    pub score: Score,
+    /// Range of the row sequence spanned by the hits.
+    pub row_range: Range<usize>,
+    /// Range of the column sequence spanned by the hits.
+    pub column_range: Range<usize>,
+}
+
+impl HitCluster {
+    fn new(index: usize, hit: StrandedHit) -> Self {
+        Self {
+            strand: hit.strand,
+            members: vec![index],
+            score: hit.result.score,
+            row_range: hit.result.aligned_row_seq.start
+                .. hit.result.aligned_row_seq.end,
+            column_range: hit.result.aligned_column_seq.start
+                .. hit.result.aligned_column_seq.end,
+        }
+    }
+
+    /// Distance from the end of the cluster to the start of a hit, on the
+    /// sequence where it is longest. Hits starting inside the cluster are at
+    /// distance zero.
+    fn gap_to(&self, hit: StrandedHit) -> usize {
+        let row_gap =
+            hit.result.aligned_row_seq.start.saturating_sub(self.row_range.end);
+        let column_gap = hit
+            .result
+            .aligned_column_seq
+            .start
+            .saturating_sub(self.column_range.end);
+        row_gap.max(column_gap)
+    }
+
+    fn push(&mut self, index: usize, hit: StrandedHit) {
+        self.members.push(index);
+        self.score += hit.result.score;
+        self.row_range.start =
+            self.row_range.start.min(hit.result.aligned_row_seq.start);
+        self.row_range.end =
+            self.row_range.end.max(hit.result.aligned_row_seq.end);
+        self.column_range.start =
+            self.column_range.start.min(hit.result.aligned_column_seq.start);
+        self.column_range.end =
+            self.column_range.end.max(hit.result.aligned_column_seq.end);
+    }
+}
+
+/// Groups local hits into clusters of nearby hits along close diagonals, in
+/// the same orientation, e.g. to summarize the many hits of a repetitive
+/// query into a handful of syntenic regions.
+///
+/// Hits are visited by row start, each one chained to the cluster whose last
+/// hit has the closest diagonal, within the thresholds. Clusters are returned
+/// from the best aggregate score to the worst.
+pub fn cluster_hits(
+    hits: &[StrandedHit],
+    config: ClusterConfig,
+) -> Vec<HitCluster> {
+    let mut order: Vec<usize> = (0 .. hits.len()).collect();
+    order.sort_by_key(|&index| {
+        let result = hits[index].result;
+        (result.aligned_row_seq.start, result.aligned_column_seq.start)
+    });
+
+    let mut clusters: Vec<HitCluster> = Vec::new();
+    for index in order {
+        let hit = hits[index];
+        let closest = clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, cluster)| {
+                cluster.strand == hit.strand
+                    && cluster.gap_to(hit) <= config.max_gap
+            })
+            .map(|(position, cluster)| {
+                let last = hits[cluster.members[cluster.members.len() - 1]];
+                (position, last.diagonal().abs_diff(hit.diagonal()))
+            })
+            .filter(|(_, distance)| *distance <= config.max_diagonal_distance)
+            .min_by_key(|(_, distance)| *distance);
+        match closest {
+            Some((position, _)) => clusters[position].push(index, hit),
+            None => clusters.push(HitCluster::new(index, hit)),
+        }
+    }
+
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.score));
+    clusters
+}
+
+#[cfg(test)]
+mod test {
+    use crate::local::{LocalAlignmentResult, LocallyAlignedSeq};
+
+    use super::{cluster_hits, ClusterConfig, Strand, StrandedHit};
+
+    fn hit(
+        row_start: usize,
+        column_start: usize,
+        len: usize,
+    ) -> LocalAlignmentResult {
+        let seq = |start| LocallyAlignedSeq {
+            start,
+            end: start + len,
+            data: vec!['A'; len],
+        };
+        LocalAlignmentResult {
+            aligned_row_seq: seq(row_start),
+            aligned_column_seq: seq(column_start),
+            score: len as i64,
+            identity_numer: len as u32,
+            identity_denom: len as u32,
+            mapping_quality: 0,
+        }
+    }
+
+    #[test]
+    fn nearby_hits_on_close_diagonals_are_chained() {
+        let results = [
+            hit(0, 100, 20),
+            hit(5000, 0, 10),
+            hit(40, 142, 30),
+            hit(100, 198, 25),
+            hit(10, 110, 15),
+        ];
+        let mut hits: Vec<StrandedHit> = results
+            .iter()
+            .map(|result| StrandedHit { strand: Strand::Forward, result })
+            .collect();
+        hits[4].strand = Strand::Reverse;
+
+        let clusters = cluster_hits(&hits, ClusterConfig::default());
+        assert_eq!(clusters.len(), 3);
+        assert_eq!(clusters[0].members, vec![0, 2, 3]);
+        assert_eq!(clusters[0].score, 75);
+        assert_eq!(clusters[0].row_range, 0 .. 125);
+        assert_eq!(clusters[0].column_range, 100 .. 223);
+        assert_eq!(clusters[1].strand, Strand::Reverse);
+        assert_eq!(clusters[2].members, vec![1]);
+    }
+}
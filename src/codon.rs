@@ -0,0 +1,277 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentResult},
+    letter::{GenericLetter, Letter, GAP},
+    score::{Score, ScoringScheme},
+};
+
+/// A triplet of nucleotides.
+pub type Codon = [Letter; 3];
+
+/// Amino acid letter emitted for stop codons.
+pub const STOP: Letter = '*';
+
+/// Amino acid letter emitted for codons with unknown nucleotides.
+pub const UNKNOWN_AMINO_ACID: Letter = 'X';
+
+/// Amino acids of the standard genetic code, indexed by codon in TCAG order
+/// (e.g. `TTT` is 0, `TTC` is 1, `GGG` is 63).
+const STANDARD_CODE: &[u8; 64] =
+    b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+
+/// Translates a codon using the standard genetic code. `U` is accepted as
+/// `T`, and lowercase nucleotides are accepted as well.
+pub fn translate_codon(codon: Codon) -> Letter {
+    let mut index = 0;
+    for nucleotide in codon {
+        let code = match nucleotide.to_ascii_uppercase() {
+            'T' | 'U' => 0,
+            'C' => 1,
+            'A' => 2,
+            'G' => 3,
+            _ => return UNKNOWN_AMINO_ACID,
+        };
+        index = index * 4 + code;
+    }
+    char::from(STANDARD_CODE[index])
+}
+
+/// Translates a nucleotide sequence in frame 0. Trailing letters that do not
+/// form a full codon are ignored.
+pub fn translate(seq: &[Letter]) -> Vec<Letter> {
+    seq.chunks_exact(3)
+        .map(|codon| translate_codon([codon[0], codon[1], codon[2]]))
+        .collect()
+}
+
+/// Complements a nucleotide, keeping its case. `U` is complemented into `A`,
+/// and other letters are kept as they are.
+pub fn complement(nucleotide: Letter) -> Letter {
+    let complement = match nucleotide.to_ascii_uppercase() {
+        'A' => 'T',
+        'T' | 'U' => 'A',
+        'C' => 'G',
+        'G' => 'C',
+        _ => return nucleotide,
+    };
+    if nucleotide.is_ascii_lowercase() {
+        complement.to_ascii_lowercase()
+    } else {
+        complement
+    }
+}
+
+/// Reverse complement of a nucleotide sequence, i.e. the opposite strand read
+/// in its own 5' to 3' direction.
+pub fn reverse_complement(seq: &[Letter]) -> Vec<Letter> {
+    seq.iter().rev().map(|&nucleotide| complement(nucleotide)).collect()
+}
+
+/// Translates a nucleotide sequence in all six reading frames: offsets 0, 1
+/// and 2 of the sequence, then offsets 0, 1 and 2 of its reverse complement.
+pub fn translate_six_frames(seq: &[Letter]) -> [Vec<Letter>; 6] {
+    let reverse = reverse_complement(seq);
+    let frame = |strand: &[Letter], offset: usize| {
+        translate(strand.get(offset ..).unwrap_or(&[]))
+    };
+    [
+        frame(seq, 0),
+        frame(seq, 1),
+        frame(seq, 2),
+        frame(&reverse, 0),
+        frame(&reverse, 1),
+        frame(&reverse, 2),
+    ]
+}
+
+/// Scoring of a codon-level alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodonAlignmentConfig {
+    /// Added when codons are identical.
+    pub match_penalty: Score,
+    /// Added when codons differ but encode the same amino acid.
+    pub synonymous_penalty: Score,
+    /// Added when codons encode different amino acids.
+    pub nonsynonymous_penalty: Score,
+    /// Added for each whole codon inserted or deleted.
+    pub gap_penalty: Score,
+}
+
+impl Default for CodonAlignmentConfig {
+    fn default() -> Self {
+        Self {
+            match_penalty: 3,
+            synonymous_penalty: 2,
+            nonsynonymous_penalty: -2,
+            gap_penalty: -4,
+        }
+    }
+}
+
+impl CodonAlignmentConfig {
+    /// Score of aligning two codons against each other.
+    pub fn score(&self, row_codon: Codon, column_codon: Codon) -> Score {
+        if row_codon == column_codon {
+            self.match_penalty
+        } else if translate_codon(row_codon) == translate_codon(column_codon) {
+            self.synonymous_penalty
+        } else {
+            self.nonsynonymous_penalty
+        }
+    }
+}
+
+/// Error returned when a coding sequence does not split into whole codons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialCodon {
+    /// Length of the offending sequence, in nucleotides.
+    pub len: usize,
+}
+
+impl fmt::Display for PartialCodon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "coding sequence of {} nucleotides ends in a partial codon",
+            self.len
+        )
+    }
+}
+
+impl Error for PartialCodon {}
+
+/// Splits a nucleotide sequence into codons, failing if trailing letters do
+/// not form a full codon.
+fn codons(seq: &[Letter]) -> Result<Vec<CodonLetter>, PartialCodon> {
+    if !seq.len().is_multiple_of(3) {
+        return Err(PartialCodon { len: seq.len() });
+    }
+    Ok(seq
+        .chunks_exact(3)
+        .map(|codon| CodonLetter { codon: [codon[0], codon[1], codon[2]] })
+        .collect())
+}
+
+/// A codon taking part in an alignment as a single letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CodonLetter {
+    /// The nucleotides of the codon.
+    codon: Codon,
+}
+
+impl GenericLetter for CodonLetter {
+    const GAP: Self = Self { codon: [GAP; 3] };
+
+    fn to_char(self) -> char {
+        if self == Self::GAP {
+            GAP
+        } else {
+            translate_codon(self.codon)
+        }
+    }
+}
+
+impl ScoringScheme<CodonLetter> for CodonAlignmentConfig {
+    fn score(
+        &self,
+        row_letter: CodonLetter,
+        column_letter: CodonLetter,
+    ) -> Score {
+        CodonAlignmentConfig::score(self, row_letter.codon, column_letter.codon)
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
+/// Expands aligned codons back into nucleotides.
+fn nucleotides(aligned_seq: Vec<CodonLetter>) -> Vec<Letter> {
+    aligned_seq.into_iter().flat_map(|letter| letter.codon).collect()
+}
+
+/// Globally aligns two coding sequences codon by codon, so that the reading
+/// frame is preserved: indels always span whole codons.
+///
+/// The aligned sequences are given in nucleotides, and the identity fraction
+/// counts identical codons among aligned codon pairs. Both sequences must be
+/// made of whole codons, otherwise their trailing letters would be left out
+/// of the alignment, so a partial codon is an error.
+pub fn codon_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: CodonAlignmentConfig,
+) -> Result<GlobalAlignmentResult, PartialCodon> {
+    let result =
+        needleman_wunsch(&codons(row_seq)?, &codons(column_seq)?, config);
+    Ok(GlobalAlignmentResult {
+        aligned_row_seq: nucleotides(result.aligned_row_seq),
+        aligned_column_seq: nucleotides(result.aligned_column_seq),
+        score: result.score,
+        identity_numer: result.identity_numer,
+        identity_denom: result.identity_denom,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        codon_needleman_wunsch,
+        reverse_complement,
+        translate,
+        translate_codon,
+        translate_six_frames,
+        CodonAlignmentConfig,
+        PartialCodon,
+    };
+
+    #[test]
+    fn standard_code() {
+        assert_eq!(translate_codon(['A', 'T', 'G']), 'M');
+        assert_eq!(translate_codon(['T', 'A', 'A']), '*');
+        assert_eq!(translate_codon(['g', 'g', 'u']), 'G');
+        assert_eq!(translate_codon(['N', 'G', 'G']), 'X');
+        let seq: Vec<char> = "ATGGCCTGGTA".chars().collect();
+        assert_eq!(translate(&seq), vec!['M', 'A', 'W']);
+
+        let reverse: String = reverse_complement(&seq).into_iter().collect();
+        assert_eq!(reverse, "TACCAGGCCAT");
+        let frames = translate_six_frames(&seq);
+        assert_eq!(frames[0], vec!['M', 'A', 'W']);
+        assert_eq!(frames[1], vec!['W', 'P', 'G']);
+        assert_eq!(frames[3], vec!['Y', 'Q', 'A']);
+        assert_eq!(frames[5], vec!['P', 'G', 'H']);
+    }
+
+    #[test]
+    fn codon_sized_gap() {
+        let row_seq: Vec<char> = "ATGGCCAAATGG".chars().collect();
+        let column_seq: Vec<char> = "ATGGCTTGG".chars().collect();
+
+        let result = codon_needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            CodonAlignmentConfig::default(),
+        )
+        .unwrap();
+
+        let aligned_row: String = result.aligned_row_seq.iter().collect();
+        let aligned_column: String = result.aligned_column_seq.iter().collect();
+        assert_eq!(aligned_row, "ATGGCCAAATGG");
+        assert_eq!(aligned_column, "ATGGCT---TGG");
+        assert_eq!(result.score, 3 + 2 - 4 + 3);
+        assert_eq!(result.identity_numer, 2);
+        assert_eq!(result.identity_denom, 3);
+
+        let truncated = &column_seq[.. 8];
+        assert_eq!(
+            codon_needleman_wunsch(
+                &row_seq,
+                truncated,
+                CodonAlignmentConfig::default()
+            ),
+            Err(PartialCodon { len: 8 })
+        );
+    }
+}
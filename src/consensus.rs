@@ -0,0 +1,101 @@
+use crate::{
+    letter::{Letter, GAP},
+    pileup::{Pileup, PileupColumn},
+};
+
+/// Letter emitted where no confident consensus could be called.
+pub const AMBIGUOUS: Letter = 'N';
+
+/// Thresholds of the consensus caller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConsensusConfig {
+    /// Minimum depth required to call a letter.
+    pub min_depth: u32,
+    /// Minimum fraction of the depth (between 0 and 1) that must agree on the
+    /// majority letter.
+    pub min_agreement: f64,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self { min_depth: 1, min_agreement: 0.5 }
+    }
+}
+
+/// Calls the consensus sequence of a pileup, column by column.
+///
+/// Each position yields its majority letter, or [`AMBIGUOUS`] when depth is
+/// too low, the majority is tied, or it does not reach the agreement
+/// threshold. Positions whose majority is a deletion are left out.
+pub fn call_consensus(pileup: &Pileup, config: ConsensusConfig) -> Vec<Letter> {
+    let mut consensus = Vec::with_capacity(pileup.len());
+    for column in pileup.columns() {
+        match call_column(column, config) {
+            Some(GAP) => (),
+            Some(letter) => consensus.push(letter),
+            None => consensus.push(AMBIGUOUS),
+        }
+    }
+    consensus
+}
+
+/// Calls the majority letter of a single pileup column, if confident enough.
+fn call_column(
+    column: &PileupColumn,
+    config: ConsensusConfig,
+) -> Option<Letter> {
+    let depth = column.depth();
+    if depth == 0 || depth < config.min_depth {
+        return None;
+    }
+
+    let mut majority = None;
+    let mut majority_count = 0;
+    let mut tied = false;
+    for (&letter, &count) in &column.counts {
+        if count > majority_count {
+            majority = Some(letter);
+            majority_count = count;
+            tied = false;
+        } else if count == majority_count {
+            tied = true;
+        }
+    }
+
+    let agreement = f64::from(majority_count) / f64::from(depth);
+    if tied || agreement < config.min_agreement {
+        None
+    } else {
+        majority
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{global::GlobalAlignmentResult, pileup::Pileup};
+
+    use super::{call_consensus, ConsensusConfig};
+
+    fn read(reference: &str, read: &str) -> GlobalAlignmentResult {
+        GlobalAlignmentResult {
+            aligned_row_seq: reference.chars().collect(),
+            aligned_column_seq: read.chars().collect(),
+            score: 0,
+            identity_numer: 0,
+            identity_denom: 1,
+        }
+    }
+
+    #[test]
+    fn majority_with_ambiguity_and_deletion() {
+        let mut pileup = Pileup::new(5);
+        pileup.add_global(&read("ACGTA", "ACGTA"));
+        pileup.add_global(&read("ACGTA", "AC-TC"));
+        pileup.add_global(&read("ACGTA", "AT-TG"));
+
+        let config = ConsensusConfig { min_depth: 2, min_agreement: 0.6 };
+        let expected_consensus = vec!['A', 'C', 'T', 'N'];
+
+        assert_eq!(call_consensus(&pileup, config), expected_consensus);
+    }
+}
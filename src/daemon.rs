@@ -0,0 +1,729 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, BufRead, Read, Write},
+    iter::Peekable,
+    str::Chars,
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    align::{align, Alignment, AlignmentMode},
+    global::GlobalAlignmentConfig,
+    letter::Letter,
+    report::json::json_string,
+    score::Score,
+};
+
+/// Deepest nesting of arrays and objects accepted in a request.
+pub const MAX_JSON_DEPTH: usize = 64;
+
+/// Largest number of matrix cells a single request may fill, summed over
+/// all of its alignments. Bounds the time and memory spent on each request.
+pub const MAX_CELLS: usize = 1 << 24;
+
+/// Largest request line accepted, in bytes, newline included. Longer lines
+/// are answered with an error without being kept in memory.
+pub const MAX_LINE_LEN: usize = 1 << 24;
+
+/// Largest magnitude accepted for the `match`, `mismatch` and `gap` scores.
+/// Together with [`MAX_CELLS`], it keeps every alignment score far from
+/// overflowing.
+pub const MAX_SCORE_MAGNITUDE: Score = 1 << 20;
+
+/// Largest number of connections served at the same time.
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Time a connection may stay idle before it is dropped.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Error in an alignment request, reported back to the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestError {
+    /// The request is not a JSON object.
+    Json {
+        /// What went wrong while reading the JSON text.
+        message: String,
+    },
+    /// A required field is absent.
+    MissingField {
+        /// Name of the field.
+        field: &'static str,
+    },
+    /// A field has a value of the wrong type.
+    InvalidField {
+        /// Name of the field.
+        field: String,
+    },
+    /// The `mode` field is not one of `"global"`, `"local"`, `"semiglobal"`,
+    /// `"overlap"` or `"fitting"`.
+    UnknownMode {
+        /// The mode requested.
+        mode: String,
+    },
+    /// The alignments requested would fill more than [`MAX_CELLS`] cells.
+    TooLarge {
+        /// Number of cells the request would fill.
+        cells: usize,
+    },
+    /// A score of the alignments requested does not fit in a [`Score`].
+    Overflow {
+        /// Matrix cell whose score overflowed.
+        cell: (usize, usize),
+    },
+    /// The request line is longer than [`MAX_LINE_LEN`] bytes.
+    LineTooLong {
+        /// Largest line length accepted.
+        limit: usize,
+    },
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json { message } => write!(f, "malformed JSON: {message}"),
+            Self::MissingField { field } => {
+                write!(f, "missing field {field:?}")
+            },
+            Self::InvalidField { field } => {
+                write!(f, "field {field:?} has an invalid value")
+            },
+            Self::UnknownMode { mode } => write!(f, "unknown mode {mode:?}"),
+            Self::TooLarge { cells } => write!(
+                f,
+                "request fills {cells} cells, more than the limit of \
+                 {MAX_CELLS}"
+            ),
+            Self::Overflow { cell: (i, j) } => {
+                write!(f, "score overflowed at matrix cell ({i}, {j})")
+            },
+            Self::LineTooLong { limit } => {
+                write!(f, "request line longer than {limit} bytes")
+            },
+        }
+    }
+}
+
+impl Error for RequestError {}
+
+/// A JSON value, as read from requests.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    /// Number, kept as its source text.
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// Object fields, in source order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Parses a whole JSON document.
+    pub(crate) fn parse(text: &str) -> Result<Self, RequestError> {
+        let mut parser =
+            JsonParser { chars: text.chars().peekable(), depth: 0 };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.chars.next().is_some() {
+            return Err(JsonParser::error("trailing characters after value"));
+        }
+        Ok(value)
+    }
+
+    /// Value of a field, if this is an object that has it.
+    pub(crate) fn get(&self, name: &str) -> Option<&Self> {
+        match self {
+            Self::Object(fields) => fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Renders the value back as JSON.
+    pub(crate) fn to_json(&self) -> String {
+        match self {
+            Self::Null => "null".to_owned(),
+            Self::Bool(value) => value.to_string(),
+            Self::Number(text) => text.clone(),
+            Self::String(text) => json_string(text),
+            Self::Array(items) => {
+                let items: Vec<String> =
+                    items.iter().map(Self::to_json).collect();
+                format!("[{}]", items.join(","))
+            },
+            Self::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| {
+                        format!("{}:{}", json_string(key), value.to_json())
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            },
+        }
+    }
+}
+
+/// Reader of JSON text.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// Number of arrays and objects currently open.
+    depth: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(message: &str) -> RequestError {
+        RequestError::Json { message: message.to_owned() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RequestError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(ch) if ch == expected => Ok(()),
+            _ => Err(Self::error(&format!("expected {expected:?}"))),
+        }
+    }
+
+    /// Parses the items of an array or the fields of an object, after the
+    /// opening bracket, up to the closing one.
+    fn parse_sequence<F>(
+        &mut self,
+        closing: char,
+        mut parse_item: F,
+    ) -> Result<(), RequestError>
+    where
+        F: FnMut(&mut Self) -> Result<(), RequestError>,
+    {
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&closing).is_some() {
+            return Ok(());
+        }
+        loop {
+            parse_item(self)?;
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => continue,
+                Some(ch) if ch == closing => return Ok(()),
+                _ => {
+                    return Err(Self::error(&format!(
+                        "expected ',' or {closing:?}"
+                    )))
+                },
+            }
+        }
+    }
+
+    /// Parses an array or object body one nesting level deeper, refusing to
+    /// go past [`MAX_JSON_DEPTH`] so that hostile input cannot overflow the
+    /// stack.
+    fn parse_nested<T, F>(&mut self, parse: F) -> Result<T, RequestError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, RequestError>,
+    {
+        if self.depth >= MAX_JSON_DEPTH {
+            return Err(Self::error("nesting too deep"));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, RequestError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('"') => self.parse_string().map(JsonValue::String),
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                self.parse_nested(|parser| {
+                    parser.parse_sequence('}', |parser| {
+                        parser.skip_whitespace();
+                        let key = parser.parse_string()?;
+                        parser.expect(':')?;
+                        fields.push((key, parser.parse_value()?));
+                        Ok(())
+                    })
+                })?;
+                Ok(JsonValue::Object(fields))
+            },
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.parse_nested(|parser| {
+                    parser.parse_sequence(']', |parser| {
+                        items.push(parser.parse_value()?);
+                        Ok(())
+                    })
+                })?;
+                Ok(JsonValue::Array(items))
+            },
+            Some(ch) if *ch == '-' || ch.is_ascii_digit() => {
+                let mut text = String::new();
+                while let Some(ch) = self.chars.next_if(|ch| {
+                    ch.is_ascii_digit()
+                        || matches!(ch, '-' | '+' | '.' | 'e' | 'E')
+                }) {
+                    text.push(ch);
+                }
+                Ok(JsonValue::Number(text))
+            },
+            _ => {
+                let mut word = String::new();
+                while let Some(ch) =
+                    self.chars.next_if(char::is_ascii_lowercase)
+                {
+                    word.push(ch);
+                }
+                match word.as_str() {
+                    "null" => Ok(JsonValue::Null),
+                    "true" => Ok(JsonValue::Bool(true)),
+                    "false" => Ok(JsonValue::Bool(false)),
+                    _ => Err(Self::error("expected a value")),
+                }
+            },
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, RequestError> {
+        if self.chars.next() != Some('"') {
+            return Err(Self::error("expected a string"));
+        }
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                None => return Err(Self::error("unterminated string")),
+                Some('"') => return Ok(text),
+                Some('\\') => {
+                    let escaped = match self.chars.next() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = (0 .. 4)
+                                .filter_map(|_| self.chars.next())
+                                .collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        },
+                        Some(ch @ ('"' | '\\' | '/')) => ch,
+                        _ => return Err(Self::error("invalid escape")),
+                    };
+                    text.push(escaped);
+                },
+                Some(ch) => text.push(ch),
+            }
+        }
+    }
+}
+
+/// Reads an optional string field of a request object.
+pub(crate) fn text_field(
+    object: &JsonValue,
+    field: &'static str,
+) -> Result<Option<String>, RequestError> {
+    match object.get(field) {
+        Some(JsonValue::String(text)) => Ok(Some(text.clone())),
+        None | Some(JsonValue::Null) => Ok(None),
+        Some(_) => Err(RequestError::InvalidField { field: field.to_owned() }),
+    }
+}
+
+/// Reads a required string field of a request object.
+pub(crate) fn required_text_field(
+    object: &JsonValue,
+    field: &'static str,
+) -> Result<String, RequestError> {
+    text_field(object, field)?.ok_or(RequestError::MissingField { field })
+}
+
+/// Reads an optional integer field of a request object.
+pub(crate) fn number_field<T>(
+    object: &JsonValue,
+    field: &'static str,
+    default: T,
+) -> Result<T, RequestError>
+where
+    T: std::str::FromStr,
+{
+    match object.get(field) {
+        Some(JsonValue::Number(text)) => text.parse().map_err(|_| {
+            RequestError::InvalidField { field: field.to_owned() }
+        }),
+        None | Some(JsonValue::Null) => Ok(default),
+        Some(_) => Err(RequestError::InvalidField { field: field.to_owned() }),
+    }
+}
+
+/// Reads the `match`, `mismatch` and `gap` fields of a request object, each
+/// defaulting to the value of [`GlobalAlignmentConfig::default`]. Scores
+/// beyond [`MAX_SCORE_MAGNITUDE`] are invalid.
+pub(crate) fn scheme_fields(
+    object: &JsonValue,
+) -> Result<GlobalAlignmentConfig, RequestError> {
+    let defaults = GlobalAlignmentConfig::default();
+    let score_field = |field: &'static str, default: Score| {
+        let score = number_field(object, field, default)?;
+        if !(-MAX_SCORE_MAGNITUDE ..= MAX_SCORE_MAGNITUDE).contains(&score) {
+            return Err(RequestError::InvalidField { field: field.to_owned() });
+        }
+        Ok(score)
+    };
+    Ok(GlobalAlignmentConfig {
+        match_penalty: score_field("match", defaults.match_penalty)?,
+        mismatch_penalty: score_field("mismatch", defaults.mismatch_penalty)?,
+        gap_penalty: score_field("gap", defaults.gap_penalty)?,
+    })
+}
+
+/// Counts the cells filled when aligning sequences of the given lengths,
+/// failing if they exceed [`MAX_CELLS`].
+pub(crate) fn count_cells<I>(lens: I) -> Result<usize, RequestError>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let cells =
+        lens.into_iter().fold(0usize, |cells, (row_len, column_len)| {
+            cells.saturating_add(
+                row_len
+                    .saturating_add(1)
+                    .saturating_mul(column_len.saturating_add(1)),
+            )
+        });
+    if cells > MAX_CELLS {
+        Err(RequestError::TooLarge { cells })
+    } else {
+        Ok(cells)
+    }
+}
+
+/// Parses a request document, which must be an object.
+pub(crate) fn parse_request_object(
+    text: &str,
+) -> Result<JsonValue, RequestError> {
+    match JsonValue::parse(text)? {
+        object @ JsonValue::Object(_) => Ok(object),
+        _ => Err(JsonParser::error("expected an object")),
+    }
+}
+
+/// A parsed alignment request.
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    mode: AlignmentMode,
+    row_seq: Vec<Letter>,
+    column_seq: Vec<Letter>,
+    scheme: GlobalAlignmentConfig,
+}
+
+impl Request {
+    fn parse(object: &JsonValue) -> Result<Self, RequestError> {
+        let mode = match text_field(object, "mode")?.as_deref() {
+            None | Some("global") => AlignmentMode::Global,
+            Some("local") => AlignmentMode::Local,
+            Some("semiglobal") => AlignmentMode::SemiGlobal,
+            Some("overlap") => AlignmentMode::Overlap,
+            Some("fitting") => AlignmentMode::Fitting,
+            Some(mode) => {
+                return Err(RequestError::UnknownMode {
+                    mode: mode.to_owned(),
+                });
+            },
+        };
+        let row_seq: Vec<Letter> =
+            required_text_field(object, "row")?.chars().collect();
+        let column_seq: Vec<Letter> =
+            required_text_field(object, "column")?.chars().collect();
+        count_cells([(row_seq.len(), column_seq.len())])?;
+        Ok(Self { mode, row_seq, column_seq, scheme: scheme_fields(object)? })
+    }
+
+    /// Aligns the sequences, or returns `None` for a local alignment without
+    /// any positive hit.
+    fn run(&self) -> Option<Alignment> {
+        let alignment =
+            align(&self.row_seq, &self.column_seq, self.mode, self.scheme);
+        let no_hit = self.mode == AlignmentMode::Local
+            && alignment.aligned_row_seq.data.is_empty();
+        (!no_hit).then_some(alignment)
+    }
+}
+
+/// Answers one request line with one response line (without the newline).
+///
+/// A request is a JSON object:
+///
+/// ```text
+/// {"id": 7, "mode": "local", "row": "GATTACA", "column": "TTAC",
+///  "match": 1, "mismatch": -1, "gap": -2}
+/// ```
+///
+/// `id` is optional and echoed back as given; `mode` is one of the modes of
+/// [`align`], `"global"`, `"local"`, `"semiglobal"`, `"overlap"` or
+/// `"fitting"`, and defaults to `"global"`; the scores default to those of
+/// [`GlobalAlignmentConfig::default`] and may not exceed
+/// [`MAX_SCORE_MAGNITUDE`]. The
+/// response carries the score, the aligned sequences with gaps, their 0-based
+/// half-open ranges and the identity, or an `error` message. A local request
+/// without any positive hit gets a `null` alignment.
+pub fn handle_request(line: &str) -> String {
+    respond(line).unwrap_or_else(|error| error)
+}
+
+/// Answers a request like [`handle_request`], telling apart successful
+/// responses and error responses.
+pub(crate) fn respond(text: &str) -> Result<String, String> {
+    let object = parse_request_object(text)
+        .map_err(|error| error_response(&JsonValue::Null, &error))?;
+    let id = object.get("id").cloned().unwrap_or(JsonValue::Null);
+    let request =
+        Request::parse(&object).map_err(|error| error_response(&id, &error))?;
+    let id = id.to_json();
+    Ok(match request.run() {
+        None => format!("{{\"id\":{id},\"score\":0,\"alignment\":null}}"),
+        Some(alignment) => {
+            let row = &alignment.aligned_row_seq;
+            let column = &alignment.aligned_column_seq;
+            format!(
+                "{{\"id\":{id},\"score\":{},\"alignment\":{{\"row\":{},\"\
+                 column\":{},\"row_start\":{},\"row_end\":{},\"column_start\":\
+                 {},\"column_end\":{},\"identity\":{}}}}}",
+                alignment.score,
+                json_string(&row.data.iter().collect::<String>()),
+                json_string(&column.data.iter().collect::<String>()),
+                row.start,
+                row.end,
+                column.start,
+                column.end,
+                alignment.identity(),
+            )
+        },
+    })
+}
+
+/// Renders the response to a failed request.
+pub(crate) fn error_response(id: &JsonValue, error: &RequestError) -> String {
+    format!(
+        "{{\"id\":{},\"error\":{}}}",
+        id.to_json(),
+        json_string(&error.to_string())
+    )
+}
+
+/// Serves requests read line by line from `input`, writing and flushing one
+/// response line per request, until the input ends. Blank lines are skipped,
+/// and lines longer than [`MAX_LINE_LEN`] are answered with an error.
+pub fn serve<R, W>(input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write + ?Sized,
+{
+    serve_limited(input, output, MAX_LINE_LEN)
+}
+
+/// Implements [`serve`] with the given line length limit.
+fn serve_limited<R, W>(
+    mut input: R,
+    output: &mut W,
+    max_line_len: usize,
+) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write + ?Sized,
+{
+    let mut bytes = Vec::new();
+    loop {
+        bytes.clear();
+        let len = input
+            .by_ref()
+            .take(max_line_len as u64)
+            .read_until(b'\n', &mut bytes)?;
+        if len == 0 {
+            return Ok(());
+        }
+        let response = if len == max_line_len && !bytes.ends_with(b"\n") {
+            // drop the rest of the line without keeping it
+            input.skip_until(b'\n')?;
+            let error = RequestError::LineTooLong { limit: max_line_len };
+            error_response(&JsonValue::Null, &error)
+        } else {
+            let line = String::from_utf8(std::mem::take(&mut bytes)).map_err(
+                |error| io::Error::new(io::ErrorKind::InvalidData, error),
+            )?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            handle_request(&line)
+        };
+        writeln!(output, "{response}")?;
+        output.flush()?;
+    }
+}
+
+/// Bounds the number of connections served at the same time.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionLimit {
+    active: Arc<(Mutex<usize>, Condvar)>,
+}
+
+impl ConnectionLimit {
+    pub(crate) fn new() -> Self {
+        Self { active: Arc::new((Mutex::new(0), Condvar::new())) }
+    }
+
+    /// Waits until fewer than [`MAX_CONNECTIONS`] connections are served,
+    /// then takes a slot, given back when the returned guard is dropped.
+    pub(crate) fn acquire(&self) -> ConnectionSlot {
+        let (count, freed) = &*self.active;
+        let mut count = count.lock().unwrap_or_else(|error| error.into_inner());
+        while *count >= MAX_CONNECTIONS {
+            count =
+                freed.wait(count).unwrap_or_else(|error| error.into_inner());
+        }
+        *count += 1;
+        ConnectionSlot { active: self.active.clone() }
+    }
+}
+
+/// A connection slot taken from a [`ConnectionLimit`].
+#[derive(Debug)]
+pub(crate) struct ConnectionSlot {
+    active: Arc<(Mutex<usize>, Condvar)>,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let (count, freed) = &*self.active;
+        *count.lock().unwrap_or_else(|error| error.into_inner()) -= 1;
+        freed.notify_one();
+    }
+}
+
+/// Listens on a Unix socket, serving every connection with [`serve`] in its
+/// own thread, at most [`MAX_CONNECTIONS`] at a time. Connections idle for
+/// [`IDLE_TIMEOUT`] are dropped. Runs until accepting a connection fails.
+#[cfg(unix)]
+pub fn serve_unix_socket<P>(path: P) -> io::Result<()>
+where
+    P: AsRef<std::path::Path>,
+{
+    use std::{io::BufReader, os::unix::net::UnixListener, thread};
+
+    let listener = UnixListener::bind(path)?;
+    let limit = ConnectionLimit::new();
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let slot = limit.acquire();
+        thread::spawn(move || -> io::Result<()> {
+            let _slot = slot;
+            stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+            stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+            let input = BufReader::new(stream.try_clone()?);
+            serve(input, &mut stream)
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{handle_request, serve, serve_limited, MAX_JSON_DEPTH};
+
+    #[test]
+    fn requests_are_answered_line_by_line() {
+        let input = "\
+{\"id\": 1, \"row\": \"GATTACA\", \"column\": \"GATCA\"}
+
+{\"id\": \"two\", \"mode\": \"local\", \"row\": \"AAGATTACAAA\", \"column\": \
+                     \"TTAC\", \"gap\": -3}
+{\"id\": 3, \"mode\": \"semi\", \"row\": \"A\", \"column\": \"A\"}
+{\"row\": \"A\"}
+not json
+";
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("{\"id\":1,\"score\":1,"));
+        assert_eq!(
+            lines[1],
+            "{\"id\":\"two\",\"score\":4,\"alignment\":{\"row\":\"TTAC\",\"\
+             column\":\"TTAC\",\"row_start\":4,\"row_end\":8,\"column_start\":\
+             0,\"column_end\":4,\"identity\":1}}"
+        );
+        assert_eq!(
+            lines[2],
+            "{\"id\":3,\"error\":\"unknown mode \\\"semi\\\"\"}"
+        );
+        assert_eq!(
+            lines[3],
+            "{\"id\":null,\"error\":\"missing field \\\"column\\\"\"}"
+        );
+        assert!(lines[4].contains("malformed JSON"));
+    }
+
+    #[test]
+    fn long_lines_are_answered_with_an_error() {
+        let long = format!("{{\"row\": \"{}\"}}", "A".repeat(100));
+        let input = format!(
+            "{long}\n{{\"id\": 2, \"row\": \"A\", \"column\": \"A\"}}\n"
+        );
+        let mut output = Vec::new();
+        serve_limited(input.as_bytes(), &mut output, 64).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "{\"id\":null,\"error\":\"request line longer than 64 bytes\"}"
+        );
+        assert!(lines[1].starts_with("{\"id\":2,\"score\":1,"));
+    }
+
+    #[test]
+    fn hostile_requests_are_rejected() {
+        let deep = "[".repeat(200_000);
+        assert!(handle_request(&deep).contains("nesting too deep"));
+        let nested = format!(
+            "{{\"row\": \"A\", \"column\": \"A\", \"x\": {}{}}}",
+            "[".repeat(MAX_JSON_DEPTH - 1),
+            "]".repeat(MAX_JSON_DEPTH - 1)
+        );
+        assert!(handle_request(&nested).contains("\"score\":1"));
+
+        let overflowing = "{\"row\": \"AC\", \"column\": \"AC\", \"gap\": \
+                           -9223372036854775808}";
+        assert_eq!(
+            handle_request(overflowing),
+            "{\"id\":null,\"error\":\"field \\\"gap\\\" has an invalid \
+             value\"}"
+        );
+        let fitting = handle_request(
+            "{\"mode\": \"fitting\", \"row\": \"TTGATTACATT\", \"column\": \
+             \"GATTACA\"}",
+        );
+        assert!(fitting.contains("\"row_start\":2,\"row_end\":9"));
+
+        let long_seq = "A".repeat(5000);
+        let response = handle_request(&format!(
+            "{{\"row\": \"{long_seq}\", \"column\": \"{long_seq}\"}}"
+        ));
+        assert!(response.contains("more than the limit"));
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentResult},
+    heatmap::IdentityMatrix,
+    letter::Letter,
+    score::{Score, ScoringScheme},
+};
+
+/// Globally aligns every pair of distinct sequences once, giving the indices
+/// `i < j` of each pair and its alignment to `visit`.
+pub(crate) fn align_all_pairs<S, F>(
+    seqs: &[&[Letter]],
+    scheme: &S,
+    mut visit: F,
+) where
+    S: ScoringScheme,
+    F: FnMut(usize, usize, GlobalAlignmentResult),
+{
+    for i in 0 .. seqs.len() {
+        for j in i + 1 .. seqs.len() {
+            visit(i, j, needleman_wunsch(seqs[i], seqs[j], scheme));
+        }
+    }
+}
+
+/// How alignments are turned into distances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DistanceKind {
+    /// One minus the identity of the alignment, from 0 to 1.
+    #[default]
+    Identity,
+    /// One minus the alignment score divided by the mean of the scores of
+    /// each sequence aligned with itself. Identical sequences are at 0, and
+    /// alignments scoring below zero are farther than 1.
+    Score,
+}
+
+/// Pairwise distances of a set of sequences, from all-vs-all global
+/// alignments. The matrix is symmetric with zeros in the diagonal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    values: Vec<f64>,
+    size: usize,
+}
+
+impl DistanceMatrix {
+    /// Globally aligns every pair of sequences and converts the alignments
+    /// into distances of the given kind.
+    pub fn compute<S>(seqs: &[&[Letter]], scheme: S, kind: DistanceKind) -> Self
+    where
+        S: ScoringScheme,
+    {
+        #[cfg(feature = "instrument")]
+        let mut span = Span::enter("distance_matrix", 0);
+        let size = seqs.len();
+        let self_scores: Vec<Score> = match kind {
+            DistanceKind::Identity => Vec::new(),
+            DistanceKind::Score => seqs
+                .iter()
+                .map(|seq| {
+                    seq.iter().map(|&letter| scheme.score(letter, letter)).sum()
+                })
+                .collect(),
+        };
+        let mut values = vec![0.0; size * size];
+        align_all_pairs(seqs, &scheme, |i, j, result| {
+            let distance = match kind {
+                DistanceKind::Identity => 1.0 - result.identity(),
+                DistanceKind::Score => {
+                    let mean_self_score =
+                        (self_scores[i] + self_scores[j]) as f64 / 2.0;
+                    1.0 - result.score as f64 / mean_self_score.max(1.0)
+                },
+            };
+            values[i * size + j] = distance;
+            values[j * size + i] = distance;
+            #[cfg(feature = "instrument")]
+            span.add_item();
+        });
+        Self { values, size }
+    }
+
+    /// Converts identities already computed into distances of the
+    /// [`DistanceKind::Identity`] kind, without aligning anything again.
+    pub fn from_identities(identities: &IdentityMatrix) -> Self {
+        let size = identities.len();
+        let values = (0 .. size * size)
+            .map(|index| {
+                identities
+                    .get(index / size, index % size)
+                    .map_or(0.0, |x| 1.0 - x)
+            })
+            .collect();
+        Self { values, size }
+    }
+
+    /// Number of sequences.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Whether there are no sequences at all.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Distance between the `i`-th and `j`-th sequences.
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        if i >= self.size || j >= self.size {
+            None
+        } else {
+            Some(self.values[i * self.size + j])
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{global::GlobalAlignmentConfig, heatmap::IdentityMatrix};
+
+    use super::{DistanceKind, DistanceMatrix};
+
+    #[test]
+    fn distances_grow_with_differences() {
+        let seqs: Vec<Vec<char>> = ["GATTACA", "GATTACA", "GATCACA", "CCGGTTA"]
+            .into_iter()
+            .map(|seq| seq.chars().collect())
+            .collect();
+        let seqs: Vec<&[char]> = seqs.iter().map(Vec::as_slice).collect();
+        let config = GlobalAlignmentConfig::default();
+
+        let identity =
+            DistanceMatrix::compute(&seqs, config, DistanceKind::Identity);
+        assert_eq!(identity.len(), 4);
+        assert_eq!(identity.get(0, 0), Some(0.0));
+        assert_eq!(identity.get(0, 1), Some(0.0));
+        assert_eq!(identity.get(0, 2), Some(1.0 - 6.0 / 7.0));
+        assert_eq!(identity.get(2, 0), identity.get(0, 2));
+        assert_eq!(identity.get(0, 4), None);
+
+        let named: Vec<_> = seqs.iter().map(|seq| ("seq", *seq)).collect();
+        let identities = IdentityMatrix::compute(&named, config);
+        assert_eq!(DistanceMatrix::from_identities(&identities), identity);
+
+        let score = DistanceMatrix::compute(&seqs, config, DistanceKind::Score);
+        assert_eq!(score.get(0, 1), Some(0.0));
+        assert_eq!(score.get(0, 2), Some(1.0 - 5.0 / 7.0));
+        assert!(score.get(0, 3).unwrap() > 1.0);
+    }
+}
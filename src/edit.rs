@@ -0,0 +1,42 @@
+use crate::{
+    global::{nw_score_only, GlobalAlignmentConfig},
+    letter::GenericLetter,
+};
+
+/// Levenshtein edit distance: the least number of substitutions, insertions
+/// and deletions turning one sequence into the other.
+///
+/// Only two matrix rows are kept in memory. For the edits themselves, align
+/// with [`GlobalAlignmentConfig::LEVENSHTEIN`].
+pub fn edit_distance<L>(a: &[L], b: &[L]) -> usize
+where
+    L: GenericLetter,
+{
+    nw_score_only(a, b, GlobalAlignmentConfig::LEVENSHTEIN).unsigned_abs()
+        as usize
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::{needleman_wunsch, GlobalAlignmentConfig};
+
+    use super::edit_distance;
+
+    #[test]
+    fn kitten_becomes_sitting_in_three_edits() {
+        let kitten: Vec<char> = "kitten".chars().collect();
+        let sitting: Vec<char> = "sitting".chars().collect();
+        assert_eq!(edit_distance(&kitten, &sitting), 3);
+        assert_eq!(edit_distance(&kitten, &kitten), 0);
+        assert_eq!(edit_distance(&[], &sitting), 7);
+        assert_eq!(edit_distance(b"flaw", b"lawn"), 2);
+
+        let result = needleman_wunsch(
+            &kitten,
+            &sitting,
+            GlobalAlignmentConfig::LEVENSHTEIN,
+        );
+        assert_eq!(result.score, -3);
+        assert_eq!(result.alignment_ops().counts(), [4, 2, 1, 0]);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use crate::{letter::Letter, score::Score};
+
+/// Dense code of a letter in an [`Alphabet`].
+pub type Code = u8;
+
+/// Largest number of letters an alphabet can hold.
+pub const MAX_ALPHABET_SIZE: usize = Code::MAX as usize + 1;
+
+/// Mapping from letters to dense small integer codes, `0 .. len()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<Letter>,
+    codes: HashMap<Letter, Code>,
+}
+
+impl Alphabet {
+    /// Creates an alphabet from distinct letters, coded in the given order.
+    /// Duplicates are ignored. Returns `None` if there are more than
+    /// [`MAX_ALPHABET_SIZE`] distinct letters.
+    pub fn new<I>(letters: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Letter>,
+    {
+        let mut alphabet = Self::default();
+        for letter in letters {
+            alphabet.insert(letter)?;
+        }
+        Some(alphabet)
+    }
+
+    /// Creates an alphabet of all letters occurring in the given sequences,
+    /// in order of first occurrence.
+    pub fn from_seqs(seqs: &[&[Letter]]) -> Option<Self> {
+        Self::new(seqs.iter().flat_map(|seq| seq.iter().copied()))
+    }
+
+    /// Adds a letter if not already present, returning its code. Returns
+    /// `None` if the alphabet is full.
+    pub fn insert(&mut self, letter: Letter) -> Option<Code> {
+        if let Some(&code) = self.codes.get(&letter) {
+            return Some(code);
+        }
+        let code = Code::try_from(self.letters.len()).ok()?;
+        self.letters.push(letter);
+        self.codes.insert(letter, code);
+        Some(code)
+    }
+
+    /// Number of letters.
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    /// Tests whether the alphabet has no letters.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Code of a letter, if in the alphabet.
+    pub fn code(&self, letter: Letter) -> Option<Code> {
+        self.codes.get(&letter).copied()
+    }
+
+    /// Letter of a code, if in the alphabet.
+    pub fn letter(&self, code: Code) -> Option<Letter> {
+        self.letters.get(usize::from(code)).copied()
+    }
+
+    /// Letters, in code order.
+    pub fn letters(&self) -> &[Letter] {
+        &self.letters
+    }
+
+    /// Encodes a sequence. Returns `None` if a letter is not in the alphabet.
+    pub fn encode(&self, seq: &[Letter]) -> Option<Vec<Code>> {
+        seq.iter().map(|&letter| self.code(letter)).collect()
+    }
+
+    /// Decodes a sequence. Returns `None` if a code is not in the alphabet.
+    pub fn decode(&self, codes: &[Code]) -> Option<Vec<Letter>> {
+        codes.iter().map(|&code| self.letter(code)).collect()
+    }
+}
+
+/// Scores of every pair of codes of an alphabet, looked up without branching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreTable {
+    size: usize,
+    scores: Vec<Score>,
+}
+
+impl ScoreTable {
+    /// Builds the table of a match/mismatch scheme.
+    pub fn new(
+        alphabet: &Alphabet,
+        match_penalty: Score,
+        mismatch_penalty: Score,
+    ) -> Self {
+        Self::from_fn(alphabet, |row_letter, column_letter| {
+            if row_letter == column_letter {
+                match_penalty
+            } else {
+                mismatch_penalty
+            }
+        })
+    }
+
+    /// Builds the table from a function scoring pairs of letters, e.g. a
+    /// substitution matrix.
+    pub fn from_fn<F>(alphabet: &Alphabet, mut pair_score: F) -> Self
+    where
+        F: FnMut(Letter, Letter) -> Score,
+    {
+        let size = alphabet.len();
+        let mut scores = Vec::with_capacity(size * size);
+        for &row_letter in alphabet.letters() {
+            for &column_letter in alphabet.letters() {
+                scores.push(pair_score(row_letter, column_letter));
+            }
+        }
+        Self { size, scores }
+    }
+
+    /// Number of codes on each side of the table.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Score of aligning two codes.
+    ///
+    /// # Panics
+    /// Panics if a code is out of the alphabet of the table.
+    pub fn get(&self, row_code: Code, column_code: Code) -> Score {
+        self.scores
+            [usize::from(row_code) * self.size + usize::from(column_code)]
+    }
+
+    /// Scores of a code of the row sequence against every code.
+    fn row(&self, row_code: Code) -> &[Score] {
+        let start = usize::from(row_code) * self.size;
+        &self.scores[start .. start + self.size]
+    }
+}
+
+/// Computes a Needleman-Wunsch score over encoded sequences, keeping only two
+/// rows in memory.
+pub fn encoded_global_score(
+    row_codes: &[Code],
+    column_codes: &[Code],
+    table: &ScoreTable,
+    gap_penalty: Score,
+) -> Score {
+    let mut previous: Vec<Score> =
+        (0 ..= column_codes.len()).map(|j| j as Score * gap_penalty).collect();
+    let mut current = vec![0; column_codes.len() + 1];
+    for (i, &row_code) in row_codes.iter().enumerate() {
+        let pair_scores = table.row(row_code);
+        current[0] = (i + 1) as Score * gap_penalty;
+        for (j, &column_code) in column_codes.iter().enumerate() {
+            let no_gap_score =
+                previous[j] + pair_scores[usize::from(column_code)];
+            let best_gap_score = previous[j + 1].max(current[j]) + gap_penalty;
+            current[j + 1] = no_gap_score.max(best_gap_score);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[column_codes.len()]
+}
+
+/// Computes the best Smith-Waterman score over encoded sequences, keeping only
+/// two rows in memory.
+pub fn encoded_local_score(
+    row_codes: &[Code],
+    column_codes: &[Code],
+    table: &ScoreTable,
+    gap_penalty: Score,
+) -> Score {
+    let mut previous = vec![0; column_codes.len() + 1];
+    let mut current = vec![0; column_codes.len() + 1];
+    let mut best = 0;
+    for &row_code in row_codes {
+        let pair_scores = table.row(row_code);
+        for (j, &column_code) in column_codes.iter().enumerate() {
+            let no_gap_score =
+                previous[j] + pair_scores[usize::from(column_code)];
+            let best_gap_score = previous[j + 1].max(current[j]) + gap_penalty;
+            current[j + 1] = no_gap_score.max(best_gap_score).max(0);
+            best = best.max(current[j + 1]);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{
+        encoded_global_score,
+        encoded_local_score,
+        Alphabet,
+        ScoreTable,
+    };
+
+    #[test]
+    fn table_driven_scores_match_char_kernels() {
+        let mut rng = Rng::new(42);
+        let row_seq = random_dna(60, &mut rng);
+        let column_seq = random_dna(50, &mut rng);
+        let alphabet = Alphabet::from_seqs(&[&row_seq, &column_seq]).unwrap();
+        assert_eq!(alphabet.len(), 4);
+        let row_codes = alphabet.encode(&row_seq).unwrap();
+        let column_codes = alphabet.encode(&column_seq).unwrap();
+        assert_eq!(alphabet.decode(&row_codes), Some(row_seq.clone()));
+        assert_eq!(alphabet.encode(&['N']), None);
+
+        let global = GlobalAlignmentConfig::default();
+        let table = ScoreTable::new(
+            &alphabet,
+            global.match_penalty,
+            global.mismatch_penalty,
+        );
+        assert_eq!(
+            encoded_global_score(
+                &row_codes,
+                &column_codes,
+                &table,
+                global.gap_penalty
+            ),
+            needleman_wunsch(&row_seq, &column_seq, global).score
+        );
+        let local = LocalAlignmentConfig::default();
+        assert_eq!(
+            encoded_local_score(
+                &row_codes,
+                &column_codes,
+                &table,
+                local.gap_penalty
+            ),
+            best_smith_waterman(&row_seq, &column_seq, local)[0].score
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    global::GlobalAlignmentConfig,
+    letter::{Letter, GAP},
+    local::LocalAlignmentConfig,
+    score::{Score, ScoringScheme},
+};
+
+/// Parameters of the scoring estimator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitConfig {
+    /// Multiplier applied to log-odds in bits before rounding, e.g. `2.0` for
+    /// half-bit units.
+    pub scale: f64,
+    /// Count added to every observed frequency, avoiding infinite scores for
+    /// events that never appear in the examples.
+    pub pseudocount: f64,
+}
+
+impl Default for FitConfig {
+    fn default() -> Self {
+        Self { scale: 2.0, pseudocount: 1.0 }
+    }
+}
+
+/// Scoring parameters estimated from trusted alignments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FittedScoring {
+    /// Log-odds of a match against chance.
+    pub match_penalty: Score,
+    /// Log-odds of a mismatch against chance.
+    pub mismatch_penalty: Score,
+    /// Log-probability of a gap column.
+    pub gap_penalty: Score,
+    /// Log-odds of every observed letter pair, keyed with the smallest letter
+    /// first.
+    pub pair_scores: BTreeMap<(Letter, Letter), Score>,
+}
+
+impl FittedScoring {
+    /// Score of a letter pair in the fitted log-odds table, if both letters
+    /// were observed.
+    pub fn pair_score(&self, a: Letter, b: Letter) -> Option<Score> {
+        self.pair_scores.get(&(a.min(b), a.max(b))).copied()
+    }
+
+    /// Fitted parameters as a global alignment configuration.
+    pub fn global_config(&self) -> GlobalAlignmentConfig {
+        GlobalAlignmentConfig {
+            match_penalty: self.match_penalty,
+            mismatch_penalty: self.mismatch_penalty,
+            gap_penalty: self.gap_penalty,
+        }
+    }
+
+    /// Fitted parameters as a local alignment configuration.
+    pub fn local_config(&self) -> LocalAlignmentConfig {
+        LocalAlignmentConfig {
+            match_penalty: self.match_penalty,
+            mismatch_penalty: self.mismatch_penalty,
+            gap_penalty: self.gap_penalty,
+        }
+    }
+}
+
+/// Scores pairs with the fitted table, falling back to the fitted match and
+/// mismatch scores for letters never observed.
+impl ScoringScheme for FittedScoring {
+    fn score(&self, row_letter: Letter, column_letter: Letter) -> Score {
+        self.pair_score(row_letter, column_letter).unwrap_or(
+            if row_letter == column_letter {
+                self.match_penalty
+            } else {
+                self.mismatch_penalty
+            },
+        )
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
+/// Converts a probability ratio into a rounded, scaled log-odds score.
+fn log_odds(ratio: f64, scale: f64) -> Score {
+    (scale * ratio.log2()).round() as Score
+}
+
+/// Estimates scoring parameters from trusted alignments, each given as a pair
+/// of gapped sequences of the same length.
+///
+/// Match and mismatch scores are the log-odds of the observed match rate
+/// against the rate expected from letter frequencies alone. The gap score is
+/// the log-probability of a gap column. The pair table follows the classic
+/// BLOSUM construction.
+pub fn fit_scoring(
+    alignments: &[(&[Letter], &[Letter])],
+    config: FitConfig,
+) -> FittedScoring {
+    let mut pair_counts: BTreeMap<(Letter, Letter), f64> = BTreeMap::new();
+    let mut letter_counts: BTreeMap<Letter, f64> = BTreeMap::new();
+    let mut matches = 0.0;
+    let mut pairs = 0.0;
+    let mut gap_columns = 0.0;
+    let mut columns = 0.0;
+
+    for (row_seq, column_seq) in alignments {
+        for (&a, &b) in row_seq.iter().zip(column_seq.iter()) {
+            if a == GAP && b == GAP {
+                continue;
+            }
+            columns += 1.0;
+            if a == GAP || b == GAP {
+                gap_columns += 1.0;
+                continue;
+            }
+            pairs += 1.0;
+            if a == b {
+                matches += 1.0;
+            }
+            *pair_counts.entry((a.min(b), a.max(b))).or_insert(0.0) += 1.0;
+            *letter_counts.entry(a).or_insert(0.0) += 1.0;
+            *letter_counts.entry(b).or_insert(0.0) += 1.0;
+        }
+    }
+
+    let pseudocount = config.pseudocount;
+    let letter_total: f64 = letter_counts.values().sum::<f64>()
+        + pseudocount * letter_counts.len() as f64;
+    let letter_freq = |letter: Letter| {
+        (letter_counts.get(&letter).copied().unwrap_or(0.0) + pseudocount)
+            / letter_total.max(1.0)
+    };
+    let expected_match: f64 =
+        letter_counts.keys().map(|&letter| letter_freq(letter).powi(2)).sum();
+    let expected_match = expected_match.clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+
+    let observed_match =
+        (matches + pseudocount) / (pairs + 2.0 * pseudocount).max(1.0);
+    let observed_mismatch = 1.0 - observed_match;
+    let gap_rate =
+        (gap_columns + pseudocount) / (columns + 2.0 * pseudocount).max(1.0);
+
+    let letters: Vec<Letter> = letter_counts.keys().copied().collect();
+    let pair_kinds = (letters.len() * (letters.len() + 1) / 2) as f64;
+    let pair_total: f64 =
+        pair_counts.values().sum::<f64>() + pseudocount * pair_kinds;
+    let mut pair_scores = BTreeMap::new();
+    for (index, &a) in letters.iter().enumerate() {
+        for &b in &letters[index ..] {
+            let observed = (pair_counts.get(&(a, b)).copied().unwrap_or(0.0)
+                + pseudocount)
+                / pair_total.max(1.0);
+            let expected = if a == b {
+                letter_freq(a) * letter_freq(b)
+            } else {
+                2.0 * letter_freq(a) * letter_freq(b)
+            };
+            pair_scores
+                .insert((a, b), log_odds(observed / expected, config.scale));
+        }
+    }
+
+    FittedScoring {
+        match_penalty: log_odds(observed_match / expected_match, config.scale),
+        mismatch_penalty: log_odds(
+            observed_mismatch / (1.0 - expected_match),
+            config.scale,
+        ),
+        gap_penalty: log_odds(gap_rate, config.scale),
+        pair_scores,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fit_scoring, FitConfig};
+
+    #[test]
+    fn similar_examples_reward_matches() {
+        let examples: Vec<(Vec<char>, Vec<char>)> = [
+            ("ACGTACGTAC", "ACGTACGTAC"),
+            ("ACGTTCGTAC", "ACGTACG-AC"),
+            ("GGCATCGA-T", "GGCATGGACT"),
+        ]
+        .iter()
+        .map(|(a, b)| (a.chars().collect(), b.chars().collect()))
+        .collect();
+        let alignments: Vec<(&[char], &[char])> =
+            examples.iter().map(|(a, b)| (&a[..], &b[..])).collect();
+
+        let fitted = fit_scoring(&alignments, FitConfig::default());
+
+        assert!(fitted.match_penalty > 0);
+        assert!(fitted.mismatch_penalty < 0);
+        assert!(fitted.gap_penalty < fitted.mismatch_penalty);
+        assert!(fitted.pair_score('A', 'A').unwrap() > 0);
+        assert_eq!(fitted.pair_score('T', 'A'), fitted.pair_score('A', 'T'));
+    }
+}
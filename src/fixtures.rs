@@ -0,0 +1,94 @@
+use crate::{
+    generate::{random_dna, Rng, DNA},
+    letter::Letter,
+    mutate::{mutate, MutationRates},
+};
+
+/// A named pair of sequences to be aligned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairScenario {
+    /// Short identifier, e.g. `"long/similar"`.
+    pub name: &'static str,
+    /// Sequence associated with a row display.
+    pub row_seq: Vec<Letter>,
+    /// Sequence associated with a column display.
+    pub column_seq: Vec<Letter>,
+}
+
+/// A query and a database of targets, for batch workloads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchScenario {
+    /// Short identifier, e.g. `"batch/100x300"`.
+    pub name: &'static str,
+    /// Sequence searched for.
+    pub query: Vec<Letter>,
+    /// Sequences searched, some of them related to the query.
+    pub targets: Vec<Vec<Letter>>,
+}
+
+/// Mutation rates of pairs that share most letters.
+const SIMILAR: MutationRates =
+    MutationRates { substitution: 0.05, insertion: 0.01, deletion: 0.01 };
+
+/// Mutation rates of pairs that barely resemble each other.
+const DIVERGENT: MutationRates =
+    MutationRates { substitution: 0.4, insertion: 0.1, deletion: 0.1 };
+
+/// Builds a pair from a random DNA sequence and a mutant of it.
+fn pair(
+    name: &'static str,
+    length: usize,
+    rates: MutationRates,
+    rng: &mut Rng,
+) -> PairScenario {
+    let row_seq = random_dna(length, rng);
+    let column_seq = mutate(&row_seq, rates, DNA, rng).seq;
+    PairScenario { name, row_seq, column_seq }
+}
+
+/// Short and long, similar and divergent DNA pairs, reproducible from the
+/// seed.
+pub fn pair_scenarios(seed: u64) -> Vec<PairScenario> {
+    let mut rng = Rng::new(seed);
+    vec![
+        pair("short/similar", 100, SIMILAR, &mut rng),
+        pair("short/divergent", 100, DIVERGENT, &mut rng),
+        pair("long/similar", 2000, SIMILAR, &mut rng),
+        pair("long/divergent", 2000, DIVERGENT, &mut rng),
+    ]
+}
+
+/// A query against `count` targets of the same length, every tenth target
+/// being a similar mutant of the query and the others unrelated.
+pub fn batch_scenario(
+    name: &'static str,
+    seed: u64,
+    count: usize,
+    length: usize,
+) -> BatchScenario {
+    let mut rng = Rng::new(seed);
+    let query = random_dna(length, &mut rng);
+    let targets = (0 .. count)
+        .map(|index| {
+            if index % 10 == 0 {
+                mutate(&query, SIMILAR, DNA, &mut rng).seq
+            } else {
+                random_dna(length, &mut rng)
+            }
+        })
+        .collect();
+    BatchScenario { name, query, targets }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{batch_scenario, pair_scenarios};
+
+    #[test]
+    fn scenarios_are_reproducible() {
+        assert_eq!(pair_scenarios(1), pair_scenarios(1));
+        let batch = batch_scenario("batch", 2, 20, 50);
+        assert_eq!(batch.targets.len(), 20);
+        assert_eq!(batch.query.len(), 50);
+    }
+}
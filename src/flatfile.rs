@@ -0,0 +1,243 @@
+use std::{error::Error, fmt};
+
+use crate::letter::Letter;
+
+/// A sequence record read from a GenBank or EMBL flat file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Primary accession number.
+    pub accession: String,
+    /// Free-text description of the sequence.
+    pub definition: String,
+    /// The sequence, with letters converted to uppercase.
+    pub seq: Vec<Letter>,
+}
+
+/// Flat file format of a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FlatFileFormat {
+    /// NCBI GenBank, whose records start with `LOCUS`.
+    GenBank,
+    /// EMBL-EBI, whose records start with `ID`.
+    Embl,
+}
+
+/// Error raised when a flat file is malformed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlatFileError {
+    /// The first line is neither a GenBank `LOCUS` nor an EMBL `ID` line.
+    UnknownFormat,
+    /// A record ended without an accession line.
+    MissingAccession {
+        /// Line number (starting from 1) where the record ended.
+        line: usize,
+    },
+    /// A record ended without a sequence section.
+    MissingSequence {
+        /// Accession of the offending record.
+        accession: String,
+    },
+    /// The input ended in the middle of a record, without the `//`
+    /// terminator.
+    UnterminatedRecord,
+}
+
+impl fmt::Display for FlatFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => {
+                write!(f, "input is neither in GenBank nor in EMBL format")
+            },
+            Self::MissingAccession { line } => {
+                write!(f, "record ending at line {line} has no accession")
+            },
+            Self::MissingSequence { accession } => {
+                write!(f, "record {accession} has no sequence")
+            },
+            Self::UnterminatedRecord => {
+                write!(f, "input ended in the middle of a record")
+            },
+        }
+    }
+}
+
+impl Error for FlatFileError {}
+
+/// Line keywords of a format, with the column where their content starts.
+struct Keywords {
+    accession: &'static str,
+    definition: &'static str,
+    sequence: &'static str,
+    content_column: usize,
+}
+
+impl FlatFileFormat {
+    /// Detects the format from the first non-blank line of the input.
+    pub fn detect(text: &str) -> Option<Self> {
+        let first = text.lines().find(|line| !line.trim().is_empty())?;
+        if first.starts_with("LOCUS") {
+            Some(Self::GenBank)
+        } else if first.starts_with("ID ") {
+            Some(Self::Embl)
+        } else {
+            None
+        }
+    }
+
+    fn keywords(self) -> Keywords {
+        match self {
+            Self::GenBank => Keywords {
+                accession: "ACCESSION",
+                definition: "DEFINITION",
+                sequence: "ORIGIN",
+                content_column: 12,
+            },
+            Self::Embl => Keywords {
+                accession: "AC",
+                definition: "DE",
+                sequence: "SQ",
+                content_column: 5,
+            },
+        }
+    }
+}
+
+/// Parses every record of a GenBank or EMBL flat file, detecting the format
+/// from the first line.
+pub fn parse_flat_file(text: &str) -> Result<Vec<Record>, FlatFileError> {
+    let format =
+        FlatFileFormat::detect(text).ok_or(FlatFileError::UnknownFormat)?;
+    parse_records(text, format)
+}
+
+/// Parses every record of a flat file in the given format.
+///
+/// Only the accession, the definition and the sequence are kept; features and
+/// other annotations are skipped.
+pub fn parse_records(
+    text: &str,
+    format: FlatFileFormat,
+) -> Result<Vec<Record>, FlatFileError> {
+    let keywords = format.keywords();
+    let mut records = Vec::new();
+    let mut accession: Option<String> = None;
+    let mut definition = String::new();
+    let mut seq: Option<Vec<Letter>> = None;
+    let mut in_record = false;
+    let mut in_definition = false;
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with("//") {
+            let accession = accession
+                .take()
+                .ok_or(FlatFileError::MissingAccession { line: index + 1 })?;
+            let seq = seq.take().ok_or(FlatFileError::MissingSequence {
+                accession: accession.clone(),
+            })?;
+            records.push(Record {
+                accession,
+                definition: std::mem::take(&mut definition),
+                seq,
+            });
+            in_record = false;
+            in_definition = false;
+            continue;
+        }
+        in_record = true;
+
+        if let Some(seq) = &mut seq {
+            seq.extend(
+                line.chars()
+                    .filter(char::is_ascii_alphabetic)
+                    .map(|letter| letter.to_ascii_uppercase()),
+            );
+            continue;
+        }
+
+        let keyword = line.split_whitespace().next().unwrap_or("");
+        let content = line.get(keywords.content_column ..).unwrap_or("").trim();
+        let continuation = match format {
+            FlatFileFormat::GenBank => line.starts_with(' '),
+            FlatFileFormat::Embl => keyword == keywords.definition,
+        };
+        if in_definition && continuation {
+            if !definition.is_empty() {
+                definition.push(' ');
+            }
+            definition.push_str(content);
+            continue;
+        }
+        in_definition = false;
+
+        if keyword == keywords.accession && accession.is_none() {
+            let first = content.split([' ', ';']).next().unwrap_or("");
+            accession = Some(first.to_string());
+        } else if keyword == keywords.definition {
+            definition.push_str(content);
+            in_definition = true;
+        } else if keyword == keywords.sequence {
+            seq = Some(Vec::new());
+        }
+    }
+
+    if in_record {
+        return Err(FlatFileError::UnterminatedRecord);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_flat_file, FlatFileError, Record};
+
+    #[test]
+    fn genbank_and_embl_records() {
+        let genbank = "\
+LOCUS       SCU49845     20 bp    DNA             PLN       21-JUN-1999
+DEFINITION  Saccharomyces cerevisiae TCP1-beta gene, partial cds, and Axl2p
+            (AXL2) genes.
+ACCESSION   U49845 U49846
+FEATURES             Location/Qualifiers
+     source          1..20
+ORIGIN
+        1 gatcctccat atacaacggt
+//
+";
+        let embl = "\
+ID   X56734; SV 1; linear; mRNA; STD; PLN; 12 BP.
+AC   X56734; S46826;
+DE   Trifolium repens mRNA
+DE   for non-cyanogenic beta-glucosidase
+SQ   Sequence 12 BP;
+     aaacaaacca aa                                                        12
+//
+";
+        assert_eq!(
+            parse_flat_file(genbank),
+            Ok(vec![Record {
+                accession: "U49845".to_string(),
+                definition: "Saccharomyces cerevisiae TCP1-beta gene, partial \
+                             cds, and Axl2p (AXL2) genes."
+                    .to_string(),
+                seq: "GATCCTCCATATACAACGGT".chars().collect(),
+            }])
+        );
+        assert_eq!(
+            parse_flat_file(embl),
+            Ok(vec![Record {
+                accession: "X56734".to_string(),
+                definition: "Trifolium repens mRNA for non-cyanogenic \
+                             beta-glucosidase"
+                    .to_string(),
+                seq: "AAACAAACCAAA".chars().collect(),
+            }])
+        );
+        assert_eq!(
+            parse_flat_file(&genbank[.. genbank.len() - 3]),
+            Err(FlatFileError::UnterminatedRecord)
+        );
+    }
+}
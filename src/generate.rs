@@ -0,0 +1,141 @@
+use crate::letter::Letter;
+
+/// The four DNA nucleotides.
+pub const DNA: &[Letter] = &['A', 'C', 'G', 'T'];
+
+/// The twenty standard amino acids.
+pub const PROTEIN: &[Letter] = &[
+    'A', 'R', 'N', 'D', 'C', 'Q', 'E', 'G', 'H', 'I', 'L', 'K', 'M', 'F', 'P',
+    'S', 'T', 'W', 'Y', 'V',
+];
+
+/// Small seeded pseudo-random number generator (SplitMix64).
+///
+/// It is not cryptographically secure, but it is fast and fully reproducible
+/// given the seed, which is what test data generation needs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generates the next 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a float uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generates an integer uniformly distributed in `[0, bound)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "random bound must be positive");
+        let bound = bound as u64;
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                break (value % bound) as usize;
+            }
+        }
+    }
+
+    /// Picks a uniformly random element of a slice, if it is not empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.below(items.len()))
+        }
+    }
+}
+
+/// Generates a random sequence where each letter is drawn independently with
+/// probability proportional to its weight in `composition`.
+///
+/// Letters with non-positive weights are never drawn. An empty sequence is
+/// returned if no letter has a positive weight.
+pub fn random_sequence(
+    length: usize,
+    composition: &[(Letter, f64)],
+    rng: &mut Rng,
+) -> Vec<Letter> {
+    let total: f64 =
+        composition.iter().map(|(_, weight)| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let mut seq = Vec::with_capacity(length);
+    for _ in 0 .. length {
+        let mut threshold = rng.next_f64() * total;
+        let mut drawn = None;
+        for &(letter, weight) in composition {
+            if weight <= 0.0 {
+                continue;
+            }
+            drawn = Some(letter);
+            if threshold < weight {
+                break;
+            }
+            threshold -= weight;
+        }
+        seq.extend(drawn);
+    }
+    seq
+}
+
+/// Generates a random sequence with letters uniformly drawn from `alphabet`.
+pub fn random_uniform(
+    length: usize,
+    alphabet: &[Letter],
+    rng: &mut Rng,
+) -> Vec<Letter> {
+    (0 .. length).filter_map(|_| rng.choose(alphabet).copied()).collect()
+}
+
+/// Generates a random DNA sequence with uniform composition.
+pub fn random_dna(length: usize, rng: &mut Rng) -> Vec<Letter> {
+    random_uniform(length, DNA, rng)
+}
+
+/// Generates a random protein sequence with uniform composition.
+pub fn random_protein(length: usize, rng: &mut Rng) -> Vec<Letter> {
+    random_uniform(length, PROTEIN, rng)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{random_dna, random_sequence, Rng, DNA};
+
+    #[test]
+    fn reproducible_and_composed() {
+        let first = random_dna(50, &mut Rng::new(42));
+        let second = random_dna(50, &mut Rng::new(42));
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 50);
+        assert!(first.iter().all(|letter| DNA.contains(letter)));
+
+        let gc_only = random_sequence(
+            100,
+            &[('G', 1.0), ('C', 1.0), ('A', 0.0)],
+            &mut Rng::new(7),
+        );
+        assert_eq!(gc_only.len(), 100);
+        assert!(gc_only.iter().all(|letter| *letter == 'G' || *letter == 'C'));
+    }
+}
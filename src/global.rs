@@ -1,13 +1,29 @@
-use std::fmt;
+use std::{error::Error, fmt};
 
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
 use crate::{
-    letter::{Letter, NormalizeLetter, GAP},
+    arena::Arena,
+    cigar::Cigar,
+    letter::{letter_or_gap, GenericLetter, Letter, GAP},
     matrix::AlignmentMatrix,
-    score::Score,
+    ops::AlignmentOps,
+    report::{
+        write_blocks,
+        write_clustal_pair,
+        write_emboss_pair,
+        write_fasta_pair,
+        HeaderVerbosity,
+        PrettyStyle,
+        WriteReport,
+    },
+    score::{Score, ScoringScheme},
+    trace::{CellStep, FillTrace},
+    traceback::{TracebackMatrix, TracebackPointer},
 };
 
 /// Penalty/base score system of a global alignment.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GlobalAlignmentConfig {
     /// Added when letters match.
     pub match_penalty: Score,
@@ -17,21 +33,46 @@ pub struct GlobalAlignmentConfig {
     pub gap_penalty: Score,
 }
 
+impl GlobalAlignmentConfig {
+    /// Penalties whose alignments are the ones of the Levenshtein edit
+    /// distance: substitutions and indels cost one, matches are free, so the
+    /// score is minus the edit distance.
+    pub const LEVENSHTEIN: Self =
+        Self { match_penalty: 0, mismatch_penalty: -1, gap_penalty: -1 };
+}
+
 impl Default for GlobalAlignmentConfig {
     fn default() -> Self {
         Self { match_penalty: 1, mismatch_penalty: -1, gap_penalty: -2 }
     }
 }
 
+impl<L> ScoringScheme<L> for GlobalAlignmentConfig
+where
+    L: GenericLetter,
+{
+    fn score(&self, row_letter: L, column_letter: L) -> Score {
+        if row_letter == column_letter {
+            self.match_penalty
+        } else {
+            self.mismatch_penalty
+        }
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
 /// Result of the global alignment.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GlobalAlignmentResult {
+pub struct GlobalAlignmentResult<L = Letter> {
     /// The sequence that was associated with "row" display,
     /// aligned with the one displayed in a column.
-    pub aligned_row_seq: Vec<Letter>,
+    pub aligned_row_seq: Vec<L>,
     /// The sequence that was associated with "column" display,
     /// aligned with the one displayed in a row.
-    pub aligned_column_seq: Vec<Letter>,
+    pub aligned_column_seq: Vec<L>,
     /// Total score of the global alignment.
     pub score: Score,
     /// Numerator of the identity fraction (32-bit).
@@ -40,13 +81,43 @@ pub struct GlobalAlignmentResult {
     pub identity_denom: u32,
 }
 
-impl GlobalAlignmentResult {
+impl<L> GlobalAlignmentResult<L> {
     /// Computes the identity as a percentage.
     pub fn identity(&self) -> f64 {
         f64::from(self.identity_numer) / f64::from(self.identity_denom)
     }
 }
 
+impl GlobalAlignmentResult {
+    /// Encodes the alignment as a CIGAR string of `M`, `I` and `D`
+    /// operations, taking the row sequence as the reference.
+    pub fn cigar(&self) -> Cigar {
+        Cigar::from_aligned(
+            &self.aligned_row_seq,
+            &self.aligned_column_seq,
+            false,
+        )
+    }
+
+    /// Encodes the alignment as a CIGAR string telling matches (`=`) and
+    /// mismatches (`X`) apart.
+    pub fn extended_cigar(&self) -> Cigar {
+        Cigar::from_aligned(
+            &self.aligned_row_seq,
+            &self.aligned_column_seq,
+            true,
+        )
+    }
+
+    /// Converts the alignment into column operations.
+    pub fn alignment_ops(&self) -> AlignmentOps {
+        AlignmentOps::from_aligned(
+            &self.aligned_row_seq,
+            &self.aligned_column_seq,
+        )
+    }
+}
+
 /// Possible directions during traceback phase.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum TracebackStep {
@@ -62,30 +133,359 @@ enum TracebackStep {
 /// `row_seq` and `column_seq` are the sequences to be aligned.
 /// `row_seq` will be displayed as a row in the matrix, while `column_seq` will
 /// be displayed as a column in the matrix.
-pub fn needleman_wunsch(
+///
+/// Any [`ScoringScheme`] may be used, e.g. a [`GlobalAlignmentConfig`] or a
+/// substitution matrix.
+pub fn needleman_wunsch<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> GlobalAlignmentResult<L>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let matrix = compute_nw_matrix(row_seq, column_seq, &scheme);
+    traceback_nw_best_alignment(row_seq, column_seq, &scheme, &matrix)
+}
+
+/// Executes the Needleman-Wunsch algorithm only while the final score can still
+/// reach `min_score`.
+///
+/// The matrix is filled row by row. After each row, the best cell of the row
+/// plus the best score obtainable from the remaining letters bounds the final
+/// score; once that bound falls below `min_score`, the computation is
+/// abandoned and `None` is returned. `None` is also returned if the final
+/// score is below `min_score`.
+pub fn needleman_wunsch_at_least(
     row_seq: &[Letter],
     column_seq: &[Letter],
     config: GlobalAlignmentConfig,
-) -> GlobalAlignmentResult {
-    let matrix = compute_nw_matrix(row_seq, column_seq, config);
-    traceback_nw_best_alignment(row_seq, column_seq, config, &matrix)
+    min_score: Score,
+) -> Option<GlobalAlignmentResult> {
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    fill_nw_matrix_base(row_seq, column_seq, &config, &mut matrix);
+    for i in 0 .. row_count {
+        if i > 0 {
+            for j in 1 .. column_count {
+                compute_nw_matrix_cell(
+                    row_seq,
+                    column_seq,
+                    &config,
+                    &mut matrix,
+                    i - 1,
+                    j - 1,
+                    None,
+                );
+            }
+        }
+        let reachable = (0 .. column_count).any(|j| {
+            let remaining = remaining_score_upper_bound(
+                row_seq.len() - i,
+                column_seq.len() - j,
+                config,
+            );
+            matrix[[i, j]] + remaining >= min_score
+        });
+        if !reachable {
+            return None;
+        }
+    }
+    Some(traceback_nw_best_alignment(row_seq, column_seq, config, &matrix))
+}
+
+/// Best score obtainable by globally aligning any `row_left` letters against
+/// any `column_left` letters.
+fn remaining_score_upper_bound(
+    row_left: usize,
+    column_left: usize,
+    config: GlobalAlignmentConfig,
+) -> Score {
+    let pairs = row_left.min(column_left) as Score;
+    let unpaired = row_left.abs_diff(column_left) as Score;
+    let best_pair = config.match_penalty.max(config.mismatch_penalty);
+    let all_paired = pairs * best_pair + unpaired * config.gap_penalty;
+    let all_gaps = (2 * pairs + unpaired) * config.gap_penalty;
+    all_paired.max(all_gaps)
 }
 
 /// Given Needleman-Wunsch input and a score matrix already populated, this
 /// function computes the alignment.
-pub fn traceback_nw_best_alignment(
+pub fn traceback_nw_best_alignment<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+    matrix: &AlignmentMatrix,
+) -> GlobalAlignmentResult<L>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let initial_capacity = row_seq.len() + column_seq.len();
+    let mut result = traceback_nw_into(
+        row_seq,
+        column_seq,
+        &scheme,
+        matrix,
+        Vec::with_capacity(initial_capacity),
+        Vec::with_capacity(initial_capacity),
+    );
+    result.aligned_row_seq.shrink_to_fit();
+    result.aligned_column_seq.shrink_to_fit();
+    result
+}
+
+/// Executes the Needleman-Wunsch algorithm like [`needleman_wunsch`], taking
+/// the matrix and the aligned sequences from an arena. The matrix goes back
+/// to the arena when done.
+pub fn needleman_wunsch_in<S>(
     row_seq: &[Letter],
     column_seq: &[Letter],
-    config: GlobalAlignmentConfig,
+    scheme: S,
+    arena: &mut Arena,
+) -> GlobalAlignmentResult
+where
+    S: ScoringScheme,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = arena.matrix(row_count, column_count);
+    fill_nw_matrix_base(row_seq, column_seq, &scheme, &mut matrix);
+    fill_nw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        None,
+        None,
+    );
+    let initial_capacity = row_seq.len() + column_seq.len();
+    let result = traceback_nw_into(
+        row_seq,
+        column_seq,
+        &scheme,
+        &matrix,
+        arena.letters(initial_capacity),
+        arena.letters(initial_capacity),
+    );
+    arena.recycle_matrix(matrix);
+    result
+}
+
+/// Fills a zeroed Needleman-Wunsch matrix of dimensions
+/// `(row_seq.len() + 1) X (column_seq.len() + 1)` allocated by the caller.
+pub(crate) fn fill_nw_matrix<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
+    matrix: &mut AlignmentMatrix,
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
+    fill_nw_matrix_base(row_seq, column_seq, scheme, matrix);
+    fill_nw_matrix_content(row_seq, column_seq, scheme, matrix, None, None);
+}
+
+/// Computes only the Needleman-Wunsch score, without traceback, keeping two
+/// matrix rows in memory instead of the whole matrix.
+pub fn nw_score_only<L, S>(row_seq: &[L], column_seq: &[L], scheme: S) -> Score
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    last_nw_score_row(row_seq, column_seq, &scheme, false)[column_seq.len()]
+}
+
+/// Executes Needleman-Wunsch with Hirschberg's divide and conquer, using
+/// working memory linear in the length of the shortest sequence instead of a
+/// whole matrix, at about twice the time.
+///
+/// The score is the same as the one of [`needleman_wunsch`], but among
+/// alignments with the same score, another one may be chosen.
+pub fn hirschberg_needleman_wunsch<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: S,
+) -> GlobalAlignmentResult
+where
+    S: ScoringScheme,
+{
+    let capacity = row_seq.len() + column_seq.len();
+    let mut result = GlobalAlignmentResult {
+        aligned_row_seq: Vec::with_capacity(capacity),
+        aligned_column_seq: Vec::with_capacity(capacity),
+        score: 0,
+        identity_numer: 0,
+        identity_denom: 0,
+    };
+    let mut push = |row_letter: Letter, column_letter: Letter| {
+        result.aligned_row_seq.push(row_letter);
+        result.aligned_column_seq.push(column_letter);
+        if row_letter == GAP || column_letter == GAP {
+            result.score += scheme.gap_penalty();
+        } else {
+            result.score += scheme.score(row_letter, column_letter);
+            result.identity_denom += 1;
+            if row_letter == column_letter {
+                result.identity_numer += 1;
+            }
+        }
+    };
+    if column_seq.len() > row_seq.len() {
+        hirschberg_into(
+            column_seq,
+            row_seq,
+            &Transposed(&scheme),
+            &mut |column_letter, row_letter| push(row_letter, column_letter),
+        );
+    } else {
+        hirschberg_into(row_seq, column_seq, &scheme, &mut push);
+    }
+    result.identity_denom = result.identity_denom.max(1);
+    result
+}
+
+/// A scheme scoring pairs with the roles of the sequences swapped.
+struct Transposed<'a, S: ?Sized>(&'a S);
+
+impl<S> ScoringScheme for Transposed<'_, S>
+where
+    S: ScoringScheme + ?Sized,
+{
+    fn score(&self, row_letter: Letter, column_letter: Letter) -> Score {
+        self.0.score(column_letter, row_letter)
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.0.gap_penalty()
+    }
+}
+
+/// Scores of globally aligning the whole row sequence against every prefix
+/// of the column sequence, or against every suffix if `reverse` is set,
+/// keeping only two rows in memory. The score against the `k` letters of the
+/// prefix or suffix is at index `k`.
+fn last_nw_score_row<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
+    reverse: bool,
+) -> Vec<Score>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
+    let gap_penalty = scheme.gap_penalty();
+    let at = |seq: &[L], k: usize| {
+        if reverse {
+            seq[seq.len() - 1 - k]
+        } else {
+            seq[k]
+        }
+    };
+    let mut previous: Vec<Score> =
+        (0 ..= column_seq.len()).map(|j| j as Score * gap_penalty).collect();
+    let mut current = vec![0; column_seq.len() + 1];
+    for i in 0 .. row_seq.len() {
+        let row_letter = at(row_seq, i);
+        current[0] = (i + 1) as Score * gap_penalty;
+        for j in 0 .. column_seq.len() {
+            let no_gap_score =
+                previous[j] + scheme.score(row_letter, at(column_seq, j));
+            let best_gap_score = previous[j + 1].max(current[j]) + gap_penalty;
+            current[j + 1] = no_gap_score.max(best_gap_score);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous
+}
+
+/// Recursion of [`hirschberg_needleman_wunsch`], calling `push` with every
+/// column of the alignment, in order.
+fn hirschberg_into<S, F>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: &S,
+    push: &mut F,
+) where
+    S: ScoringScheme + ?Sized,
+    F: FnMut(Letter, Letter),
+{
+    if row_seq.is_empty() || column_seq.is_empty() {
+        for &row_letter in row_seq {
+            push(row_letter, GAP);
+        }
+        for &column_letter in column_seq {
+            push(GAP, column_letter);
+        }
+        return;
+    }
+    if let [row_letter] = *row_seq {
+        let (paired_j, paired_score) = column_seq
+            .iter()
+            .enumerate()
+            .map(|(j, &column_letter)| {
+                (j, scheme.score(row_letter, column_letter))
+            })
+            .rev()
+            .max_by_key(|&(_, score)| score)
+            .unwrap_or((0, Score::MIN));
+        if paired_score >= 2 * scheme.gap_penalty() {
+            for (j, &column_letter) in column_seq.iter().enumerate() {
+                if j == paired_j {
+                    push(row_letter, column_letter);
+                } else {
+                    push(GAP, column_letter);
+                }
+            }
+        } else {
+            push(row_letter, GAP);
+            for &column_letter in column_seq {
+                push(GAP, column_letter);
+            }
+        }
+        return;
+    }
+
+    let middle = row_seq.len() / 2;
+    let forward =
+        last_nw_score_row(&row_seq[.. middle], column_seq, scheme, false);
+    let backward =
+        last_nw_score_row(&row_seq[middle ..], column_seq, scheme, true);
+    let column_len = column_seq.len();
+    let split = (0 ..= column_len)
+        .rev()
+        .max_by_key(|&j| forward[j] + backward[column_len - j])
+        .unwrap_or(0);
+    hirschberg_into(&row_seq[.. middle], &column_seq[.. split], scheme, push);
+    hirschberg_into(&row_seq[middle ..], &column_seq[split ..], scheme, push);
+}
+
+/// Traceback of [`traceback_nw_best_alignment`], pushing the aligned
+/// sequences into the given empty buffers.
+fn traceback_nw_into<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &AlignmentMatrix,
-) -> GlobalAlignmentResult {
+    aligned_row_seq: Vec<L>,
+    aligned_column_seq: Vec<L>,
+) -> GlobalAlignmentResult<L>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
+    #[cfg(feature = "instrument")]
+    let mut span = Span::enter("needleman_wunsch::traceback", 0);
     let mut current_i = matrix.height() - 1;
     let mut current_j = matrix.width() - 1;
 
-    let initial_capacity = row_seq.len() + column_seq.len();
     let mut result = GlobalAlignmentResult {
-        aligned_row_seq: Vec::with_capacity(initial_capacity),
-        aligned_column_seq: Vec::with_capacity(initial_capacity),
+        aligned_row_seq,
+        aligned_column_seq,
         score: matrix[[current_i, current_j]],
         identity_numer: 0,
         identity_denom: 0,
@@ -96,14 +496,14 @@ pub fn traceback_nw_best_alignment(
         let mut maybe_step = None;
         if current_i > 0 {
             let previous_score = matrix[[current_i - 1, current_j]];
-            let penalty = config.gap_penalty;
+            let penalty = scheme.gap_penalty();
             if current_score == previous_score + penalty {
                 maybe_step = Some(TracebackStep::Top);
             }
         }
         if maybe_step.is_none() && current_j > 0 {
             let previous_score = matrix[[current_i, current_j - 1]];
-            let penalty = config.gap_penalty;
+            let penalty = scheme.gap_penalty();
             if current_score == previous_score + penalty {
                 maybe_step = Some(TracebackStep::Left);
             }
@@ -133,26 +533,278 @@ pub fn traceback_nw_best_alignment(
         }
     }
 
-    result.aligned_row_seq.shrink_to_fit();
-    result.aligned_column_seq.shrink_to_fit();
     result.aligned_row_seq.reverse();
     result.aligned_column_seq.reverse();
     result.identity_denom = result.identity_denom.max(1);
+    #[cfg(feature = "instrument")]
+    span.add_cells(result.aligned_row_seq.len());
     result
 }
 
 /// This function fills a Needleman-Wunsch score matrix.
-pub fn compute_nw_matrix(
+pub fn compute_nw_matrix<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> AlignmentMatrix
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    #[cfg(feature = "instrument")]
+    let _span = Span::enter("needleman_wunsch::fill", row_count * column_count);
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    fill_nw_matrix_base(row_seq, column_seq, &scheme, &mut matrix);
+    fill_nw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        None,
+        None,
+    );
+    matrix
+}
+
+/// Fills a Needleman-Wunsch score matrix like [`compute_nw_matrix`], while
+/// also recording the candidates of every cell, for step-by-step replay.
+pub fn compute_nw_matrix_traced<S>(
     row_seq: &[Letter],
     column_seq: &[Letter],
-    config: GlobalAlignmentConfig,
-) -> AlignmentMatrix {
+    scheme: S,
+) -> (AlignmentMatrix, FillTrace)
+where
+    S: ScoringScheme,
+{
     let row_count = row_seq.len() + 1;
     let column_count = column_seq.len() + 1;
     let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
-    fill_nw_matrix_base(row_seq, column_seq, config, &mut matrix);
-    fill_nw_matrix_content(row_seq, column_seq, config, &mut matrix);
-    matrix
+    fill_nw_matrix_base(row_seq, column_seq, &scheme, &mut matrix);
+    let mut trace = FillTrace::new(matrix.clone());
+    fill_nw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        Some(&mut trace),
+        None,
+    );
+    (matrix, trace)
+}
+
+/// Fills a Needleman-Wunsch score matrix like [`compute_nw_matrix`], while
+/// also recording the direction chosen at every cell, for
+/// [`traceback_nw_pointers`].
+pub fn compute_nw_matrix_with_pointers<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> (AlignmentMatrix, TracebackMatrix)
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    let mut pointers = TracebackMatrix::new(row_count, column_count);
+    fill_nw_matrix_base(row_seq, column_seq, &scheme, &mut matrix);
+    for j in 1 .. column_count {
+        pointers[[0, j]] = TracebackPointer::Left;
+    }
+    for i in 1 .. row_count {
+        pointers[[i, 0]] = TracebackPointer::Top;
+    }
+    fill_nw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        None,
+        Some(&mut pointers),
+    );
+    (matrix, pointers)
+}
+
+/// Score arithmetic overflowed while filling a matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScoreOverflow {
+    /// Row of the first cell whose score overflowed.
+    pub i: usize,
+    /// Column of the first cell whose score overflowed.
+    pub j: usize,
+}
+
+impl fmt::Display for ScoreOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "score overflowed at matrix cell ({}, {})", self.i, self.j)
+    }
+}
+
+impl Error for ScoreOverflow {}
+
+/// How score additions behave when they exceed the range of [`Score`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScoreArithmetic {
+    /// Fail with [`ScoreOverflow`] at the first overflowing cell.
+    #[default]
+    Checked,
+    /// Clamp scores at `Score::MIN` and `Score::MAX`. The alignment is still
+    /// returned, but clamped scores no longer tell alignments apart.
+    Saturating,
+}
+
+impl ScoreArithmetic {
+    /// Adds two scores, `None` meaning an overflow to be reported.
+    fn add(self, score: Score, penalty: Score) -> Option<Score> {
+        match self {
+            Self::Checked => score.checked_add(penalty),
+            Self::Saturating => Some(score.saturating_add(penalty)),
+        }
+    }
+}
+
+/// Executes Needleman-Wunsch like [`needleman_wunsch`], but checking every
+/// score addition, so that extreme penalties on long sequences return an
+/// error instead of silently wrapping around.
+pub fn checked_needleman_wunsch<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> Result<GlobalAlignmentResult<L>, ScoreOverflow>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    needleman_wunsch_with_arithmetic(
+        row_seq,
+        column_seq,
+        scheme,
+        ScoreArithmetic::Checked,
+    )
+}
+
+/// Executes Needleman-Wunsch like [`needleman_wunsch`], with score additions
+/// behaving as selected on overflow. Only [`ScoreArithmetic::Checked`] ever
+/// fails.
+pub fn needleman_wunsch_with_arithmetic<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+    arithmetic: ScoreArithmetic,
+) -> Result<GlobalAlignmentResult<L>, ScoreOverflow>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let (matrix, pointers) = compute_nw_matrix_with_arithmetic(
+        row_seq, column_seq, scheme, arithmetic,
+    )?;
+    Ok(traceback_nw_pointers(row_seq, column_seq, &matrix, &pointers))
+}
+
+/// Fills a Needleman-Wunsch score matrix like
+/// [`compute_nw_matrix_with_pointers`], with score additions behaving as
+/// selected on overflow. Checked arithmetic fails at the first cell whose
+/// score overflows.
+///
+/// Traceback follows the pointers, which needs no further arithmetic.
+pub fn compute_nw_matrix_with_arithmetic<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+    arithmetic: ScoreArithmetic,
+) -> Result<(AlignmentMatrix, TracebackMatrix), ScoreOverflow>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    let mut pointers = TracebackMatrix::new(row_count, column_count);
+    let gap_penalty = scheme.gap_penalty();
+    let add = |score, penalty, i, j| {
+        arithmetic.add(score, penalty).ok_or(ScoreOverflow { i, j })
+    };
+    for j in 1 .. column_count {
+        matrix[[0, j]] = add(matrix[[0, j - 1]], gap_penalty, 0, j)?;
+        pointers[[0, j]] = TracebackPointer::Left;
+    }
+    for i in 1 .. row_count {
+        matrix[[i, 0]] = add(matrix[[i - 1, 0]], gap_penalty, i, 0)?;
+        pointers[[i, 0]] = TracebackPointer::Top;
+        for j in 1 .. column_count {
+            let pair = scheme.score(row_seq[i - 1], column_seq[j - 1]);
+            let no_gap_score = add(matrix[[i - 1, j - 1]], pair, i, j)?;
+            let top_score = add(matrix[[i - 1, j]], gap_penalty, i, j)?;
+            let left_score = add(matrix[[i, j - 1]], gap_penalty, i, j)?;
+            let score = no_gap_score.max(top_score).max(left_score);
+            matrix[[i, j]] = score;
+            pointers[[i, j]] = if score == top_score {
+                TracebackPointer::Top
+            } else if score == left_score {
+                TracebackPointer::Left
+            } else {
+                TracebackPointer::Diagonal
+            };
+        }
+    }
+    Ok((matrix, pointers))
+}
+
+/// Computes the alignment by following the pointers recorded by
+/// [`compute_nw_matrix_with_pointers`] from the bottom-right cell, instead of
+/// comparing neighbor scores.
+pub fn traceback_nw_pointers<L>(
+    row_seq: &[L],
+    column_seq: &[L],
+    matrix: &AlignmentMatrix,
+    pointers: &TracebackMatrix,
+) -> GlobalAlignmentResult<L>
+where
+    L: GenericLetter,
+{
+    let mut current_i = row_seq.len();
+    let mut current_j = column_seq.len();
+    let initial_capacity = current_i + current_j;
+    let mut result = GlobalAlignmentResult {
+        aligned_row_seq: Vec::with_capacity(initial_capacity),
+        aligned_column_seq: Vec::with_capacity(initial_capacity),
+        score: matrix[[current_i, current_j]],
+        identity_numer: 0,
+        identity_denom: 0,
+    };
+    loop {
+        match pointers[[current_i, current_j]] {
+            TracebackPointer::Stop => break,
+            TracebackPointer::Diagonal => {
+                current_i -= 1;
+                current_j -= 1;
+                traceback_nw_top_left(
+                    row_seq,
+                    column_seq,
+                    &mut result,
+                    current_i,
+                    current_j,
+                );
+            },
+            TracebackPointer::Top => {
+                current_i -= 1;
+                traceback_nw_top(row_seq, &mut result, current_i);
+            },
+            TracebackPointer::Left => {
+                current_j -= 1;
+                traceback_nw_left(column_seq, &mut result, current_j);
+            },
+        }
+    }
+    result.aligned_row_seq.reverse();
+    result.aligned_column_seq.reverse();
+    result.identity_denom = result.identity_denom.max(1);
+    result
 }
 
 /// This function fills the base "extra" cells of the Needleman-Wunsch score
@@ -161,18 +813,21 @@ pub fn compute_nw_matrix(
 /// i.e. first column 0, gap, 2*gap, 3*gap, etc
 ///
 /// and first row 0, gap, 2*gap, 3*gap, etc
-fn fill_nw_matrix_base(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: GlobalAlignmentConfig,
+pub(crate) fn fill_nw_matrix_base<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &mut AlignmentMatrix,
-) {
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
     for j in 1 ..= column_seq.len() {
-        let score = (j as Score) * config.gap_penalty;
+        let score = (j as Score) * scheme.gap_penalty();
         matrix[[0, j]] = score;
     }
     for i in 1 ..= row_seq.len() {
-        let score = (i as Score) * config.gap_penalty;
+        let score = (i as Score) * scheme.gap_penalty();
         matrix[[i, 0]] = score;
     }
 }
@@ -180,22 +835,36 @@ fn fill_nw_matrix_base(
 /// This function fills the "derived" scores of a Needleman-Wunsch matrix,
 /// given a matrix that already has the "extra" prefix gap cells filled by
 /// [`fill_nw_matrix_base`].
-fn fill_nw_matrix_content(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: GlobalAlignmentConfig,
+pub(crate) fn fill_nw_matrix_content<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &mut AlignmentMatrix,
-) {
+    mut trace: Option<&mut FillTrace>,
+    mut pointers: Option<&mut TracebackMatrix>,
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
     let mut base_i = 0;
     let mut base_j = 0;
     loop {
-        if base_j >= column_seq.len() {
+        if base_i >= row_seq.len() || base_j >= column_seq.len() {
             break;
         }
         for j in base_j .. column_seq.len() {
-            compute_nw_matrix_cell(
-                row_seq, column_seq, config, matrix, base_i, j,
+            let pointer = compute_nw_matrix_cell(
+                row_seq,
+                column_seq,
+                scheme,
+                matrix,
+                base_i,
+                j,
+                trace.as_deref_mut(),
             );
+            if let Some(pointers) = pointers.as_deref_mut() {
+                pointers[[base_i + 1, j + 1]] = pointer;
+            }
         }
         base_i += 1;
 
@@ -203,9 +872,18 @@ fn fill_nw_matrix_content(
             break;
         }
         for i in base_i .. row_seq.len() {
-            compute_nw_matrix_cell(
-                row_seq, column_seq, config, matrix, i, base_j,
+            let pointer = compute_nw_matrix_cell(
+                row_seq,
+                column_seq,
+                scheme,
+                matrix,
+                i,
+                base_j,
+                trace.as_deref_mut(),
             );
+            if let Some(pointers) = pointers.as_deref_mut() {
+                pointers[[i + 1, base_j + 1]] = pointer;
+            }
         }
         base_j += 1;
     }
@@ -213,78 +891,205 @@ fn fill_nw_matrix_content(
 
 /// Computes the score of an individual cell of a Needleman-Wunsch matrix,
 /// assuming that their top-left (pred_i, pred_j), top (pred_i, pred_j + 1) and
-/// left (pred_i + 1, pred_j) cells are already computed and filled.
-fn compute_nw_matrix_cell(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: GlobalAlignmentConfig,
+/// left (pred_i + 1, pred_j) cells are already computed and filled. Returns
+/// the direction the traceback takes from the cell.
+pub(crate) fn compute_nw_matrix_cell<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &mut AlignmentMatrix,
     pred_i: usize,
     pred_j: usize,
-) {
+    trace: Option<&mut FillTrace>,
+) -> TracebackPointer
+where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
     let top_left = matrix[[pred_i, pred_j]];
     let top = matrix[[pred_i, pred_j + 1]];
     let left = matrix[[pred_i + 1, pred_j]];
 
-    let row_letter = row_seq.get(pred_i).normalize_letter();
-    let column_letter = column_seq.get(pred_j).normalize_letter();
-    let no_gap_penalty = if row_letter == column_letter {
-        config.match_penalty
-    } else {
-        config.mismatch_penalty
-    };
+    let row_letter = letter_or_gap(row_seq, pred_i);
+    let column_letter = letter_or_gap(column_seq, pred_j);
+    let no_gap_penalty = scheme.score(row_letter, column_letter);
     let no_gap_score = top_left + no_gap_penalty;
 
+    let gap_penalty = scheme.gap_penalty();
     let best_gap_neighbor = top.max(left);
-    let best_gap_score = best_gap_neighbor + config.gap_penalty;
+    let best_gap_score = best_gap_neighbor + gap_penalty;
 
-    matrix[[pred_i + 1, pred_j + 1]] = best_gap_score.max(no_gap_score);
+    let score = best_gap_score.max(no_gap_score);
+    matrix[[pred_i + 1, pred_j + 1]] = score;
+
+    if let Some(trace) = trace {
+        trace.record(CellStep::new(
+            pred_i + 1,
+            pred_j + 1,
+            no_gap_score,
+            top + gap_penalty,
+            left + gap_penalty,
+            score,
+        ));
+    }
+
+    if score == top + gap_penalty {
+        TracebackPointer::Top
+    } else if score == left + gap_penalty {
+        TracebackPointer::Left
+    } else {
+        TracebackPointer::Diagonal
+    }
 }
 
 /// Registers result of a traceback going to a previous top-left cell in a
 /// Needleman-Wunsch global alignment.
-fn traceback_nw_top_left(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    result: &mut GlobalAlignmentResult,
+fn traceback_nw_top_left<L>(
+    row_seq: &[L],
+    column_seq: &[L],
+    result: &mut GlobalAlignmentResult<L>,
     current_i: usize,
     current_j: usize,
-) {
-    let row_letter = row_seq.get(current_i).normalize_letter();
-    let column_letter = column_seq.get(current_j).normalize_letter();
+) where
+    L: GenericLetter,
+{
+    let row_letter = letter_or_gap(row_seq, current_i);
+    let column_letter = letter_or_gap(column_seq, current_j);
     result.aligned_row_seq.push(row_letter);
     result.aligned_column_seq.push(column_letter);
     result.identity_denom += 1;
-    if row_letter == column_letter && row_letter != GAP {
+    if row_letter == column_letter && row_letter != L::GAP {
         result.identity_numer += 1;
     }
 }
 
 /// Registers result of a traceback going to a previous top cell in a
 /// Needleman-Wunsch global alignment.
-fn traceback_nw_top(
-    row_seq: &[Letter],
-    result: &mut GlobalAlignmentResult,
+fn traceback_nw_top<L>(
+    row_seq: &[L],
+    result: &mut GlobalAlignmentResult<L>,
     current_i: usize,
-) {
-    let row_letter = row_seq.get(current_i).normalize_letter();
+) where
+    L: GenericLetter,
+{
+    let row_letter = letter_or_gap(row_seq, current_i);
     result.aligned_row_seq.push(row_letter);
-    result.aligned_column_seq.push(GAP);
+    result.aligned_column_seq.push(L::GAP);
 }
 
 /// Registers result of a traceback going to a previous left cell in a
 /// Needleman-Wunsch global alignment.
-fn traceback_nw_left(
-    column_seq: &[Letter],
-    result: &mut GlobalAlignmentResult,
+fn traceback_nw_left<L>(
+    column_seq: &[L],
+    result: &mut GlobalAlignmentResult<L>,
     current_j: usize,
-) {
-    let column_letter = column_seq.get(current_j).normalize_letter();
-    result.aligned_row_seq.push(GAP);
+) where
+    L: GenericLetter,
+{
+    let column_letter = letter_or_gap(column_seq, current_j);
+    result.aligned_row_seq.push(L::GAP);
     result.aligned_column_seq.push(column_letter);
 }
 
+/// Needleman-Wunsch alignment of sequences that keep growing.
+///
+/// Appending letters to either sequence only computes the new cells of the
+/// matrix, instead of refilling it from scratch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalNeedlemanWunsch {
+    row_seq: Vec<Letter>,
+    column_seq: Vec<Letter>,
+    config: GlobalAlignmentConfig,
+    matrix: AlignmentMatrix,
+}
+
+impl IncrementalNeedlemanWunsch {
+    /// Starts with two empty sequences.
+    pub fn new(config: GlobalAlignmentConfig) -> Self {
+        Self {
+            row_seq: Vec::new(),
+            column_seq: Vec::new(),
+            config,
+            matrix: AlignmentMatrix::zeroed(1, 1),
+        }
+    }
+
+    /// Appends letters to the sequence associated with a row display.
+    pub fn extend_row_seq(&mut self, letters: &[Letter]) {
+        self.row_seq.extend_from_slice(letters);
+        self.fill_new_cells();
+    }
+
+    /// Appends letters to the sequence associated with a column display.
+    pub fn extend_column_seq(&mut self, letters: &[Letter]) {
+        self.column_seq.extend_from_slice(letters);
+        self.fill_new_cells();
+    }
+
+    /// The sequence associated with a row display, so far.
+    pub fn row_seq(&self) -> &[Letter] {
+        &self.row_seq
+    }
+
+    /// The sequence associated with a column display, so far.
+    pub fn column_seq(&self) -> &[Letter] {
+        &self.column_seq
+    }
+
+    /// The score matrix of the sequences so far.
+    pub fn matrix(&self) -> &AlignmentMatrix {
+        &self.matrix
+    }
+
+    /// Score of the global alignment of the sequences so far.
+    pub fn score(&self) -> Score {
+        self.matrix[[self.row_seq.len(), self.column_seq.len()]]
+    }
+
+    /// Global alignment of the sequences so far.
+    pub fn alignment(&self) -> GlobalAlignmentResult {
+        traceback_nw_best_alignment(
+            &self.row_seq,
+            &self.column_seq,
+            self.config,
+            &self.matrix,
+        )
+    }
+
+    /// Computes the cells that the last extension added to the matrix.
+    fn fill_new_cells(&mut self) {
+        let old_height = self.matrix.height();
+        let old_width = self.matrix.width();
+        let height = self.row_seq.len() + 1;
+        let width = self.column_seq.len() + 1;
+        self.matrix.grow(height, width);
+        for i in old_height .. height {
+            self.matrix[[i, 0]] = (i as Score) * self.config.gap_penalty;
+        }
+        for j in old_width .. width {
+            self.matrix[[0, j]] = (j as Score) * self.config.gap_penalty;
+        }
+        for i in 1 .. height {
+            let first_j = if i < old_height { old_width } else { 1 };
+            for j in first_j .. width {
+                compute_nw_matrix_cell(
+                    &self.row_seq,
+                    &self.column_seq,
+                    &self.config,
+                    &mut self.matrix,
+                    i - 1,
+                    j - 1,
+                    None,
+                );
+            }
+        }
+    }
+}
+
 /// Pretty print formatting of the results, as in a report.
+///
+/// Each wrapped line is surrounded by the coordinates (starting from 1) of its
+/// first and last letters in the original sequences.
 #[derive(Debug, Clone, Copy)]
 pub struct PrettyPrint<'a> {
     /// Print name of the sequence that was associated with a row display.
@@ -295,71 +1100,179 @@ pub struct PrettyPrint<'a> {
     pub result: &'a GlobalAlignmentResult,
     /// Maximum width in terms of characters.
     pub max_width: usize,
+    /// Symbols and layout of the report.
+    pub style: PrettyStyle<'a>,
 }
 
 impl<'a> fmt::Display for PrettyPrint<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let identity = (100_000.0 * self.result.identity()).round() / 1000.0;
-        write!(f, "# sequence above : {}\n", self.row_seq_name)?;
-        write!(f, "# sequence below : {}\n", self.column_seq_name)?;
-        write!(f, "# identity       : {}%\n", identity)?;
-        write!(f, "# score          : {}\n", self.result.score)?;
-        write!(f, "\n")?;
-
-        let length = self
-            .result
-            .aligned_row_seq
-            .len()
-            .max(self.result.aligned_column_seq.len());
-        let mut i = 0;
-        while i < length {
-            let block_start = i;
-            let block_end = length.min(block_start + self.max_width);
-            write!(f, "# block : {block_start}..{block_end}\n")?;
-            for k in block_start .. block_end {
-                write!(
-                    f,
-                    "{}",
-                    self.result.aligned_row_seq.get(k).normalize_letter()
-                )?;
-            }
-            write!(f, "\n")?;
-            for k in block_start .. block_end {
-                write!(
-                    f,
-                    "{}",
-                    self.result.aligned_column_seq.get(k).normalize_letter()
-                )?;
-            }
+        if self.style.header >= HeaderVerbosity::Brief {
+            write!(f, "# sequence above : {}\n", self.row_seq_name)?;
+            write!(f, "# sequence below : {}\n", self.column_seq_name)?;
+        }
+        if self.style.header >= HeaderVerbosity::Full {
+            write!(f, "# identity       : {}%\n", identity)?;
+            write!(f, "# score          : {}\n", self.result.score)?;
+        }
+        if self.style.header >= HeaderVerbosity::Brief {
             write!(f, "\n")?;
-
-            let row_block =
-                &self.result.aligned_row_seq[block_start .. block_end];
-            let column_block =
-                &self.result.aligned_column_seq[block_start .. block_end];
-            let mut identity_iter = row_block.iter().zip(column_block);
-            while let Some(k) =
-                (&mut identity_iter).position(|(row_letter, column_letter)| {
-                    row_letter == column_letter
-                })
-            {
-                for _ in 0 .. k {
-                    write!(f, " ")?;
-                }
-                write!(f, "*")?;
-            }
-            write!(f, "\n\n")?;
-            i = block_end;
         }
-        Ok(())
+
+        write_blocks(
+            f,
+            &self.result.aligned_row_seq,
+            &self.result.aligned_column_seq,
+            0,
+            0,
+            self.max_width,
+            &self.style,
+        )
+    }
+}
+
+impl WriteReport for PrettyPrint<'_> {}
+
+/// Formats the result as an EMBOSS `needle` report in `pair` format, for
+/// parsers of EMBOSS output. The sequence associated with a row display is
+/// the first one.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbossPrint<'a> {
+    /// Print name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Print name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished global alignment result.
+    pub result: &'a GlobalAlignmentResult,
+}
+
+impl fmt::Display for EmbossPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_emboss_pair(
+            f,
+            "needle",
+            [self.row_seq_name, self.column_seq_name],
+            [&self.result.aligned_row_seq, &self.result.aligned_column_seq],
+            [0, 0],
+            self.result.score,
+        )
+    }
+}
+
+impl WriteReport for EmbossPrint<'_> {}
+
+/// Formats the result in CLUSTAL W format, readable by alignment viewers such
+/// as Jalview.
+#[derive(Debug, Clone, Copy)]
+pub struct ClustalPrint<'a> {
+    /// Print name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Print name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished global alignment result.
+    pub result: &'a GlobalAlignmentResult,
+}
+
+impl fmt::Display for ClustalPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_clustal_pair(
+            f,
+            [self.row_seq_name, self.column_seq_name],
+            [&self.result.aligned_row_seq, &self.result.aligned_column_seq],
+        )
     }
 }
 
+impl WriteReport for ClustalPrint<'_> {}
+
+/// Formats the result as two FASTA records of the aligned sequences, gaps
+/// included.
+#[derive(Debug, Clone, Copy)]
+pub struct FastaPrint<'a> {
+    /// Name of the record of the sequence associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Name of the record of the sequence associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished global alignment result.
+    pub result: &'a GlobalAlignmentResult,
+    /// Maximum number of letters per line, or `None` to write each sequence
+    /// on a single line.
+    pub line_width: Option<usize>,
+}
+
+impl fmt::Display for FastaPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fasta_pair(
+            f,
+            [self.row_seq_name, self.column_seq_name],
+            [&self.result.aligned_row_seq, &self.result.aligned_column_seq],
+            self.line_width,
+        )
+    }
+}
+
+impl WriteReport for FastaPrint<'_> {}
+
 #[cfg(test)]
 mod test {
-    use crate::global::GlobalAlignmentResult;
+    use crate::{
+        generate::{random_dna, Rng},
+        global::GlobalAlignmentResult,
+        letter::GAP,
+        report::{HeaderVerbosity, MarkerPosition, PrettyStyle, WriteReport},
+        score::Score,
+    };
+
+    use super::{
+        checked_needleman_wunsch,
+        compute_nw_matrix,
+        compute_nw_matrix_traced,
+        hirschberg_needleman_wunsch,
+        needleman_wunsch,
+        needleman_wunsch_at_least,
+        needleman_wunsch_with_arithmetic,
+        nw_score_only,
+        ClustalPrint,
+        EmbossPrint,
+        FastaPrint,
+        GlobalAlignmentConfig,
+        IncrementalNeedlemanWunsch,
+        PrettyPrint,
+        ScoreArithmetic,
+        ScoreOverflow,
+    };
+
+    #[test]
+    fn overflowing_scores_are_reported_or_clamped() {
+        let mut rng = Rng::new(114);
+        let row_seq = random_dna(30, &mut rng);
+        let column_seq = random_dna(25, &mut rng);
+        let config = GlobalAlignmentConfig::default();
+        assert_eq!(
+            checked_needleman_wunsch(&row_seq, &column_seq, config),
+            Ok(needleman_wunsch(&row_seq, &column_seq, config))
+        );
+
+        let extreme = GlobalAlignmentConfig {
+            gap_penalty: Score::MIN / 2,
+            ..GlobalAlignmentConfig::default()
+        };
+        assert_eq!(
+            checked_needleman_wunsch(&row_seq, &column_seq, extreme),
+            Err(ScoreOverflow { i: 0, j: 3 })
+        );
 
-    use super::{needleman_wunsch, GlobalAlignmentConfig};
+        let saturated = needleman_wunsch_with_arithmetic(
+            &row_seq,
+            &column_seq,
+            extreme,
+            ScoreArithmetic::Saturating,
+        )
+        .unwrap();
+        assert_eq!(saturated.score, Score::MIN);
+        assert_eq!(saturated.aligned_row_seq.len(), 30);
+        assert!(!saturated.aligned_row_seq.contains(&GAP));
+    }
 
     #[test]
     fn simple_what_why_with_gap() {
@@ -414,4 +1327,227 @@ mod test {
 
         assert_eq!(actual_result, expected_result);
     }
+
+    #[test]
+    fn traced_fill_replays_to_same_matrix() {
+        let input_row_seq = ['W', 'H', 'A', 'T'];
+        let input_column_seq = ['W', 'H', 'Y'];
+        let input_config = GlobalAlignmentConfig::default();
+
+        let expected_matrix =
+            compute_nw_matrix(&input_row_seq, &input_column_seq, input_config);
+        let (actual_matrix, trace) = compute_nw_matrix_traced(
+            &input_row_seq,
+            &input_column_seq,
+            input_config,
+        );
+
+        assert_eq!(actual_matrix, expected_matrix);
+        assert_eq!(trace.steps.len(), 12);
+        assert_eq!(trace.snapshot(trace.steps.len()), expected_matrix);
+    }
+
+    #[test]
+    fn pretty_print_rules_original_coordinates() {
+        let result = GlobalAlignmentResult {
+            aligned_row_seq: "WHAT-IS".chars().collect(),
+            aligned_column_seq: "W-ATHIS".chars().collect(),
+            score: 0,
+            identity_numer: 5,
+            identity_denom: 7,
+        };
+        let pretty_print = PrettyPrint {
+            row_seq_name: "a",
+            column_seq_name: "b",
+            result: &result,
+            max_width: 4,
+            style: PrettyStyle::default(),
+        };
+
+        let output = pretty_print.to_string();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[6], "1 WHAT 4");
+        assert_eq!(lines[7], "1 W-AT 3");
+        assert_eq!(lines[11], "5 -IS 6");
+        assert_eq!(lines[12], "4 HIS 6");
+    }
+
+    #[test]
+    fn pretty_print_custom_style() {
+        let result = GlobalAlignmentResult {
+            aligned_row_seq: "GATTACA".chars().collect(),
+            aligned_column_seq: "GCT-ACA".chars().collect(),
+            score: 0,
+            identity_numer: 5,
+            identity_denom: 7,
+        };
+        let pretty_print = PrettyPrint {
+            row_seq_name: "a",
+            column_seq_name: "b",
+            result: &result,
+            max_width: 80,
+            style: PrettyStyle {
+                match_marker: '|',
+                mismatch_marker: '.',
+                gap_marker: ' ',
+                marker_position: MarkerPosition::Between,
+                block_separator: "",
+                header: HeaderVerbosity::Quiet,
+                gap_symbol: '.',
+            },
+        };
+
+        assert_eq!(
+            pretty_print.to_string(),
+            "1 GATTACA 7\n  |.| |||\n1 GCT.ACA 6\n"
+        );
+    }
+
+    #[test]
+    fn write_report_matches_display() {
+        let result = needleman_wunsch(
+            &['G', 'A', 'T', 'T', 'A', 'C', 'A'],
+            &['G', 'C', 'A', 'T', 'G', 'C', 'A'],
+            GlobalAlignmentConfig::default(),
+        );
+        let pretty_print = PrettyPrint {
+            row_seq_name: "a",
+            column_seq_name: "b",
+            result: &result,
+            max_width: 3,
+            style: PrettyStyle::default(),
+        };
+
+        let mut buf = Vec::new();
+        pretty_print.write_report(&mut buf).unwrap();
+
+        assert_eq!(String::from_utf8(buf).unwrap(), pretty_print.to_string());
+    }
+
+    #[test]
+    fn incremental_extension_matches_full_alignment() {
+        let config = GlobalAlignmentConfig::default();
+        let mut incremental = IncrementalNeedlemanWunsch::new(config);
+        incremental.extend_row_seq(&['G', 'A', 'T']);
+        incremental.extend_column_seq(&['G', 'C']);
+        incremental.extend_row_seq(&['T', 'A', 'C', 'A']);
+        incremental.extend_column_seq(&['A', 'T', 'G', 'C', 'A']);
+
+        let row_seq = ['G', 'A', 'T', 'T', 'A', 'C', 'A'];
+        let column_seq = ['G', 'C', 'A', 'T', 'G', 'C', 'A'];
+        assert_eq!(
+            incremental.matrix(),
+            &compute_nw_matrix(&row_seq, &column_seq, config)
+        );
+        assert_eq!(
+            incremental.alignment(),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+    }
+
+    #[test]
+    fn threshold_abandons_dissimilar_pairs() {
+        let config = GlobalAlignmentConfig::default();
+        let row_seq = ['G', 'A', 'T', 'T', 'A', 'C', 'A'];
+        let similar = ['G', 'A', 'T', 'C', 'A', 'C', 'A'];
+        let dissimilar = ['C', 'C', 'C', 'C', 'C', 'C', 'C', 'C', 'C', 'C'];
+
+        assert_eq!(
+            needleman_wunsch_at_least(&row_seq, &similar, config, 5),
+            Some(needleman_wunsch(&row_seq, &similar, config))
+        );
+        assert_eq!(
+            needleman_wunsch_at_least(&row_seq, &similar, config, 6),
+            None
+        );
+        assert_eq!(
+            needleman_wunsch_at_least(&row_seq, &dissimilar, config, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn hirschberg_reaches_the_optimal_score() {
+        let mut rng = Rng::new(57);
+        let config = GlobalAlignmentConfig::default();
+        for (row_len, column_len) in [(0, 5), (1, 7), (45, 30), (20, 61)] {
+            let row_seq = random_dna(row_len, &mut rng);
+            let column_seq = random_dna(column_len, &mut rng);
+            let result =
+                hirschberg_needleman_wunsch(&row_seq, &column_seq, config);
+            let score = needleman_wunsch(&row_seq, &column_seq, config).score;
+            assert_eq!(result.score, score);
+            assert_eq!(nw_score_only(&row_seq, &column_seq, config), score);
+            let ungapped = |seq: &[char]| -> Vec<char> {
+                seq.iter().copied().filter(|&letter| letter != GAP).collect()
+            };
+            assert_eq!(ungapped(&result.aligned_row_seq), row_seq);
+            assert_eq!(ungapped(&result.aligned_column_seq), column_seq);
+        }
+    }
+
+    #[test]
+    fn emboss_clustal_and_fasta_reports() {
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GATCCA".chars().collect();
+        let result = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+        let report = EmbossPrint {
+            row_seq_name: "first",
+            column_seq_name: "a_very_long_name",
+            result: &result,
+        }
+        .to_string();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[1], "# Program: needle");
+        assert_eq!(lines[10], "# 2: a_very_long_name");
+        assert_eq!(lines[13], "# Identity:       5/7 (71.4%)");
+        assert_eq!(lines[15], "# Gaps:           1/7 (14.3%)");
+        assert_eq!(lines[16], format!("# Score: {}.0", result.score));
+        assert_eq!(lines[21], "first              1 GATTACA      7");
+        assert_eq!(lines[22], "                     |||. ||");
+        assert_eq!(lines[23], "a_very_long_n      1 GATC-CA      6");
+        assert_eq!(lines[26], lines[27]);
+        assert_eq!(lines.len(), 28);
+
+        let clustal = ClustalPrint {
+            row_seq_name: "first",
+            column_seq_name: "second",
+            result: &result,
+        };
+        assert_eq!(
+            clustal.to_string(),
+            "CLUSTAL W multiple sequence alignment\n\n\nfirst       \
+             GATTACA\nsecond      GATC-CA\n            ***  **\n"
+        );
+
+        let fasta = FastaPrint {
+            row_seq_name: "first",
+            column_seq_name: "second",
+            result: &result,
+            line_width: Some(4),
+        };
+        assert_eq!(
+            fasta.to_string(),
+            ">first\nGATT\nACA\n>second\nGATC\n-CA\n"
+        );
+    }
+
+    #[test]
+    fn empty_row_sequence_fills_only_the_base_row() {
+        let column_seq = ['A', 'C'];
+        let config = GlobalAlignmentConfig::default();
+
+        let matrix = compute_nw_matrix(&[], &column_seq, config);
+        assert_eq!(matrix.height(), 1);
+        assert_eq!(matrix[[0, 2]], -4);
+        let result = needleman_wunsch(&[], &column_seq, config);
+        assert_eq!(result.aligned_row_seq, vec![GAP, GAP]);
+        assert_eq!(result.aligned_column_seq, column_seq);
+        assert_eq!(result.score, -4);
+    }
 }
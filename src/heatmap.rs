@@ -0,0 +1,302 @@
+use std::fmt;
+
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
+use crate::{
+    distance::align_all_pairs,
+    global::GlobalAlignmentConfig,
+    letter::Letter,
+    report::WriteReport,
+};
+
+/// Pairwise identities of a set of sequences, from all-vs-all global
+/// alignments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdentityMatrix {
+    names: Vec<String>,
+    values: Vec<f64>,
+}
+
+impl IdentityMatrix {
+    /// Globally aligns every pair of named sequences and collects their
+    /// identities. The matrix is symmetric with ones in the diagonal.
+    pub fn compute(
+        seqs: &[(&str, &[Letter])],
+        config: GlobalAlignmentConfig,
+    ) -> Self {
+        #[cfg(feature = "instrument")]
+        let mut span = Span::enter("identity_matrix", 0);
+        let size = seqs.len();
+        let letters: Vec<&[Letter]> =
+            seqs.iter().map(|(_, seq)| *seq).collect();
+        let mut values = vec![1.0; size * size];
+        align_all_pairs(&letters, &config, |i, j, result| {
+            let identity = result.identity();
+            values[i * size + j] = identity;
+            values[j * size + i] = identity;
+            #[cfg(feature = "instrument")]
+            span.add_item();
+        });
+        let names = seqs.iter().map(|(name, _)| name.to_string()).collect();
+        Self { names, values }
+    }
+
+    /// Number of sequences.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether there are no sequences at all.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Names of the sequences, in input order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Identity between the `i`-th and `j`-th sequences, from 0 to 1.
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        if i >= self.len() || j >= self.len() {
+            None
+        } else {
+            Some(self.values[i * self.len() + j])
+        }
+    }
+}
+
+/// Linear color scale, from the color of the lowest value of a heatmap to the
+/// color of the highest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScale {
+    /// RGB color of the lowest value.
+    pub low: (u8, u8, u8),
+    /// RGB color of the highest value.
+    pub high: (u8, u8, u8),
+}
+
+impl ColorScale {
+    /// Black for the lowest value up to white for the highest one.
+    pub const GRAYSCALE: Self = Self { low: (0, 0, 0), high: (255, 255, 255) };
+
+    /// Interpolates the color at a fraction from 0 (low) to 1 (high).
+    pub fn color(&self, fraction: f64) -> (u8, u8, u8) {
+        let level = fraction.clamp(0.0, 1.0);
+        let mix = |low: u8, high: u8| {
+            (f64::from(low) + level * (f64::from(high) - f64::from(low)))
+                .round() as u8
+        };
+        (
+            mix(self.low.0, self.high.0),
+            mix(self.low.1, self.high.1),
+            mix(self.low.2, self.high.2),
+        )
+    }
+}
+
+impl Default for ColorScale {
+    /// Blue for low values up to red for high values, as in the identity
+    /// heatmaps.
+    fn default() -> Self {
+        Self { low: (0, 64, 255), high: (255, 64, 0) }
+    }
+}
+
+/// Renders an identity matrix as a grid of percentages on colored
+/// backgrounds, using 24-bit ANSI escapes, for terminals.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalHeatmap<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a IdentityMatrix,
+    /// Whether to emit color escapes; without them, only numbers are printed.
+    pub color: bool,
+}
+
+impl fmt::Display for TerminalHeatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .matrix
+            .names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        write!(f, "{:name_width$}", "")?;
+        for j in 0 .. self.matrix.len() {
+            write!(f, " {j:>4}")?;
+        }
+        writeln!(f)?;
+        for (i, name) in self.matrix.names.iter().enumerate() {
+            write!(f, "{name:<name_width$}")?;
+            for j in 0 .. self.matrix.len() {
+                let identity = self.matrix.values[i * self.matrix.len() + j];
+                let percent = (100.0 * identity).round();
+                if self.color {
+                    let (red, green, blue) =
+                        ColorScale::default().color(identity);
+                    write!(
+                        f,
+                        " \x1b[48;2;{red};{green};{blue}m{percent:>4}\x1b[0m"
+                    )?;
+                } else {
+                    write!(f, " {percent:>4}")?;
+                }
+            }
+            writeln!(f, " {i}")?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for TerminalHeatmap<'_> {}
+
+/// Renders an identity matrix as CSV, with a header line of names and one
+/// line per sequence.
+#[derive(Debug, Clone, Copy)]
+pub struct CsvHeatmap<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a IdentityMatrix,
+}
+
+impl fmt::Display for CsvHeatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "name")?;
+        for name in &self.matrix.names {
+            write!(f, ",{}", csv_field(name))?;
+        }
+        writeln!(f)?;
+        for (i, name) in self.matrix.names.iter().enumerate() {
+            write!(f, "{}", csv_field(name))?;
+            for j in 0 .. self.matrix.len() {
+                write!(
+                    f,
+                    ",{}",
+                    self.matrix.values[i * self.matrix.len() + j]
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for CsvHeatmap<'_> {}
+
+/// Quotes a CSV field if it has special characters.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Renders an identity matrix as a standalone SVG image.
+#[derive(Debug, Clone, Copy)]
+pub struct SvgHeatmap<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a IdentityMatrix,
+    /// Side of each cell, in pixels.
+    pub cell_size: usize,
+}
+
+impl fmt::Display for SvgHeatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = self.cell_size;
+        let label_width = 8 * self
+            .matrix
+            .names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let side = label_width + cell * self.matrix.len();
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{side}\" \
+             height=\"{side}\" font-family=\"monospace\" font-size=\"12\">"
+        )?;
+        for (i, name) in self.matrix.names.iter().enumerate() {
+            let offset = label_width + i * cell + cell / 2;
+            let name = xml_escape(name);
+            writeln!(
+                f,
+                "<text x=\"0\" y=\"{offset}\" \
+                 dominant-baseline=\"middle\">{name}</text>"
+            )?;
+            writeln!(
+                f,
+                "<text transform=\"translate({offset},0) rotate(90)\" \
+                 dominant-baseline=\"middle\">{name}</text>"
+            )?;
+        }
+        for i in 0 .. self.matrix.len() {
+            for j in 0 .. self.matrix.len() {
+                let identity = self.matrix.values[i * self.matrix.len() + j];
+                let (red, green, blue) = ColorScale::default().color(identity);
+                let x = label_width + j * cell;
+                let y = label_width + i * cell;
+                writeln!(
+                    f,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{cell}\" \
+                     height=\"{cell}\" \
+                     fill=\"rgb({red},{green},{blue})\"><title>{:.1}%</\
+                     title></rect>",
+                    100.0 * identity
+                )?;
+            }
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+impl WriteReport for SvgHeatmap<'_> {}
+
+/// Escapes text for XML content.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::GlobalAlignmentConfig;
+
+    use super::{CsvHeatmap, IdentityMatrix, TerminalHeatmap};
+
+    #[test]
+    fn symmetric_identities_and_csv() {
+        let first: Vec<char> = "GATTACA".chars().collect();
+        let second: Vec<char> = "GATTACC".chars().collect();
+        let matrix = IdentityMatrix::compute(
+            &[("first", &first), ("second", &second)],
+            GlobalAlignmentConfig::default(),
+        );
+
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(matrix.get(0, 0), Some(1.0));
+        assert_eq!(matrix.get(0, 1), matrix.get(1, 0));
+        assert_eq!(matrix.get(2, 0), None);
+
+        let identity = matrix.get(0, 1).unwrap();
+        assert_eq!(
+            CsvHeatmap { matrix: &matrix }.to_string(),
+            format!(
+                "name,first,second\nfirst,1,{identity}\nsecond,{identity},1\n"
+            )
+        );
+        let plain = TerminalHeatmap { matrix: &matrix, color: false };
+        assert!(plain.to_string().starts_with("          0    1\n"));
+
+        let greek = IdentityMatrix::compute(
+            &[("αβγ", &first), ("ab", &second)],
+            GlobalAlignmentConfig::default(),
+        );
+        let plain = TerminalHeatmap { matrix: &greek, color: false };
+        let lines: Vec<String> =
+            plain.to_string().lines().map(str::to_owned).collect();
+        assert_eq!(lines[0], "       0    1");
+        assert!(lines[2].starts_with("ab   "));
+        assert_eq!(lines[1].chars().count(), lines[2].chars().count());
+    }
+}
@@ -0,0 +1,331 @@
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
+use crate::{
+    banded::{banded_needleman_wunsch, Band},
+    global::{GlobalAlignmentConfig, GlobalAlignmentResult},
+    index::KmerIndex,
+    letter::Letter,
+    score::Score,
+    xdrop::xdrop_extend,
+};
+
+/// Parameters of the seed-and-extend alignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeuristicConfig {
+    /// Length of the exact k-mer matches used as seeds.
+    pub k: usize,
+    /// K-mers occurring more often than this in the row sequence are
+    /// repeats and never used as seeds.
+    pub max_occurrences: usize,
+    /// Largest distance, in either sequence, between consecutive seeds of a
+    /// chain.
+    pub max_gap: usize,
+    /// Diagonals added on both sides of the chain diagonals for the banded
+    /// extension.
+    pub padding: usize,
+    /// How far below the best score the extension beyond the chain ends may
+    /// drop before it stops.
+    pub x_drop: Score,
+    /// Scoring of the banded extension.
+    pub alignment: GlobalAlignmentConfig,
+}
+
+impl Default for HeuristicConfig {
+    fn default() -> Self {
+        Self {
+            k: 15,
+            max_occurrences: 32,
+            max_gap: 1000,
+            padding: 16,
+            x_drop: 20,
+            alignment: GlobalAlignmentConfig::default(),
+        }
+    }
+}
+
+/// Exact match shared by both sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seed {
+    /// Start of the match in the row sequence.
+    pub row_start: usize,
+    /// Start of the match in the column sequence.
+    pub column_start: usize,
+    /// Number of matching letters.
+    pub len: usize,
+}
+
+impl Seed {
+    /// Diagonal of the match, the column position minus the row position.
+    pub fn diagonal(&self) -> isize {
+        self.column_start as isize - self.row_start as isize
+    }
+
+    /// Exclusive end of the match in the row sequence.
+    pub fn row_end(&self) -> usize {
+        self.row_start + self.len
+    }
+
+    /// Exclusive end of the match in the column sequence.
+    pub fn column_end(&self) -> usize {
+        self.column_start + self.len
+    }
+}
+
+/// Alignment of the region spanned by the best seed chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeuristicAlignment {
+    /// Start of the aligned region in the row sequence.
+    pub row_start: usize,
+    /// Exclusive end of the aligned region in the row sequence.
+    pub row_end: usize,
+    /// Start of the aligned region in the column sequence.
+    pub column_start: usize,
+    /// Exclusive end of the aligned region in the column sequence.
+    pub column_end: usize,
+    /// Seeds of the chain, in sequence order.
+    pub chain: Vec<Seed>,
+    /// Banded global alignment of the regions.
+    pub result: GlobalAlignmentResult,
+}
+
+/// Finds the exact matches of at least `k` letters between both sequences,
+/// each k-mer hit merged with the overlapping hits of its diagonal. K-mers
+/// occurring more than `max_occurrences` times in the row sequence are
+/// skipped. Seeds are sorted by row, then column.
+pub fn find_seeds(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    k: usize,
+    max_occurrences: usize,
+) -> Vec<Seed> {
+    let index = KmerIndex::new(row_seq, k);
+    let k = index.k();
+    let mut hits: Vec<(isize, usize)> = index
+        .lookup(column_seq, max_occurrences)
+        .iter()
+        .map(|hit| (-hit.diagonal(), hit.target_position))
+        .collect();
+    hits.sort_unstable();
+
+    let mut seeds: Vec<Seed> = Vec::new();
+    for (diagonal, i) in hits {
+        match seeds.last_mut() {
+            Some(seed)
+                if seed.diagonal() == diagonal && i <= seed.row_end() =>
+            {
+                seed.len = seed.len.max(i + k - seed.row_start);
+            },
+            _ => seeds.push(Seed {
+                row_start: i,
+                column_start: (i as isize + diagonal) as usize,
+                len: k,
+            }),
+        }
+    }
+    seeds.sort_unstable();
+    seeds
+}
+
+/// Chooses the best colinear chain of seeds, as sorted by [`find_seeds`].
+///
+/// A chain scores the letters its seeds cover, minus the diagonal shift
+/// between consecutive seeds. Consecutive seeds must advance in both
+/// sequences and be at most `max_gap` letters apart. Ties go to the chain
+/// ending first.
+pub fn chain_seeds(seeds: &[Seed], max_gap: usize) -> Vec<Seed> {
+    let longest = seeds.iter().map(|seed| seed.len).max().unwrap_or(0);
+    let mut scores: Vec<isize> = Vec::with_capacity(seeds.len());
+    let mut previous: Vec<Option<usize>> = Vec::with_capacity(seeds.len());
+    for (b, seed) in seeds.iter().enumerate() {
+        let mut best = (seed.len as isize, None);
+        for a in (0 .. b).rev() {
+            let before = seeds[a];
+            if seed.row_start - before.row_start > max_gap + longest {
+                break;
+            }
+            if before.row_start >= seed.row_start
+                || before.column_start >= seed.column_start
+                || seed.row_start.saturating_sub(before.row_end()) > max_gap
+                || seed.column_start.saturating_sub(before.column_end())
+                    > max_gap
+            {
+                continue;
+            }
+            let overlap = before
+                .row_end()
+                .saturating_sub(seed.row_start)
+                .max(before.column_end().saturating_sub(seed.column_start));
+            if overlap >= seed.len {
+                continue;
+            }
+            let shift = (seed.diagonal() - before.diagonal()).abs();
+            let score = scores[a] + (seed.len - overlap) as isize - shift;
+            if score > best.0 {
+                best = (score, Some(a));
+            }
+        }
+        scores.push(best.0);
+        previous.push(best.1);
+    }
+
+    let mut chain = Vec::new();
+    let mut current = (0 .. seeds.len()).rev().max_by_key(|&b| scores[b]);
+    while let Some(b) = current {
+        chain.push(seeds[b]);
+        current = previous[b];
+    }
+    chain.reverse();
+    chain
+}
+
+/// Aligns two sequences by seed and extend: exact k-mer matches are found
+/// with an index of the row sequence, the best colinear chain of them is
+/// selected, its ends are extended outward with [`xdrop_extend`], and the
+/// region spanned is aligned with the banded Needleman-Wunsch, in a band
+/// around the chain diagonals.
+///
+/// Memory and time grow with the region length times the band width rather
+/// than with the product of the sequence lengths, so megabase sequences can
+/// be compared. Returns `None` when the sequences share no seed.
+pub fn seed_and_extend(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: HeuristicConfig,
+) -> Option<HeuristicAlignment> {
+    #[cfg(feature = "instrument")]
+    let mut span = Span::enter("seed_and_extend", 0);
+    let seeds =
+        find_seeds(row_seq, column_seq, config.k, config.max_occurrences);
+    let chain = chain_seeds(&seeds, config.max_gap);
+    let (first, last) = (chain.first()?, chain.last()?);
+    let before = xdrop_extend(
+        &row_seq[.. first.row_start],
+        &column_seq[.. first.column_start],
+        (first.row_start, first.column_start),
+        config.alignment,
+        config.x_drop,
+    )?;
+    let after = xdrop_extend(
+        &row_seq[last.row_end() ..],
+        &column_seq[last.column_end() ..],
+        (0, 0),
+        config.alignment,
+        config.x_drop,
+    )?;
+    let (row_start, row_end) =
+        (before.row_start, last.row_end() + after.row_end);
+    let (column_start, column_end) =
+        (before.column_start, last.column_end() + after.column_end);
+
+    let region_diagonal = column_start as isize - row_start as isize;
+    let end_diagonal = column_end as isize - row_end as isize;
+    let padding = config.padding as isize;
+    let diagonals = || {
+        chain.iter().map(Seed::diagonal).chain([region_diagonal, end_diagonal])
+    };
+    let lo = diagonals().min()? - region_diagonal;
+    let hi = diagonals().max()? - region_diagonal;
+    let band = Band { lo: lo - padding, hi: hi + padding };
+    let result = banded_needleman_wunsch(
+        &row_seq[row_start .. row_end],
+        &column_seq[column_start .. column_end],
+        config.alignment,
+        band,
+    );
+    #[cfg(feature = "instrument")]
+    span.add_cells((row_end - row_start + 1) * band.width());
+    Some(HeuristicAlignment {
+        row_start,
+        row_end,
+        column_start,
+        column_end,
+        chain,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng, DNA},
+        global::needleman_wunsch,
+        mutate::{mutate, MutationRates},
+    };
+
+    use super::{find_seeds, seed_and_extend, HeuristicConfig, Seed};
+
+    #[test]
+    fn chained_seeds_find_the_shared_region() {
+        let mut rng = Rng::new(131);
+        let shared = random_dna(3000, &mut rng);
+        let rates = MutationRates {
+            substitution: 0.02,
+            insertion: 0.005,
+            deletion: 0.005,
+        };
+        let mutated = mutate(&shared, rates, DNA, &mut rng).seq;
+        let row_seq = [random_dna(500, &mut rng), shared].concat();
+        let column_seq = [mutated, random_dna(800, &mut rng)].concat();
+        let config = HeuristicConfig::default();
+
+        let alignment = seed_and_extend(&row_seq, &column_seq, config).unwrap();
+        assert_eq!((alignment.row_start, alignment.row_end), (500, 3500));
+        assert_eq!(
+            (alignment.column_start, alignment.column_end),
+            (0, column_seq.len() - 800)
+        );
+        assert_eq!(alignment.result.score, 2785);
+        assert!(alignment.chain.len() > 10);
+        assert_eq!(
+            alignment.result,
+            needleman_wunsch(
+                &row_seq[alignment.row_start .. alignment.row_end],
+                &column_seq[alignment.column_start .. alignment.column_end],
+                config.alignment
+            )
+        );
+
+        let abc: Vec<char> = "xxABCDEyyABCDE".chars().collect();
+        let cde: Vec<char> = "zCDEz".chars().collect();
+        assert_eq!(
+            find_seeds(&abc, &cde, 2, 8),
+            [
+                Seed { row_start: 4, column_start: 1, len: 3 },
+                Seed { row_start: 11, column_start: 1, len: 3 },
+            ]
+        );
+        assert_eq!(seed_and_extend(&abc, &row_seq, config), None);
+
+        // flanks too diverged for seeds are still reached by the extension
+        let diverge = |flank: &[char]| -> Vec<char> {
+            let next = |letter| match letter {
+                'A' => 'C',
+                'C' => 'G',
+                'G' => 'T',
+                _ => 'A',
+            };
+            (0 .. flank.len())
+                .map(|i| if i % 5 == 2 { next(flank[i]) } else { flank[i] })
+                .collect()
+        };
+        let (left, core, right) = (
+            random_dna(30, &mut rng),
+            random_dna(60, &mut rng),
+            random_dna(30, &mut rng),
+        );
+        let row_seq =
+            [&['A'; 20][..], &left, &core, &right, &['A'; 20]].concat();
+        let column_seq = [
+            &['C'; 25][..],
+            &diverge(&left),
+            &core,
+            &diverge(&right),
+            &['C'; 15],
+        ]
+        .concat();
+        let alignment = seed_and_extend(&row_seq, &column_seq, config).unwrap();
+        assert_eq!((alignment.row_start, alignment.row_end), (20, 140));
+        assert_eq!((alignment.column_start, alignment.column_end), (25, 145));
+        assert_eq!(alignment.result.score, 60 + 2 * (24 - 6));
+    }
+}
@@ -0,0 +1,393 @@
+use std::io::{self, BufRead, Read, Write};
+
+use crate::{
+    daemon::{
+        count_cells,
+        error_response,
+        number_field,
+        parse_request_object,
+        required_text_field,
+        respond,
+        scheme_fields,
+        ConnectionLimit,
+        JsonValue,
+        RequestError,
+        IDLE_TIMEOUT,
+    },
+    letter::Letter,
+    local::LocalAlignmentConfig,
+    lockstep::{
+        batch_padded_lens,
+        needleman_wunsch_scores_batch,
+        smith_waterman_scores_batch,
+    },
+    report::json::json_string,
+    search::{search, SearchConfig, Target},
+};
+
+/// Largest request body accepted, in bytes.
+pub const MAX_BODY_LEN: usize = 1 << 24;
+
+/// Largest request or header line accepted, in bytes.
+pub const MAX_LINE_LEN: usize = 8192;
+
+/// Largest number of header lines accepted in a request.
+pub const MAX_HEADERS: usize = 100;
+
+/// Status line and body of an HTTP response.
+type Response = (&'static str, String);
+
+/// Reads a required array field of a request object.
+fn array_field<'a>(
+    object: &'a JsonValue,
+    field: &'static str,
+) -> Result<&'a [JsonValue], RequestError> {
+    match object.get(field) {
+        Some(JsonValue::Array(items)) => Ok(items),
+        None | Some(JsonValue::Null) => {
+            Err(RequestError::MissingField { field })
+        },
+        Some(_) => Err(RequestError::InvalidField { field: field.to_owned() }),
+    }
+}
+
+/// Answers `POST /batch`: scores of many pairs, without traceback.
+///
+/// ```text
+/// {"mode": "global", "pairs": [["GATTACA", "GATCA"], ...],
+///  "match": 1, "mismatch": -1, "gap": -2}
+/// ```
+fn batch(object: &JsonValue) -> Result<String, RequestError> {
+    let scheme = scheme_fields(object)?;
+    let invalid_pairs = || RequestError::InvalidField { field: "pairs".into() };
+    let mut seqs = Vec::new();
+    for pair in array_field(object, "pairs")? {
+        match pair {
+            JsonValue::Array(items) => match items.as_slice() {
+                [JsonValue::String(row), JsonValue::String(column)] => {
+                    seqs.push((
+                        row.chars().collect::<Vec<Letter>>(),
+                        column.chars().collect::<Vec<Letter>>(),
+                    ));
+                },
+                _ => return Err(invalid_pairs()),
+            },
+            _ => return Err(invalid_pairs()),
+        }
+    }
+    let pairs: Vec<_> = seqs
+        .iter()
+        .map(|(row, column)| (row.as_slice(), column.as_slice()))
+        .collect();
+    // padding cells cost as much as real ones
+    count_cells(batch_padded_lens(&pairs))?;
+    let scores = match object.get("mode") {
+        None | Some(JsonValue::Null) => {
+            needleman_wunsch_scores_batch(&pairs, scheme)
+        },
+        Some(JsonValue::String(mode)) if mode == "global" => {
+            needleman_wunsch_scores_batch(&pairs, scheme)
+        },
+        Some(JsonValue::String(mode)) if mode == "local" => {
+            smith_waterman_scores_batch(
+                &pairs,
+                LocalAlignmentConfig {
+                    match_penalty: scheme.match_penalty,
+                    mismatch_penalty: scheme.mismatch_penalty,
+                    gap_penalty: scheme.gap_penalty,
+                },
+            )
+        },
+        Some(JsonValue::String(mode)) => {
+            return Err(RequestError::UnknownMode { mode: mode.clone() });
+        },
+        Some(_) => {
+            return Err(RequestError::InvalidField { field: "mode".into() });
+        },
+    }
+    .map_err(|overflow| RequestError::Overflow {
+        cell: (overflow.i, overflow.j),
+    })?;
+    let scores: Vec<String> =
+        scores.iter().map(|score| score.to_string()).collect();
+    Ok(format!("{{\"scores\":[{}]}}", scores.join(",")))
+}
+
+/// Answers `POST /search`: best local hits of a query in a set of targets.
+///
+/// ```text
+/// {"query": "GATTACA", "targets": [{"id": "t1", "seq": "..."}, ...],
+///  "top_k": 10, "match": 1, "mismatch": -1, "gap": -2}
+/// ```
+fn search_targets(object: &JsonValue) -> Result<String, RequestError> {
+    let scheme = scheme_fields(object)?;
+    let defaults = SearchConfig::default();
+    let config = SearchConfig {
+        alignment: LocalAlignmentConfig {
+            match_penalty: scheme.match_penalty,
+            mismatch_penalty: scheme.mismatch_penalty,
+            gap_penalty: scheme.gap_penalty,
+        },
+        top_k: number_field(object, "top_k", defaults.top_k)?,
+        ..defaults
+    };
+    let query: Vec<Letter> =
+        required_text_field(object, "query")?.chars().collect();
+    let mut records = Vec::new();
+    for target in array_field(object, "targets")? {
+        let id = required_text_field(target, "id")?;
+        let seq: Vec<Letter> =
+            required_text_field(target, "seq")?.chars().collect();
+        records.push((id, seq));
+    }
+    count_cells(records.iter().map(|(_, seq)| (query.len(), seq.len())))?;
+
+    let targets = records.iter().map(|(id, seq)| Target { id, seq });
+    let hits: Vec<String> = search(&query, targets, config)
+        .iter()
+        .map(|hit| {
+            let row = &hit.alignment.aligned_row_seq;
+            let column = &hit.alignment.aligned_column_seq;
+            format!(
+                "{{\"target\":{},\"index\":{},\"score\":{},\"evalue\":{},\"\
+                 query_start\":{},\"query_end\":{},\"target_start\":{},\"\
+                 target_end\":{}}}",
+                json_string(hit.target.id),
+                hit.target_index,
+                hit.alignment.score,
+                hit.evalue,
+                row.start,
+                row.end,
+                column.start,
+                column.end,
+            )
+        })
+        .collect();
+    Ok(format!("{{\"hits\":[{}]}}", hits.join(",")))
+}
+
+/// Routes a request to its endpoint.
+fn route(method: &str, path: &str, body: &str) -> Response {
+    let endpoint: fn(&JsonValue) -> Result<String, RequestError> = match path {
+        "/align" => {
+            if method != "POST" {
+                return method_not_allowed();
+            }
+            return match respond(body) {
+                Ok(response) => ("200 OK", response),
+                Err(response) => ("400 Bad Request", response),
+            };
+        },
+        "/batch" => batch,
+        "/search" => search_targets,
+        _ => {
+            let error = json_string(&format!("no endpoint at {path}"));
+            return ("404 Not Found", format!("{{\"error\":{error}}}"));
+        },
+    };
+    if method != "POST" {
+        return method_not_allowed();
+    }
+    match parse_request_object(body).and_then(|object| endpoint(&object)) {
+        Ok(response) => ("200 OK", response),
+        Err(error) => {
+            ("400 Bad Request", error_response(&JsonValue::Null, &error))
+        },
+    }
+}
+
+fn method_not_allowed() -> Response {
+    (
+        "405 Method Not Allowed",
+        "{\"error\":\"only POST is supported\"}".to_owned(),
+    )
+}
+
+/// Reads a line of at most [`MAX_LINE_LEN`] bytes, newline included, into
+/// `line`. Returns `false` when the input ends or the line is too long.
+fn read_line_limited<R>(input: &mut R, line: &mut String) -> io::Result<bool>
+where
+    R: BufRead,
+{
+    line.clear();
+    let len = input.take(MAX_LINE_LEN as u64).read_line(line)?;
+    Ok(len > 0 && line.ends_with('\n'))
+}
+
+/// Reads one HTTP request: its method, path and body. Returns `None` when
+/// the request is malformed, or its lines or body are too long.
+fn read_request<R>(
+    input: &mut R,
+) -> io::Result<Option<(String, String, String)>>
+where
+    R: BufRead,
+{
+    let mut line = String::new();
+    if !read_line_limited(input, &mut line)? {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (method, path) = (method.to_owned(), path.to_owned());
+
+    let mut content_len = 0;
+    let mut header_count = 0;
+    loop {
+        if !read_line_limited(input, &mut line)? {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        header_count += 1;
+        if header_count > MAX_HEADERS {
+            return Ok(None);
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(len) if len <= MAX_BODY_LEN => content_len = len,
+                    _ => return Ok(None),
+                }
+            }
+        }
+    }
+
+    let mut body = vec![0; content_len];
+    input.read_exact(&mut body)?;
+    Ok(String::from_utf8(body).ok().map(|body| (method, path, body)))
+}
+
+/// Serves one HTTP request read from `input`, writing the response to
+/// `output`. The connection is not kept alive.
+///
+/// Endpoints, all taking and returning JSON with `POST`:
+///
+/// - `/align`: one alignment, as in [`crate::daemon::handle_request`];
+/// - `/batch`: `{"mode", "pairs": [[row, column], ...]}`, answered with the
+///   scores of every pair, computed in lockstep;
+/// - `/search`: `{"query", "targets": [{"id", "seq"}, ...], "top_k"}`, answered
+///   with the best hits, as found by [`search`].
+///
+/// Every endpoint reads the `match`, `mismatch` and `gap` scores, defaulting
+/// to those of [`crate::global::GlobalAlignmentConfig::default`].
+pub fn handle_http<R, W>(mut input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write + ?Sized,
+{
+    let (status, body) = match read_request(&mut input)? {
+        Some((method, path, body)) => route(&method, &path, &body),
+        None => (
+            "400 Bad Request",
+            "{\"error\":\"malformed HTTP request\"}".to_owned(),
+        ),
+    };
+    write!(
+        output,
+        "HTTP/1.1 {status}\r\nContent-Type: \
+         application/json\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n{body}",
+        body.len()
+    )?;
+    output.flush()
+}
+
+/// Listens on a TCP address, serving every connection with [`handle_http`]
+/// in its own thread, at most
+/// [`MAX_CONNECTIONS`](crate::daemon::MAX_CONNECTIONS) at a time. Connections
+/// idle for [`IDLE_TIMEOUT`] are dropped. Runs until accepting a connection
+/// fails.
+pub fn serve_http<A>(address: A) -> io::Result<()>
+where
+    A: std::net::ToSocketAddrs,
+{
+    use std::{io::BufReader, net::TcpListener, thread};
+
+    let listener = TcpListener::bind(address)?;
+    let limit = ConnectionLimit::new();
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let slot = limit.acquire();
+        thread::spawn(move || -> io::Result<()> {
+            let _slot = slot;
+            stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+            stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+            let input = BufReader::new(stream.try_clone()?);
+            handle_http(input, &mut stream)
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{handle_http, MAX_LINE_LEN};
+
+    fn exchange(method: &str, path: &str, body: &str) -> String {
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: \
+             {}\r\n\r\n{body}",
+            body.len()
+        );
+        let mut output = Vec::new();
+        handle_http(request.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn endpoints_answer_json() {
+        let response = exchange(
+            "POST",
+            "/batch",
+            "{\"pairs\": [[\"GATTACA\", \"GATCA\"], [\"AC\", \"AC\"]]}",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"scores\":[1,2]}"));
+
+        let response = exchange(
+            "POST",
+            "/search",
+            "{\"query\": \"GATTACA\", \"top_k\": 1, \"targets\": [{\"id\": \
+             \"far\", \"seq\": \"CCCCCC\"}, {\"id\": \"near\", \"seq\": \
+             \"TTGATTACATT\"}]}",
+        );
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(
+            "{\"hits\":[{\"target\":\"near\",\"index\":1,\"score\":7,"
+        ));
+        assert!(response.contains("\"target_start\":2,\"target_end\":9}]}"));
+
+        let response = exchange("POST", "/align", "{\"row\": \"A\"}");
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(exchange("GET", "/align", "").contains(" 405 "));
+        assert!(exchange("POST", "/nowhere", "{}").contains(" 404 "));
+
+        let long_path = "/".repeat(MAX_LINE_LEN);
+        assert!(exchange("POST", &long_path, "{}").contains(" 400 "));
+        let long_seq = "A".repeat(5000);
+        let response = exchange(
+            "POST",
+            "/batch",
+            &format!("{{\"pairs\": [[\"{long_seq}\", \"{long_seq}\"]]}}"),
+        );
+        assert!(response.contains(" 400 ") && response.contains("cells"));
+
+        let long_seq = "A".repeat(2000);
+        let pairs: Vec<String> = (0 .. 16)
+            .map(|index| match index % 2 {
+                0 => format!("[\"{long_seq}\", \"A\"]"),
+                _ => format!("[\"A\", \"{long_seq}\"]"),
+            })
+            .collect();
+        let response = exchange(
+            "POST",
+            "/batch",
+            &format!("{{\"pairs\": [{}]}}", pairs.join(", ")),
+        );
+        assert!(response.contains(" 400 ") && response.contains("cells"));
+    }
+}
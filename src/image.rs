@@ -0,0 +1,249 @@
+use std::{fmt, io};
+
+pub use crate::heatmap::ColorScale;
+use crate::{matrix::AlignmentMatrix, report::WriteReport, score::Score};
+
+/// Color of every cell of a matrix, in row-major order, scaled between its
+/// lowest and highest scores. A constant matrix gets the middle color.
+fn cell_colors(
+    scale: &ColorScale,
+    matrix: &AlignmentMatrix,
+) -> Vec<(u8, u8, u8)> {
+    let (min, max) = match (matrix.min(), matrix.max()) {
+        (Some(min), Some(max)) => (i128::from(min), i128::from(max)),
+        _ => return Vec::new(),
+    };
+    // wide enough for the distance between any two scores
+    let fraction = |score: Score| {
+        if max == min {
+            0.5
+        } else {
+            (i128::from(score) - min) as f64 / (max - min) as f64
+        }
+    };
+    matrix.iter().map(|score| scale.color(fraction(score))).collect()
+}
+
+/// Renders the scores of an alignment matrix as a standalone SVG heatmap,
+/// each cell titled with its coordinates and score.
+#[derive(Debug, Clone, Copy)]
+pub struct SvgMatrixHeatmap<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a AlignmentMatrix,
+    /// Colors of the scores.
+    pub scale: ColorScale,
+    /// Side of each cell, in pixels.
+    pub cell_size: usize,
+}
+
+impl fmt::Display for SvgMatrixHeatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = self.cell_size;
+        let width = cell * self.matrix.width();
+        let height = cell * self.matrix.height();
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" \
+             height=\"{height}\">"
+        )?;
+        let colors = cell_colors(&self.scale, self.matrix);
+        for ((i, j, score), (red, green, blue)) in
+            self.matrix.enumerate_cells().zip(colors)
+        {
+            let (x, y) = (j * cell, i * cell);
+            writeln!(
+                f,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{cell}\" height=\"{cell}\" \
+                 fill=\"rgb({red},{green},{blue})\"><title>[{i}, {j}] \
+                 {score}</title></rect>"
+            )?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+impl WriteReport for SvgMatrixHeatmap<'_> {}
+
+/// Renders the scores of an alignment matrix as a PNG heatmap.
+#[derive(Debug, Clone, Copy)]
+pub struct PngMatrixHeatmap<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a AlignmentMatrix,
+    /// Colors of the scores.
+    pub scale: ColorScale,
+    /// Side of each cell, in pixels.
+    pub cell_size: usize,
+}
+
+impl PngMatrixHeatmap<'_> {
+    /// Writes the PNG image into the given sink. The pixels are stored
+    /// uncompressed, so the image takes three bytes per pixel.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the image would be
+    /// empty or larger than PNG allows.
+    pub fn write_png<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        let cell = self.cell_size;
+        let width = self.matrix.width() * cell;
+        let height = self.matrix.height() * cell;
+        let invalid_size = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid PNG dimensions {width}x{height}"),
+            )
+        };
+        if width == 0 || height == 0 {
+            return Err(invalid_size());
+        }
+        let png_width = u32::try_from(width)
+            .ok()
+            .filter(|&size| size <= i32::MAX as u32)
+            .ok_or_else(invalid_size)?;
+        let png_height = u32::try_from(height)
+            .ok()
+            .filter(|&size| size <= i32::MAX as u32)
+            .ok_or_else(invalid_size)?;
+
+        let colors = cell_colors(&self.scale, self.matrix);
+        let mut pixels = Vec::with_capacity(height * (1 + 3 * width));
+        for color_row in colors.chunks(self.matrix.width()) {
+            let mut scanline = vec![0];
+            for &(red, green, blue) in color_row {
+                for _ in 0 .. cell {
+                    scanline.extend_from_slice(&[red, green, blue]);
+                }
+            }
+            for _ in 0 .. cell {
+                pixels.extend_from_slice(&scanline);
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&png_width.to_be_bytes());
+        header.extend_from_slice(&png_height.to_be_bytes());
+        // 8-bit RGB, default compression and filters, no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(writer, b"IHDR", &header)?;
+        write_chunk(writer, b"IDAT", &zlib_stored(&pixels))?;
+        write_chunk(writer, b"IEND", &[])
+    }
+}
+
+/// Writes a PNG chunk: length, type, data and CRC of type and data.
+fn write_chunk<W>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()>
+where
+    W: io::Write + ?Sized,
+{
+    let len = u32::try_from(data.len()).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "PNG chunk too large")
+    })?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(&[&kind[..], data].concat());
+    writer.write_all(&crc.to_be_bytes())
+}
+
+/// Wraps data in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+    let mut stream =
+        Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    stream.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(u8::from(last));
+        stream.extend_from_slice(&len.to_le_bytes());
+        stream.extend_from_slice(&(!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+/// CRC-32 checksum of PNG chunks.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0 .. 8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Adler-32 checksum of zlib streams.
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod test {
+    use crate::matrix::AlignmentMatrix;
+
+    use super::{ColorScale, PngMatrixHeatmap, SvgMatrixHeatmap};
+
+    #[test]
+    fn heatmaps_scale_scores_into_colors() {
+        let matrix =
+            AlignmentMatrix::from_rows(&[&[0, -2, -4], &[-2, 1, -1]]).unwrap();
+        let scale = ColorScale::GRAYSCALE;
+        assert_eq!(scale.color(0.5), (128, 128, 128));
+
+        let svg = SvgMatrixHeatmap { matrix: &matrix, scale, cell_size: 4 }
+            .to_string();
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"12\" \
+             height=\"8\">"
+        ));
+        assert_eq!(svg.matches("<rect").count(), 6);
+        assert!(svg.contains("fill=\"rgb(0,0,0)\"><title>[0, 2] -4</title>"));
+        assert!(
+            svg.contains("fill=\"rgb(255,255,255)\"><title>[1, 1] 1</title>")
+        );
+
+        let mut png = Vec::new();
+        PngMatrixHeatmap { matrix: &matrix, scale, cell_size: 2 }
+            .write_png(&mut png)
+            .unwrap();
+        assert_eq!(&png[.. 8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12 .. 24], b"IHDR\0\0\0\x06\0\0\0\x04");
+        // 4 scanlines of a filter byte and 6 RGB pixels, plus zlib framing
+        let idat_len = u32::from_be_bytes(png[33 .. 37].try_into().unwrap());
+        assert_eq!(idat_len, 4 * (1 + 6 * 3) + 11);
+        assert_eq!(&png[png.len() - 12 ..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+        let extreme =
+            AlignmentMatrix::from_rows(&[&[i64::MIN, 0, i64::MAX]]).unwrap();
+        let svg = SvgMatrixHeatmap { matrix: &extreme, scale, cell_size: 1 }
+            .to_string();
+        assert!(svg.contains("rgb(0,0,0)"));
+        assert!(svg.contains("rgb(128,128,128)"));
+        assert!(svg.contains("rgb(255,255,255)"));
+
+        let empty = AlignmentMatrix::zeroed(0, 3);
+        let heatmap = PngMatrixHeatmap { matrix: &empty, scale, cell_size: 2 };
+        assert!(heatmap.write_png(&mut Vec::new()).is_err());
+    }
+}
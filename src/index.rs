@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::{
+    letter::Letter,
+    minimizer::{minimizers, MinimizerConfig},
+};
+
+/// Occurrence of an indexed k-mer in a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KmerHit {
+    /// Position of the k-mer in the query.
+    pub query_position: usize,
+    /// Position of the k-mer in the target.
+    pub target_position: usize,
+}
+
+impl KmerHit {
+    /// Diagonal of the hit, the target position minus the query position.
+    pub fn diagonal(&self) -> isize {
+        self.target_position as isize - self.query_position as isize
+    }
+}
+
+/// Positions of the k-mers of a target sequence, looked up by k-mer.
+///
+/// Either every k-mer is indexed, or only the minimizers, which keeps the
+/// index several times smaller at the cost of missing short matches.
+#[derive(Debug, Clone)]
+pub struct KmerIndex<'target> {
+    target: &'target [Letter],
+    k: usize,
+    sampling: Option<MinimizerConfig>,
+    positions: HashMap<&'target [Letter], Vec<usize>>,
+}
+
+impl<'target> KmerIndex<'target> {
+    /// Indexes every k-mer of the target. A `k` of zero is taken as one.
+    pub fn new(target: &'target [Letter], k: usize) -> Self {
+        let k = k.max(1);
+        let mut positions: HashMap<&[Letter], Vec<usize>> = HashMap::new();
+        for (i, kmer) in target.windows(k).enumerate() {
+            positions.entry(kmer).or_default().push(i);
+        }
+        Self { target, k, sampling: None, positions }
+    }
+
+    /// Indexes only the minimizers of the target. Lookups then use the
+    /// minimizers of the query, so that shared windows still meet.
+    pub fn minimizers(
+        target: &'target [Letter],
+        config: MinimizerConfig,
+    ) -> Self {
+        let mut positions: HashMap<&[Letter], Vec<usize>> = HashMap::new();
+        for minimizer in minimizers(target, config) {
+            let kmer =
+                &target[minimizer.position .. minimizer.position + config.k];
+            positions.entry(kmer).or_default().push(minimizer.position);
+        }
+        Self { target, k: config.k, sampling: Some(config), positions }
+    }
+
+    /// The indexed sequence.
+    pub fn target(&self) -> &'target [Letter] {
+        self.target
+    }
+
+    /// Length of the indexed k-mers.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Number of distinct k-mers indexed.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether no k-mer is indexed, e.g. when the target is shorter than `k`.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Target positions of a k-mer, in increasing order.
+    pub fn positions(&self, kmer: &[Letter]) -> &[usize] {
+        self.positions.get(kmer).map_or(&[], Vec::as_slice)
+    }
+
+    /// Hits of the query k-mers in the target, sorted by query position, then
+    /// target position. K-mers occurring more than `max_occurrences` times in
+    /// the target are skipped as repeats.
+    pub fn lookup(
+        &self,
+        query: &[Letter],
+        max_occurrences: usize,
+    ) -> Vec<KmerHit> {
+        let query_positions: Vec<usize> = match self.sampling {
+            Some(config) => minimizers(query, config)
+                .into_iter()
+                .map(|minimizer| minimizer.position)
+                .collect(),
+            None => (0 .. (query.len() + 1).saturating_sub(self.k)).collect(),
+        };
+        let mut hits = Vec::new();
+        for query_position in query_positions {
+            let kmer = &query[query_position .. query_position + self.k];
+            let positions = self.positions(kmer);
+            if positions.len() <= max_occurrences {
+                hits.extend(positions.iter().map(|&target_position| KmerHit {
+                    query_position,
+                    target_position,
+                }));
+            }
+        }
+        hits
+    }
+
+    /// K-mers occurring at least `min_occurrences` times in the target, with
+    /// their positions, sorted by first position.
+    pub fn repeats(
+        &self,
+        min_occurrences: usize,
+    ) -> Vec<(&'target [Letter], &[usize])> {
+        let mut repeats: Vec<_> = self
+            .positions
+            .iter()
+            .filter(|(_, positions)| positions.len() >= min_occurrences)
+            .map(|(&kmer, positions)| (kmer, positions.as_slice()))
+            .collect();
+        repeats.sort_by_key(|(_, positions)| positions[0]);
+        repeats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::minimizer::MinimizerConfig;
+
+    use super::{KmerHit, KmerIndex};
+
+    #[test]
+    fn query_kmers_are_found_in_the_target() {
+        let target: Vec<char> = "ACGTACGTTTGCA".chars().collect();
+        let query: Vec<char> = "GGTACG".chars().collect();
+        let index = KmerIndex::new(&target, 3);
+        assert_eq!(index.positions(&['A', 'C', 'G']), [0, 4]);
+        assert_eq!(index.positions(&['C', 'C', 'C']), []);
+        assert_eq!(
+            index.lookup(&query, usize::MAX),
+            [
+                KmerHit { query_position: 1, target_position: 2 },
+                KmerHit { query_position: 2, target_position: 3 },
+                KmerHit { query_position: 3, target_position: 0 },
+                KmerHit { query_position: 3, target_position: 4 },
+            ]
+        );
+        assert_eq!(index.lookup(&query, 1).len(), 2);
+        let repeats = index.repeats(2);
+        assert_eq!(repeats.len(), 2);
+        assert_eq!(repeats[0], (&target[0 .. 3], &[0, 4][..]));
+
+        let config = MinimizerConfig { k: 3, window: 3 };
+        let sampled = KmerIndex::minimizers(&target, config);
+        assert!(sampled.len() < index.len());
+        assert!(!sampled.lookup(&target, usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+/// A finished span of work: what ran, how much it processed and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpanRecord {
+    /// Name of the instrumented step, e.g. `"needleman_wunsch::fill"`.
+    pub name: &'static str,
+    /// Number of matrix cells processed by the step.
+    pub cells: u64,
+    /// Number of sequences processed, for batch steps; zero otherwise.
+    pub items: u64,
+    /// Wall-clock time taken by the step.
+    pub elapsed: Duration,
+}
+
+/// Receiver of finished spans.
+///
+/// Sinks run synchronously in the aligning thread, so they should be cheap,
+/// e.g. forwarding records to a channel or to the embedding service's own
+/// tracing framework.
+pub type Sink = fn(&SpanRecord);
+
+/// The currently installed sink.
+static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+
+/// Installs a sink that receives every finished span, replacing the previous
+/// one. `None` disables reporting.
+pub fn set_sink(sink: Option<Sink>) {
+    let mut guard = SINK.write().unwrap_or_else(|error| error.into_inner());
+    *guard = sink;
+}
+
+/// Returns the currently installed sink.
+pub fn sink() -> Option<Sink> {
+    *SINK.read().unwrap_or_else(|error| error.into_inner())
+}
+
+/// A running span, reported to the sink when dropped.
+#[derive(Debug)]
+pub(crate) struct Span {
+    name: &'static str,
+    cells: u64,
+    items: u64,
+    start: Instant,
+}
+
+impl Span {
+    /// Starts a span expected to process the given number of cells.
+    pub(crate) fn enter(name: &'static str, cells: usize) -> Self {
+        Self { name, cells: cells as u64, items: 0, start: Instant::now() }
+    }
+
+    /// Accounts for more cells processed during the span.
+    pub(crate) fn add_cells(&mut self, cells: usize) {
+        self.cells += cells as u64;
+    }
+
+    /// Accounts for one more sequence processed during the span.
+    pub(crate) fn add_item(&mut self) {
+        self.items += 1;
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(sink) = sink() {
+            sink(&SpanRecord {
+                name: self.name,
+                cells: self.cells,
+                items: self.items,
+                elapsed: self.start.elapsed(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::global::{needleman_wunsch, GlobalAlignmentConfig};
+
+    use super::{set_sink, SpanRecord};
+
+    static RECORDS: Mutex<Vec<(&'static str, u64)>> = Mutex::new(Vec::new());
+
+    fn collect(record: &SpanRecord) {
+        RECORDS.lock().unwrap().push((record.name, record.cells));
+    }
+
+    #[test]
+    fn global_alignment_reports_fill_and_traceback() {
+        set_sink(Some(collect));
+        needleman_wunsch(
+            &['G', 'A', 'T', 'T', 'A', 'C', 'A'],
+            &['G', 'C', 'A', 'T'],
+            GlobalAlignmentConfig::default(),
+        );
+        set_sink(None);
+
+        let records = RECORDS.lock().unwrap();
+        assert!(records.contains(&("needleman_wunsch::fill", 40)));
+        assert!(records
+            .iter()
+            .any(|(name, _)| *name == "needleman_wunsch::traceback"));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    letter::Letter,
+    score::{Score, ScoringScheme},
+};
+
+/// Bases (as a bit set of A = 1, C = 2, G = 4 and T = 8) an IUPAC nucleotide
+/// code stands for, or `None` if the letter is not an IUPAC code. `U` is read
+/// as `T`.
+pub fn iupac_bases(code: Letter) -> Option<u8> {
+    let bases = match code {
+        'A' => 0b0001,
+        'C' => 0b0010,
+        'G' => 0b0100,
+        'T' | 'U' => 0b1000,
+        'R' => 0b0101,
+        'Y' => 0b1010,
+        'S' => 0b0110,
+        'W' => 0b1001,
+        'K' => 0b1100,
+        'M' => 0b0011,
+        'B' => 0b1110,
+        'D' => 0b1101,
+        'H' => 0b1011,
+        'V' => 0b0111,
+        'N' => 0b1111,
+        _ => return None,
+    };
+    Some(bases)
+}
+
+/// Whether two IUPAC codes may stand for the same base, e.g. `R` (A or G) and
+/// `A`. Letters that are not IUPAC codes are only compatible with themselves.
+pub fn iupac_compatible(a: Letter, b: Letter) -> bool {
+    match (iupac_bases(a), iupac_bases(b)) {
+        (Some(a_bases), Some(b_bases)) => a_bases & b_bases != 0,
+        _ => a == b,
+    }
+}
+
+/// Scoring scheme wrapper where compatible IUPAC nucleotide codes score as a
+/// match instead of a mismatch, for both the global and the local aligners.
+///
+/// A compatible pair is scored as the row letter aligned with itself in the
+/// wrapped scheme; other pairs and gaps are scored by the wrapped scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IupacMatching<S> {
+    /// Scheme scoring matches, mismatches and gaps.
+    pub scheme: S,
+}
+
+impl<S> ScoringScheme for IupacMatching<S>
+where
+    S: ScoringScheme,
+{
+    fn score(&self, row_letter: Letter, column_letter: Letter) -> Score {
+        if iupac_compatible(row_letter, column_letter) {
+            self.scheme.score(row_letter, row_letter)
+        } else {
+            self.scheme.score(row_letter, column_letter)
+        }
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.scheme.gap_penalty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{iupac_compatible, IupacMatching};
+
+    #[test]
+    fn ambiguity_codes_match_compatible_bases() {
+        assert!(iupac_compatible('R', 'G'));
+        assert!(iupac_compatible('N', 'Y'));
+        assert!(!iupac_compatible('Y', 'A'));
+        assert!(!iupac_compatible('X', 'A'));
+
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GRTYANA".chars().collect();
+        let config = GlobalAlignmentConfig::default();
+        let plain = needleman_wunsch(&row_seq, &column_seq, config);
+        let ambiguous = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            IupacMatching { scheme: config },
+        );
+        assert!(plain.score < 7);
+        assert_eq!(ambiguous.score, 7);
+        assert_eq!(ambiguous.aligned_column_seq, column_seq);
+
+        let results = best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            IupacMatching { scheme: LocalAlignmentConfig::default() },
+        );
+        assert_eq!(results[0].score, 7);
+    }
+}
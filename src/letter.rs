@@ -1,15 +1,65 @@
+use std::fmt;
+
 /// Letter type is just a character.
 pub type Letter = char;
 
 /// Constant definition of a gap "letter".
 pub const GAP: Letter = '-';
 
+/// Letters the aligners work with, so sequences need not be made of `char`s.
+pub trait GenericLetter: Copy + Eq + fmt::Debug {
+    /// Letter standing for a gap in aligned sequences.
+    const GAP: Self;
+
+    /// Character the letter is displayed as.
+    fn to_char(self) -> char;
+}
+
+impl GenericLetter for Letter {
+    const GAP: Self = GAP;
+
+    fn to_char(self) -> char {
+        self
+    }
+}
+
+/// ASCII bytes, as genomic data usually comes, aligned without conversion
+/// into `char`s.
+impl GenericLetter for u8 {
+    const GAP: Self = b'-';
+
+    fn to_char(self) -> char {
+        char::from(self)
+    }
+}
+
+/// Gets the letter at an index of a sequence, or a gap if out of bounds.
+pub(crate) fn letter_or_gap<L>(seq: &[L], index: usize) -> L
+where
+    L: GenericLetter,
+{
+    seq.get(index).copied().unwrap_or(L::GAP)
+}
+
 /// Extension trait over primitive letter types.
 pub trait NormalizeLetter {
     /// This method normalizes `Self` into a value of `Letter` type.
     ///
     /// E.g. `None` becomes `'-'` (gap).
     fn normalize_letter(self) -> Letter;
+
+    /// Normalizes `Self` like [`NormalizeLetter::normalize_letter`], but also
+    /// reads `gap_symbol` as a gap, so sequences written with another gap
+    /// convention (e.g. `'.'`) are recognized.
+    fn normalize_letter_with_gap(self, gap_symbol: Letter) -> Letter
+    where
+        Self: Sized,
+    {
+        match self.normalize_letter() {
+            letter if letter == gap_symbol => GAP,
+            letter => letter,
+        }
+    }
 }
 
 // reflexive implementation
@@ -38,3 +88,93 @@ where
         self.map_or(GAP, L::normalize_letter)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::GenericLetter;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Base {
+        A,
+        C,
+        G,
+        T,
+        Gap,
+    }
+
+    impl GenericLetter for Base {
+        const GAP: Self = Self::Gap;
+
+        fn to_char(self) -> char {
+            match self {
+                Self::A => 'A',
+                Self::C => 'C',
+                Self::G => 'G',
+                Self::T => 'T',
+                Self::Gap => '-',
+            }
+        }
+    }
+
+    #[test]
+    fn custom_letters_align_like_chars() {
+        use Base::*;
+        let row_seq = [G, A, T, T, A, C, A];
+        let column_seq = [G, A, T, C, A];
+        let to_chars =
+            |seq: &[Base]| seq.iter().map(|b| b.to_char()).collect::<Vec<_>>();
+
+        let config = GlobalAlignmentConfig::default();
+        let global = needleman_wunsch(&row_seq, &column_seq, config);
+        let expected = needleman_wunsch(
+            &to_chars(&row_seq),
+            &to_chars(&column_seq),
+            config,
+        );
+        assert_eq!(to_chars(&global.aligned_row_seq), expected.aligned_row_seq);
+        assert_eq!(
+            to_chars(&global.aligned_column_seq),
+            expected.aligned_column_seq
+        );
+        assert_eq!(global.score, expected.score);
+
+        let config = LocalAlignmentConfig::default();
+        let local = best_smith_waterman(&row_seq, &column_seq, config);
+        let expected = best_smith_waterman(
+            &to_chars(&row_seq),
+            &to_chars(&column_seq),
+            config,
+        );
+        assert_eq!(local.len(), expected.len());
+        assert_eq!(
+            to_chars(&local[0].aligned_column_seq.data),
+            expected[0].aligned_column_seq.data
+        );
+        assert_eq!(local[0].mapping_quality, expected[0].mapping_quality);
+    }
+
+    #[test]
+    fn byte_sequences_align_directly() {
+        let row_seq = b"CCGATTACAGG";
+        let column_seq = b"GATCACA";
+        let global = needleman_wunsch(
+            row_seq,
+            column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+        let local = best_smith_waterman(
+            row_seq,
+            column_seq,
+            LocalAlignmentConfig::default(),
+        );
+        assert_eq!(global.aligned_row_seq, b"CCGATTACAGG");
+        assert_eq!(global.aligned_column_seq, b"--GATCACA--");
+        assert_eq!(local[0].aligned_row_seq.data, b"GATTACA");
+        assert_eq!(local[0].aligned_row_seq.start, 2);
+    }
+}
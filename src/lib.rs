@@ -7,8 +7,187 @@ pub mod score;
 /// Utilities implementing an alignment matrix API.
 pub mod matrix;
 
+/// Reusable buffers for allocation-free alignment loops.
+pub mod arena;
+
 /// Global alignment implementation via Needleman-Wunsch.
 pub mod global;
 
 /// Local alignment implementation via Smith-Waterman.
 pub mod local;
+
+/// Shared symbols, layout and helpers of the textual report formatters.
+pub mod report;
+
+/// Variant extraction from alignments and VCF output.
+pub mod variant;
+
+/// Pileup and coverage of many alignments over a reference.
+pub mod pileup;
+
+/// Consensus calling from a pileup.
+pub mod consensus;
+
+/// Query-vs-database search built on local alignment.
+pub mod search;
+
+/// Minimizer sketching and prefiltering.
+pub mod minimizer;
+
+/// Cheap upper bounds of alignment scores.
+pub mod bound;
+
+/// Codon translation and reading-frame preserving alignment.
+pub mod codon;
+
+/// Step-by-step recording and replay of matrix fills, for teaching.
+pub mod trace;
+
+/// Seeded random sequence generation.
+pub mod generate;
+
+/// Composition-preserving sequence shuffling, for null models.
+pub mod shuffle;
+
+/// Mutation simulation with ground-truth edits.
+pub mod mutate;
+
+/// Estimation of scoring parameters from trusted alignments.
+pub mod fit;
+
+/// Multiple sequence alignment type with column-wise access.
+pub mod msa;
+
+/// All-vs-all identity matrices and their heatmap renderings.
+pub mod heatmap;
+
+/// All-vs-all distance matrices of sequence sets.
+pub mod distance;
+
+/// Lightweight profiling spans around matrix fills, tracebacks and batches.
+#[cfg(feature = "instrument")]
+pub mod instrument;
+
+/// Randomized generators and invariant checks for property testing.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+/// Single entry point aligning in any mode.
+pub mod align;
+
+/// Reproducible workloads shared by benchmarks and tests.
+pub mod fixtures;
+
+/// GenBank and EMBL flat file parsing.
+pub mod flatfile;
+
+/// Alignment of sequences annotated with per-position label tracks.
+pub mod annotation;
+
+/// Pair-HMM posterior probabilities and maximum expected accuracy alignment.
+pub mod pairhmm;
+
+/// Banded global alignment, with automatic band estimation.
+pub mod banded;
+
+/// Score-only alignment of many independent pairs in lockstep.
+pub mod lockstep;
+
+/// Alignment with regions of either sequence excluded from matches.
+pub mod mask;
+
+/// Clustering of local hits into groups along nearby diagonals.
+pub mod cluster;
+
+/// Global alignment with two-piece affine gap penalties.
+pub mod two_piece;
+
+/// Dense integer encoding of letters and table-driven scoring.
+pub mod encoding;
+
+/// Memoization of alignment results.
+pub mod cache;
+
+/// Long-running server answering alignment requests given as JSON lines.
+#[cfg(feature = "daemon")]
+pub mod daemon;
+
+/// Minimal HTTP endpoint serving alignment, batch and search requests.
+#[cfg(feature = "http")]
+pub mod http;
+
+/// Adapter and quality trimming of reads.
+pub mod trim;
+
+/// Local alignment against several reference records at once.
+pub mod multiref;
+
+/// Needleman-Wunsch fills that can be saved to disk and resumed.
+pub mod resume;
+
+/// Resource usage of individual alignments.
+pub mod metrics;
+
+/// PHYLIP writers for distance matrices and multiple alignments.
+pub mod phylip;
+
+/// Semi-global (fitting) alignment of reads within longer references.
+pub mod semiglobal;
+
+/// Explicit traceback pointers and lazy tracebacks over filled matrices.
+pub mod traceback;
+
+/// CIGAR encoding of alignments, for interoperability with SAM tooling.
+pub mod cigar;
+
+/// Alignments as sequences of column operations.
+pub mod ops;
+
+/// Protein residues, validated and scored by direct table lookups.
+pub mod protein;
+
+/// DNA and RNA bases, with conversion between them.
+pub mod nucleotide;
+
+/// IUPAC nucleotide ambiguity codes matching any compatible base.
+pub mod iupac;
+
+/// Six-frame translated alignment of nucleotide queries against proteins.
+pub mod translated;
+
+/// Alignment of sequencing reads weighted by their Phred qualities.
+pub mod quality;
+
+/// Alignment of sequences against position-specific scoring matrices.
+pub mod pssm;
+
+/// All-vs-all and one-vs-all alignment drivers, optionally multithreaded.
+pub mod batch;
+
+/// Aligner reusing its matrix buffer across alignments.
+pub mod aligner;
+
+/// Levenshtein edit distance.
+pub mod edit;
+
+/// Hamming distance and gapless local alignment, as a pre-filter.
+pub mod ungapped;
+
+/// X-drop gapped extension of seeds, BLAST style.
+pub mod xdrop;
+
+/// Seed-and-extend alignment of long sequences from chained k-mer matches.
+pub mod heuristic;
+
+/// K-mer and minimizer indexes of target sequences.
+pub mod index;
+
+/// Wavefront alignment (WFA) under gap-affine penalties.
+pub mod wfa;
+
+/// Score-only alignment with 16-bit cells, widened only where needed.
+pub mod narrow;
+
+/// SVG and PNG heatmaps of alignment matrices.
+#[cfg(feature = "image")]
+pub mod image;
@@ -1,13 +1,33 @@
-use std::fmt;
+use std::{cmp::Reverse, collections::HashSet, fmt, ops::Range};
 
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
 use crate::{
-    letter::{Letter, NormalizeLetter, GAP},
+    arena::Arena,
+    cigar::Cigar,
+    global::{needleman_wunsch, GlobalAlignmentConfig},
+    letter::{letter_or_gap, GenericLetter, Letter},
     matrix::AlignmentMatrix,
-    score::Score,
+    ops::AlignmentOps,
+    report::{
+        count_letters,
+        write_blast_tabular_line,
+        write_blocks,
+        write_clustal_pair,
+        write_emboss_pair,
+        write_fasta_pair,
+        HeaderVerbosity,
+        PrettyStyle,
+        WriteReport,
+    },
+    score::{Score, ScoringScheme},
+    search::KarlinAltschul,
+    trace::{CellStep, FillTrace},
+    traceback::{TracebackIter, TracebackMatrix, TracebackPointer},
 };
 
 /// Penalty/base score system of a global alignment.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LocalAlignmentConfig {
     /// Added when letters match.
     pub match_penalty: Score,
@@ -23,11 +43,28 @@ impl Default for LocalAlignmentConfig {
     }
 }
 
+impl<L> ScoringScheme<L> for LocalAlignmentConfig
+where
+    L: GenericLetter,
+{
+    fn score(&self, row_letter: L, column_letter: L) -> Score {
+        if row_letter == column_letter {
+            self.match_penalty
+        } else {
+            self.mismatch_penalty
+        }
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
 /// An aligned sequence, used in local alignment results.
 ///
 /// Corresponds to a slice of an input sequence, possibly with gaps inserted.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LocallyAlignedSeq {
+pub struct LocallyAlignedSeq<L = Letter> {
     /// Position in the input sequence that delimits where the local alignment
     /// starts.
     pub start: usize,
@@ -35,35 +72,210 @@ pub struct LocallyAlignedSeq {
     /// ends.
     pub end: usize,
     /// The aligned slice of the input sequence, with potential gaps.
-    pub data: Vec<Letter>,
+    pub data: Vec<L>,
 }
 
 /// A local alignment, computed by Smith-Waterman.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LocalAlignmentResult {
+pub struct LocalAlignmentResult<L = Letter> {
     /// The aligned version of the input sequence that was associated with a
     /// "row" display in the matrix. It is aligned with the sequence displayed
     /// as a "column".
-    pub aligned_row_seq: LocallyAlignedSeq,
+    pub aligned_row_seq: LocallyAlignedSeq<L>,
     /// The aligned version of the input sequence that was associated with a
     /// "column" display in the matrix. It is aligned with the sequence
     /// displayed as a "row".
-    pub aligned_column_seq: LocallyAlignedSeq,
+    pub aligned_column_seq: LocallyAlignedSeq<L>,
     /// Total score of the alignment.
     pub score: Score,
     /// Numerator of the identity fraction (32-bit).
     pub identity_numer: u32,
     /// Denominator of the identity fraction (32-bit).
     pub identity_denom: u32,
+    /// Confidence that this is the right placement among all hits of the
+    /// query, from 0 (ambiguous) to [`MAX_MAPPING_QUALITY`].
+    pub mapping_quality: u8,
 }
 
-impl LocalAlignmentResult {
+impl<L> LocalAlignmentResult<L> {
     /// Computes the identity as a percentage.
     pub fn identity(&self) -> f64 {
         f64::from(self.identity_numer) / f64::from(self.identity_denom)
     }
 }
 
+impl LocalAlignmentResult {
+    /// Encodes the alignment as a CIGAR string of `M`, `I` and `D`
+    /// operations, taking the row sequence as the reference. Only the aligned
+    /// slices are encoded, without clipping.
+    pub fn cigar(&self) -> Cigar {
+        Cigar::from_aligned(
+            &self.aligned_row_seq.data,
+            &self.aligned_column_seq.data,
+            false,
+        )
+    }
+
+    /// Encodes the alignment as a CIGAR string telling matches (`=`) and
+    /// mismatches (`X`) apart.
+    pub fn extended_cigar(&self) -> Cigar {
+        Cigar::from_aligned(
+            &self.aligned_row_seq.data,
+            &self.aligned_column_seq.data,
+            true,
+        )
+    }
+
+    /// Converts the alignment into column operations.
+    pub fn alignment_ops(&self) -> AlignmentOps {
+        AlignmentOps::from_aligned(
+            &self.aligned_row_seq.data,
+            &self.aligned_column_seq.data,
+        )
+    }
+}
+
+/// Mapping quality of a hit with no competing hit at all.
+pub const MAX_MAPPING_QUALITY: u8 = 60;
+
+/// Estimates a mapping quality from the best score of a query and the score of
+/// the runner-up hit, scaling the relative gap between both scores.
+pub fn mapping_quality(best_score: Score, second_best: Option<Score>) -> u8 {
+    if best_score <= 0 {
+        return 0;
+    }
+    let Some(second_best) = second_best else {
+        return MAX_MAPPING_QUALITY;
+    };
+    let gap = (best_score - second_best.max(0)).max(0);
+    let quality = Score::from(MAX_MAPPING_QUALITY) * gap / best_score;
+    quality.clamp(0, Score::from(MAX_MAPPING_QUALITY)) as u8
+}
+
+/// Assigns mapping qualities to all hits of the same query.
+///
+/// The single best hit is scored against the runner-up. Hits tied for the best
+/// score, as well as any hit below the best one, are ambiguous placements and
+/// get a zero quality.
+pub fn assign_mapping_qualities<L>(results: &mut [LocalAlignmentResult<L>])
+where
+    L: GenericLetter,
+{
+    assign_mapping_qualities_against(results, None);
+}
+
+/// Assigns mapping qualities like [`assign_mapping_qualities`], but against
+/// the runner-up placement in the Smith-Waterman matrix the results were
+/// traced back from, even if it is not among the results: the best cell whose
+/// alignment starts elsewhere than the alignments with the best score.
+///
+/// The tracebacks only compare the hits they return, which is meaningless
+/// when a single best hit is returned. This finds the runner-up at the cost
+/// of an index per matrix cell, so it is only done on request.
+pub fn assign_matrix_mapping_qualities<L, S>(
+    results: &mut [LocalAlignmentResult<L>],
+    matrix: &AlignmentMatrix,
+    scheme: S,
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let gap_penalty = scheme.gap_penalty();
+    let runner_up =
+        runner_up_score(matrix, |i, j| sw_step(matrix, gap_penalty, i, j));
+    assign_mapping_qualities_against(results, runner_up);
+}
+
+/// Assigns mapping qualities like [`assign_mapping_qualities`], also taking
+/// into account the score of a runner-up hit that may not be among the
+/// results.
+fn assign_mapping_qualities_against<L>(
+    results: &mut [LocalAlignmentResult<L>],
+    runner_up: Option<Score>,
+) where
+    L: GenericLetter,
+{
+    let Some(best_score) = results.iter().map(|result| result.score).max()
+    else {
+        return;
+    };
+    let best_count =
+        results.iter().filter(|result| result.score == best_score).count();
+    let second_best = results
+        .iter()
+        .map(|result| result.score)
+        .filter(|score| *score < best_score)
+        .chain(runner_up)
+        .max();
+    for result in results {
+        result.mapping_quality =
+            if result.score == best_score && best_count == 1 {
+                mapping_quality(best_score, second_best)
+            } else {
+                0
+            };
+    }
+}
+
+/// Score of the runner-up placement in a Smith-Waterman matrix: the best
+/// cell whose alignment starts at a different cell from the alignments with
+/// the best score, whose prefixes and extensions are thus not counted.
+/// `step` gives the direction taken by the traceback at a positive cell.
+fn runner_up_score<F>(matrix: &AlignmentMatrix, mut step: F) -> Option<Score>
+where
+    F: FnMut(usize, usize) -> TracebackStep,
+{
+    let width = matrix.width();
+    let best_score = matrix.max().filter(|&score| score > 0)?;
+    // start of the alignment ending at each cell, as a row-major index
+    let mut starts = vec![0; width * matrix.height()];
+    for (i, j, score) in matrix.enumerate_cells() {
+        let index = i * width + j;
+        starts[index] = if score <= 0 {
+            index
+        } else {
+            match step(i, j) {
+                TracebackStep::TopLeft => starts[index - width - 1],
+                TracebackStep::Top => starts[index - width],
+                TracebackStep::Left => starts[index - 1],
+            }
+        };
+    }
+    let best_starts: HashSet<usize> = matrix
+        .enumerate_cells()
+        .filter(|&(_, _, score)| score == best_score)
+        .map(|(i, j, _)| starts[i * width + j])
+        .collect();
+    matrix
+        .enumerate_cells()
+        .filter(|&(i, j, score)| {
+            score > 0
+                && score < best_score
+                && !best_starts.contains(&starts[i * width + j])
+        })
+        .map(|(_, _, score)| score)
+        .max()
+}
+
+/// Direction taken by the traceback of a Smith-Waterman matrix at a cell with
+/// a positive score: a gap if the score came from one, a diagonal step
+/// otherwise.
+fn sw_step(
+    matrix: &AlignmentMatrix,
+    gap_penalty: Score,
+    i: usize,
+    j: usize,
+) -> TracebackStep {
+    let score = matrix[[i, j]];
+    if i > 0 && score == matrix[[i - 1, j]] + gap_penalty {
+        TracebackStep::Top
+    } else if j > 0 && score == matrix[[i, j - 1]] + gap_penalty {
+        TracebackStep::Left
+    } else {
+        TracebackStep::TopLeft
+    }
+}
+
 /// Possible directions during traceback phase.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum TracebackStep {
@@ -80,23 +292,508 @@ enum TracebackStep {
 /// `row_seq` and `column_seq` are the sequences to be aligned.
 /// `row_seq` will be displayed as a row in the matrix, while `column_seq` will
 /// be displayed as a column in the matrix.
-pub fn best_smith_waterman(
+///
+/// Any [`ScoringScheme`] may be used, e.g. a [`LocalAlignmentConfig`] or a
+/// substitution matrix.
+pub fn best_smith_waterman<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> Vec<LocalAlignmentResult<L>>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let matrix = compute_sw_matrix(row_seq, column_seq, &scheme);
+    traceback_best_sw_alignment(row_seq, column_seq, &scheme, &matrix)
+}
+
+/// Best Smith-Waterman score and where it was reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LocalScore {
+    /// Score of the best local alignment.
+    pub score: Score,
+    /// Matrix cell of the best score, i.e. the exclusive ends of the best
+    /// alignment in the row and column sequences. The first such cell, in
+    /// row-major order, is reported; it is `(0, 0)` when nothing scores
+    /// positively.
+    pub end: (usize, usize),
+}
+
+/// Computes only the best Smith-Waterman score and its position, without
+/// traceback, keeping two matrix rows in memory instead of the whole matrix.
+pub fn sw_score_only<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> LocalScore
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let gap_penalty = scheme.gap_penalty();
+    let mut previous = vec![0; column_seq.len() + 1];
+    let mut current = vec![0; column_seq.len() + 1];
+    let mut best = LocalScore { score: 0, end: (0, 0) };
+    for (i, &row_letter) in row_seq.iter().enumerate() {
+        for (j, &column_letter) in column_seq.iter().enumerate() {
+            let no_gap_score =
+                previous[j] + scheme.score(row_letter, column_letter);
+            let best_gap_score = previous[j + 1].max(current[j]) + gap_penalty;
+            let score = no_gap_score.max(best_gap_score).max(0);
+            current[j + 1] = score;
+            if score > best.score {
+                best = LocalScore { score, end: (i + 1, j + 1) };
+            }
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    best
+}
+
+/// Given Smit-Waterman input and a score matrix already populated, this
+/// function computes the alignment.
+pub fn traceback_best_sw_alignment<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+    matrix: &AlignmentMatrix,
+) -> Vec<LocalAlignmentResult<L>>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    #[cfg(feature = "instrument")]
+    let mut span = Span::enter("smith_waterman::traceback", 0);
+    let mut results = Vec::new();
+    for end in matrix.argmax_many() {
+        let result =
+            traceback_sw_from(row_seq, column_seq, &scheme, matrix, end);
+        #[cfg(feature = "instrument")]
+        span.add_cells(result.aligned_row_seq.data.len());
+        results.push(result);
+    }
+    assign_mapping_qualities(&mut results);
+    results
+}
+
+/// Computes up to `k` Smith-Waterman local alignments, ranked by decreasing
+/// score and then by start coordinates, instead of every alignment tied for
+/// the best score, which may be too many for repetitive sequences.
+///
+/// Alignments starting at the same cell as a better ranked one, e.g. the
+/// prefixes of the best alignment, are skipped. Cells scoring zero are never
+/// reported.
+pub fn top_k_smith_waterman<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: S,
+    k: usize,
+) -> Vec<LocalAlignmentResult>
+where
+    S: ScoringScheme,
+{
+    let matrix = compute_sw_matrix(row_seq, column_seq, &scheme);
+    traceback_top_k_sw_alignments(row_seq, column_seq, &scheme, &matrix, k)
+}
+
+/// Given Smith-Waterman input and a score matrix already populated, this
+/// function computes the `k` best alignments, as in [`top_k_smith_waterman`].
+pub fn traceback_top_k_sw_alignments<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: S,
+    matrix: &AlignmentMatrix,
+    k: usize,
+) -> Vec<LocalAlignmentResult>
+where
+    S: ScoringScheme,
+{
+    let mut ends: Vec<(Score, (usize, usize))> = (0 .. matrix.height())
+        .flat_map(|i| (0 .. matrix.width()).map(move |j| (i, j)))
+        .map(|end| (matrix[[end.0, end.1]], end))
+        .filter(|&(score, _)| score > 0)
+        .collect();
+    ends.sort_by_key(|&(score, _)| Reverse(score));
+
+    let mut used_starts = HashSet::new();
+    let mut chosen_ends = Vec::new();
+    for tied in ends.chunk_by(|left, right| left.0 == right.0) {
+        if chosen_ends.len() >= k {
+            break;
+        }
+        let mut placements: Vec<_> = tied
+            .iter()
+            .map(|&(_, end)| {
+                let mut iter = TracebackIter::local(
+                    row_seq, column_seq, &scheme, matrix, end,
+                );
+                iter.by_ref().for_each(drop);
+                (iter.position(), end)
+            })
+            .collect();
+        placements.sort();
+        for (start, end) in placements {
+            if chosen_ends.len() >= k {
+                break;
+            }
+            if used_starts.insert(start) {
+                chosen_ends.push(end);
+            }
+        }
+    }
+
+    let mut results: Vec<_> = chosen_ends
+        .into_iter()
+        .map(|end| traceback_sw_from(row_seq, column_seq, &scheme, matrix, end))
+        .collect();
+    assign_mapping_qualities(&mut results);
+    results
+}
+
+/// Computes every significant Smith-Waterman local alignment: one traceback
+/// is made from each matrix cell scoring above `threshold`, best scores
+/// first, and an alignment is skipped if its path shares a cell with a
+/// better one already reported, so a match is not reported again through
+/// each of its prefixes.
+pub fn smith_waterman_above<S>(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: S,
+    threshold: Score,
+) -> Vec<LocalAlignmentResult>
+where
+    S: ScoringScheme,
+{
+    let matrix = compute_sw_matrix(row_seq, column_seq, &scheme);
+    let mut ends: Vec<(Score, (usize, usize))> = (0 .. matrix.height())
+        .flat_map(|i| (0 .. matrix.width()).map(move |j| (i, j)))
+        .map(|end| (matrix[[end.0, end.1]], end))
+        .filter(|&(score, _)| score > threshold.max(0))
+        .collect();
+    ends.sort_by_key(|&(score, _)| Reverse(score));
+
+    let mut used_cells = HashSet::new();
+    let mut results = Vec::new();
+    for (_, end) in ends {
+        let iter =
+            TracebackIter::local(row_seq, column_seq, &scheme, &matrix, end);
+        let path: Vec<_> = iter
+            .map(|op| (op.i, op.j))
+            .filter(|&(i, j)| matrix[[i, j]] > 0)
+            .chain([end])
+            .collect();
+        if path.iter().any(|cell| used_cells.contains(cell)) {
+            continue;
+        }
+        used_cells.extend(path);
+        results.push(traceback_sw_from(
+            row_seq, column_seq, &scheme, &matrix, end,
+        ));
+    }
+    assign_mapping_qualities(&mut results);
+    results
+}
+
+/// Computes the Smith-Waterman alignment ending at the given cell.
+fn traceback_sw_from<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
+    matrix: &AlignmentMatrix,
+    (max_i, max_j): (usize, usize),
+) -> LocalAlignmentResult<L>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
+    let mut current_i = max_i;
+    let mut current_j = max_j;
+
+    let initial_capacity = row_seq.len() + column_seq.len();
+    let mut result = LocalAlignmentResult {
+        aligned_row_seq: LocallyAlignedSeq {
+            start: max_i,
+            end: max_i,
+            data: Vec::with_capacity(initial_capacity),
+        },
+        aligned_column_seq: LocallyAlignedSeq {
+            start: max_j,
+            end: max_j,
+            data: Vec::with_capacity(initial_capacity),
+        },
+        score: matrix[[max_i, max_j]],
+        identity_numer: 0,
+        identity_denom: 0,
+        mapping_quality: 0,
+    };
+
+    while matrix[[current_i, current_j]] != 0 {
+        match sw_step(matrix, scheme.gap_penalty(), current_i, current_j) {
+            TracebackStep::TopLeft => {
+                current_i -= 1;
+                current_j -= 1;
+                traceback_sw_top_left(
+                    row_seq,
+                    column_seq,
+                    &mut result,
+                    current_i,
+                    current_j,
+                );
+            },
+            TracebackStep::Top => {
+                current_i -= 1;
+                traceback_sw_top(row_seq, &mut result, current_i);
+            },
+            TracebackStep::Left => {
+                current_j -= 1;
+                traceback_sw_left(column_seq, &mut result, current_j);
+            },
+        }
+    }
+
+    result.aligned_row_seq.data.reverse();
+    result.aligned_column_seq.data.reverse();
+    result.identity_denom = result.identity_denom.max(1);
+    result
+}
+
+/// Region spanned by one or more overlapping local hits.
+#[derive(Debug, Clone)]
+struct HitSpan {
+    /// Range covered in the row sequence.
+    row_range: Range<usize>,
+    /// Range covered in the column sequence.
+    column_range: Range<usize>,
+    /// Index of the original hit, if the span was not merged with others.
+    single_hit: Option<usize>,
+}
+
+impl HitSpan {
+    /// Whether both ranges overlap with the ranges of another span.
+    fn overlaps(&self, other: &Self) -> bool {
+        self.row_range.start < other.row_range.end
+            && other.row_range.start < self.row_range.end
+            && self.column_range.start < other.column_range.end
+            && other.column_range.start < self.column_range.end
+    }
+
+    /// Extends this span so that it also covers another span.
+    fn absorb(&mut self, other: &Self) {
+        self.row_range.start = self.row_range.start.min(other.row_range.start);
+        self.row_range.end = self.row_range.end.max(other.row_range.end);
+        self.column_range.start =
+            self.column_range.start.min(other.column_range.start);
+        self.column_range.end =
+            self.column_range.end.max(other.column_range.end);
+        self.single_hit = None;
+    }
+}
+
+/// Merges local hits that overlap on both sequences into single combined
+/// regions.
+///
+/// Hits that do not overlap any other hit are kept as they are. A merged region
+/// is realigned end-to-end over its whole span, so its score, identity and
+/// gapped data are recomputed. Mapping qualities are reassigned afterwards.
+pub fn merge_overlapping_hits(
     row_seq: &[Letter],
     column_seq: &[Letter],
     config: LocalAlignmentConfig,
+    results: &[LocalAlignmentResult],
 ) -> Vec<LocalAlignmentResult> {
-    let matrix = compute_sw_matrix(row_seq, column_seq, config);
-    traceback_best_sw_alignment(row_seq, column_seq, config, &matrix)
+    let mut spans: Vec<HitSpan> = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| HitSpan {
+            row_range: result.aligned_row_seq.start
+                .. result.aligned_row_seq.end,
+            column_range: result.aligned_column_seq.start
+                .. result.aligned_column_seq.end,
+            single_hit: Some(index),
+        })
+        .collect();
+
+    let mut i = 0;
+    while i < spans.len() {
+        let mut j = i + 1;
+        let mut merged_any = false;
+        while j < spans.len() {
+            if spans[i].overlaps(&spans[j]) {
+                let other = spans.remove(j);
+                spans[i].absorb(&other);
+                merged_any = true;
+            } else {
+                j += 1;
+            }
+        }
+        if !merged_any {
+            i += 1;
+        }
+    }
+
+    let global_config = GlobalAlignmentConfig {
+        match_penalty: config.match_penalty,
+        mismatch_penalty: config.mismatch_penalty,
+        gap_penalty: config.gap_penalty,
+    };
+    let mut merged: Vec<LocalAlignmentResult> = spans
+        .into_iter()
+        .map(|span| match span.single_hit {
+            Some(index) => results[index].clone(),
+            None => {
+                let global = needleman_wunsch(
+                    &row_seq[span.row_range.clone()],
+                    &column_seq[span.column_range.clone()],
+                    global_config,
+                );
+                LocalAlignmentResult {
+                    aligned_row_seq: LocallyAlignedSeq {
+                        start: span.row_range.start,
+                        end: span.row_range.end,
+                        data: global.aligned_row_seq,
+                    },
+                    aligned_column_seq: LocallyAlignedSeq {
+                        start: span.column_range.start,
+                        end: span.column_range.end,
+                        data: global.aligned_column_seq,
+                    },
+                    score: global.score,
+                    identity_numer: global.identity_numer,
+                    identity_denom: global.identity_denom,
+                    mapping_quality: 0,
+                }
+            },
+        })
+        .collect();
+    assign_mapping_qualities(&mut merged);
+    merged
 }
 
-/// Given Smit-Waterman input and a score matrix already populated, this
-/// function computes the alignment.
-pub fn traceback_best_sw_alignment(
+/// This function fills a Smith-Waterman score matrix.
+pub fn compute_sw_matrix<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> AlignmentMatrix
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    #[cfg(feature = "instrument")]
+    let _span = Span::enter("smith_waterman::fill", row_count * column_count);
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    fill_sw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        None,
+        None,
+    );
+    matrix
+}
+
+/// Computes the best local alignments like [`best_smith_waterman`], taking the
+/// matrix from an arena. The matrix goes back to the arena when done.
+pub fn smith_waterman_in<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+    arena: &mut Arena,
+) -> Vec<LocalAlignmentResult<L>>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let mut matrix = arena.matrix(row_seq.len() + 1, column_seq.len() + 1);
+    fill_sw_matrix(row_seq, column_seq, &scheme, &mut matrix);
+    let results =
+        traceback_best_sw_alignment(row_seq, column_seq, &scheme, &matrix);
+    arena.recycle_matrix(matrix);
+    results
+}
+
+/// Fills a zeroed Smith-Waterman matrix of dimensions
+/// `(row_seq.len() + 1) X (column_seq.len() + 1)` allocated by the caller.
+pub(crate) fn fill_sw_matrix<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
+    matrix: &mut AlignmentMatrix,
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
+    fill_sw_matrix_content(row_seq, column_seq, scheme, matrix, None, None);
+}
+
+/// Fills a Smith-Waterman score matrix like [`compute_sw_matrix`], while also
+/// recording the candidates of every cell, for step-by-step replay.
+pub fn compute_sw_matrix_traced<S>(
     row_seq: &[Letter],
     column_seq: &[Letter],
-    config: LocalAlignmentConfig,
+    scheme: S,
+) -> (AlignmentMatrix, FillTrace)
+where
+    S: ScoringScheme,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    let mut trace = FillTrace::new(matrix.clone());
+    fill_sw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        Some(&mut trace),
+        None,
+    );
+    (matrix, trace)
+}
+
+/// Fills a Smith-Waterman score matrix like [`compute_sw_matrix`], while
+/// also recording the direction chosen at every cell, for
+/// [`traceback_sw_pointers`].
+pub fn compute_sw_matrix_with_pointers<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> (AlignmentMatrix, TracebackMatrix)
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let row_count = row_seq.len() + 1;
+    let column_count = column_seq.len() + 1;
+    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
+    let mut pointers = TracebackMatrix::new(row_count, column_count);
+    fill_sw_matrix_content(
+        row_seq,
+        column_seq,
+        &scheme,
+        &mut matrix,
+        None,
+        Some(&mut pointers),
+    );
+    (matrix, pointers)
+}
+
+/// Computes the best alignments by following the pointers recorded by
+/// [`compute_sw_matrix_with_pointers`] from every cell with the best score,
+/// instead of comparing neighbor scores.
+pub fn traceback_sw_pointers<L>(
+    row_seq: &[L],
+    column_seq: &[L],
     matrix: &AlignmentMatrix,
-) -> Vec<LocalAlignmentResult> {
+    pointers: &TracebackMatrix,
+) -> Vec<LocalAlignmentResult<L>>
+where
+    L: GenericLetter,
+{
     let mut results = Vec::new();
     for (max_i, max_j) in matrix.argmax_many() {
         let mut current_i = max_i;
@@ -117,29 +814,13 @@ pub fn traceback_best_sw_alignment(
             score: matrix[[max_i, max_j]],
             identity_numer: 0,
             identity_denom: 0,
+            mapping_quality: 0,
         };
 
-        while matrix[[current_i, current_j]] != 0 {
-            let current_score = matrix[[current_i, current_j]];
-            let mut maybe_step = None;
-            if current_i > 0 {
-                let previous_score = matrix[[current_i - 1, current_j]];
-                let penalty = config.gap_penalty;
-                if current_score == previous_score + penalty {
-                    maybe_step = Some(TracebackStep::Top);
-                }
-            }
-            if maybe_step.is_none() && current_j > 0 {
-                let previous_score = matrix[[current_i, current_j - 1]];
-                let penalty = config.gap_penalty;
-                if current_score == previous_score + penalty {
-                    maybe_step = Some(TracebackStep::Left);
-                }
-            }
-            let step = maybe_step.unwrap_or(TracebackStep::TopLeft);
-
-            match step {
-                TracebackStep::TopLeft => {
+        loop {
+            match pointers[[current_i, current_j]] {
+                TracebackPointer::Stop => break,
+                TracebackPointer::Diagonal => {
                     current_i -= 1;
                     current_j -= 1;
                     traceback_sw_top_left(
@@ -150,82 +831,57 @@ pub fn traceback_best_sw_alignment(
                         current_j,
                     );
                 },
-                TracebackStep::Top => {
+                TracebackPointer::Top => {
                     current_i -= 1;
                     traceback_sw_top(row_seq, &mut result, current_i);
                 },
-                TracebackStep::Left => {
+                TracebackPointer::Left => {
                     current_j -= 1;
                     traceback_sw_left(column_seq, &mut result, current_j);
                 },
             }
-
-            let top_left = matrix[[current_i - 1, current_j - 1]];
-            let top = matrix[[current_i - 1, current_j]];
-            let left = matrix[[current_i, current_j - 1]];
-            let maximum = top_left.max(top).max(left);
-
-            if current_i > 0
-                && current_j > 0
-                && (top_left == maximum || top_left == 0)
-            {
-                current_i -= 1;
-                current_j -= 1;
-                traceback_sw_top_left(
-                    row_seq,
-                    column_seq,
-                    &mut result,
-                    current_i,
-                    current_j,
-                );
-            } else if current_i > 0 && (top == maximum || top == 0) {
-                current_i -= 1;
-                traceback_sw_top(row_seq, &mut result, current_i);
-            } else {
-                current_j -= 1;
-                traceback_sw_left(column_seq, &mut result, current_j);
-            }
         }
 
         result.aligned_row_seq.data.reverse();
         result.aligned_column_seq.data.reverse();
         result.identity_denom = result.identity_denom.max(1);
-
         results.push(result);
     }
+    assign_mapping_qualities(&mut results);
     results
 }
 
-/// This function fills a Smith-Waterman score matrix.
-pub fn compute_sw_matrix(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: LocalAlignmentConfig,
-) -> AlignmentMatrix {
-    let row_count = row_seq.len() + 1;
-    let column_count = column_seq.len() + 1;
-    let mut matrix = AlignmentMatrix::zeroed(row_count, column_count);
-    fill_sw_matrix_content(row_seq, column_seq, config, &mut matrix);
-    matrix
-}
-
 /// This function fills the scores of a Smith-Waterman matrix.
-fn fill_sw_matrix_content(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: LocalAlignmentConfig,
+fn fill_sw_matrix_content<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &mut AlignmentMatrix,
-) {
+    mut trace: Option<&mut FillTrace>,
+    mut pointers: Option<&mut TracebackMatrix>,
+) where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
     let mut base_i = 0;
     let mut base_j = 0;
     loop {
-        if base_j >= column_seq.len() {
+        if base_i >= row_seq.len() || base_j >= column_seq.len() {
             break;
         }
         for j in base_j .. column_seq.len() {
-            compute_sw_matrix_cell(
-                row_seq, column_seq, config, matrix, base_i, j,
+            let pointer = compute_sw_matrix_cell(
+                row_seq,
+                column_seq,
+                scheme,
+                matrix,
+                base_i,
+                j,
+                trace.as_deref_mut(),
             );
+            if let Some(pointers) = pointers.as_deref_mut() {
+                pointers[[base_i + 1, j + 1]] = pointer;
+            }
         }
         base_i += 1;
 
@@ -233,9 +889,18 @@ fn fill_sw_matrix_content(
             break;
         }
         for i in base_i .. row_seq.len() {
-            compute_sw_matrix_cell(
-                row_seq, column_seq, config, matrix, i, base_j,
+            let pointer = compute_sw_matrix_cell(
+                row_seq,
+                column_seq,
+                scheme,
+                matrix,
+                i,
+                base_j,
+                trace.as_deref_mut(),
             );
+            if let Some(pointers) = pointers.as_deref_mut() {
+                pointers[[i + 1, base_j + 1]] = pointer;
+            }
         }
         base_j += 1;
     }
@@ -243,82 +908,116 @@ fn fill_sw_matrix_content(
 
 /// Computes the score of an individual cell of a Smith-Waterman matrix,
 /// assuming that their top-left (pred_i, pred_j), top (pred_i, pred_j + 1) and
-/// left (pred_i + 1, pred_j) cells are already computed and filled.
-fn compute_sw_matrix_cell(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    config: LocalAlignmentConfig,
+/// left (pred_i + 1, pred_j) cells are already computed and filled. Returns
+/// the direction the traceback takes from the cell.
+fn compute_sw_matrix_cell<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: &S,
     matrix: &mut AlignmentMatrix,
     pred_i: usize,
     pred_j: usize,
-) {
+    trace: Option<&mut FillTrace>,
+) -> TracebackPointer
+where
+    L: GenericLetter,
+    S: ScoringScheme<L> + ?Sized,
+{
     let top_left = matrix[[pred_i, pred_j]];
     let top = matrix[[pred_i, pred_j + 1]];
     let left = matrix[[pred_i + 1, pred_j]];
 
-    let row_letter = row_seq.get(pred_i).normalize_letter();
-    let column_letter = column_seq.get(pred_j).normalize_letter();
-    let no_gap_penalty = if row_letter == column_letter {
-        config.match_penalty
-    } else {
-        config.mismatch_penalty
-    };
+    let row_letter = letter_or_gap(row_seq, pred_i);
+    let column_letter = letter_or_gap(column_seq, pred_j);
+    let no_gap_penalty = scheme.score(row_letter, column_letter);
     let no_gap_score = top_left + no_gap_penalty;
 
+    let gap_penalty = scheme.gap_penalty();
     let best_gap_neighbor = top.max(left);
-    let best_gap_score = best_gap_neighbor + config.gap_penalty;
+    let best_gap_score = best_gap_neighbor + gap_penalty;
+
+    let score = best_gap_score.max(no_gap_score).max(0);
+    matrix[[pred_i + 1, pred_j + 1]] = score;
 
-    matrix[[pred_i + 1, pred_j + 1]] = best_gap_score.max(no_gap_score).max(0);
+    if let Some(trace) = trace {
+        trace.record(CellStep::new_local(
+            pred_i + 1,
+            pred_j + 1,
+            no_gap_score,
+            top + gap_penalty,
+            left + gap_penalty,
+            score,
+        ));
+    }
+
+    if score == 0 {
+        TracebackPointer::Stop
+    } else if score == top + gap_penalty {
+        TracebackPointer::Top
+    } else if score == left + gap_penalty {
+        TracebackPointer::Left
+    } else {
+        TracebackPointer::Diagonal
+    }
 }
 
 /// Registers result of a traceback going to a previous top-left cell in a
 /// Smith-Waterman local alignment.
-fn traceback_sw_top_left(
-    row_seq: &[Letter],
-    column_seq: &[Letter],
-    result: &mut LocalAlignmentResult,
+fn traceback_sw_top_left<L>(
+    row_seq: &[L],
+    column_seq: &[L],
+    result: &mut LocalAlignmentResult<L>,
     current_i: usize,
     current_j: usize,
-) {
-    let row_letter = row_seq.get(current_i).normalize_letter();
-    let column_letter = column_seq.get(current_j).normalize_letter();
+) where
+    L: GenericLetter,
+{
+    let row_letter = letter_or_gap(row_seq, current_i);
+    let column_letter = letter_or_gap(column_seq, current_j);
     result.aligned_row_seq.start -= 1;
     result.aligned_row_seq.data.push(row_letter);
     result.aligned_column_seq.start -= 1;
     result.aligned_column_seq.data.push(column_letter);
     result.identity_denom += 1;
-    if row_letter == column_letter && row_letter != GAP {
+    if row_letter == column_letter && row_letter != L::GAP {
         result.identity_numer += 1;
     }
 }
 
 /// Registers result of a traceback going to a previous top cell in a
 /// Smith-Waterman local alignment.
-fn traceback_sw_top(
-    row_seq: &[Letter],
-    result: &mut LocalAlignmentResult,
+fn traceback_sw_top<L>(
+    row_seq: &[L],
+    result: &mut LocalAlignmentResult<L>,
     current_i: usize,
-) {
-    let row_letter = row_seq.get(current_i).normalize_letter();
+) where
+    L: GenericLetter,
+{
+    let row_letter = letter_or_gap(row_seq, current_i);
     result.aligned_row_seq.start -= 1;
     result.aligned_row_seq.data.push(row_letter);
-    result.aligned_column_seq.data.push(GAP);
+    result.aligned_column_seq.data.push(L::GAP);
 }
 
 /// Registers result of a traceback going to a previous left cell in a
 /// Smith-Waterman local alignment.
-fn traceback_sw_left(
-    column_seq: &[Letter],
-    result: &mut LocalAlignmentResult,
+fn traceback_sw_left<L>(
+    column_seq: &[L],
+    result: &mut LocalAlignmentResult<L>,
     current_j: usize,
-) {
-    let column_letter = column_seq.get(current_j).normalize_letter();
-    result.aligned_row_seq.data.push(GAP);
+) where
+    L: GenericLetter,
+{
+    let column_letter = letter_or_gap(column_seq, current_j);
+    result.aligned_row_seq.data.push(L::GAP);
     result.aligned_column_seq.start -= 1;
     result.aligned_column_seq.data.push(column_letter);
 }
 
 /// Pretty print formatting of _one_ local alignment, as in a report.
+///
+/// Each wrapped line is surrounded by the coordinates (starting from 1) of its
+/// first and last letters in the original sequences.
 #[derive(Debug, Clone, Copy)]
 pub struct PrettyPrintOne<'a> {
     /// Print name of the sequence that was associated with a row display.
@@ -329,82 +1028,64 @@ pub struct PrettyPrintOne<'a> {
     pub result: &'a LocalAlignmentResult,
     /// Maximum width in terms of characters.
     pub max_width: usize,
+    /// Symbols and layout of the report.
+    pub style: PrettyStyle<'a>,
 }
 
 impl<'a> fmt::Display for PrettyPrintOne<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let identity = (100_000.0 * self.result.identity()).round() / 1000.0;
-        write!(f, "# sequence above : {}\n", self.row_seq_name)?;
-        write!(f, "# sequence below : {}\n", self.column_seq_name)?;
-        write!(
-            f,
-            "# range above    : {}..{}\n",
-            self.result.aligned_row_seq.start, self.result.aligned_row_seq.end
-        )?;
-        write!(
-            f,
-            "# range below    : {}..{}\n",
-            self.result.aligned_column_seq.start,
-            self.result.aligned_column_seq.end
-        )?;
-        write!(f, "# identity       : {}%\n", identity)?;
-        write!(f, "# score          : {}\n", self.result.score)?;
-        write!(f, "\n")?;
-
-        let length = self
-            .result
-            .aligned_row_seq
-            .data
-            .len()
-            .max(self.result.aligned_column_seq.data.len());
-        let mut i = 0;
-        while i < length {
-            let block_start = i;
-            let block_end = length.min(block_start + self.max_width);
-            write!(f, "# block : {block_start}..{block_end}\n")?;
-            for k in block_start .. block_end {
-                write!(
-                    f,
-                    "{}",
-                    self.result.aligned_row_seq.data.get(k).normalize_letter()
-                )?;
-            }
-            write!(f, "\n")?;
-            for k in block_start .. block_end {
-                write!(
-                    f,
-                    "{}",
-                    self.result
-                        .aligned_column_seq
-                        .data
-                        .get(k)
-                        .normalize_letter()
-                )?;
-            }
+        if self.style.header >= HeaderVerbosity::Brief {
+            write!(f, "# sequence above : {}\n", self.row_seq_name)?;
+            write!(f, "# sequence below : {}\n", self.column_seq_name)?;
+        }
+        if self.style.header >= HeaderVerbosity::Full {
+            write!(
+                f,
+                "# range above    : {}..{}\n",
+                self.result.aligned_row_seq.start,
+                self.result.aligned_row_seq.end
+            )?;
+            write!(
+                f,
+                "# range below    : {}..{}\n",
+                self.result.aligned_column_seq.start,
+                self.result.aligned_column_seq.end
+            )?;
+            write!(f, "# identity       : {}%\n", identity)?;
+            write!(f, "# score          : {}\n", self.result.score)?;
+        }
+        if self.style.header >= HeaderVerbosity::Brief {
             write!(f, "\n")?;
-
-            let row_block =
-                &self.result.aligned_row_seq.data[block_start .. block_end];
-            let column_block =
-                &self.result.aligned_column_seq.data[block_start .. block_end];
-            let mut identity_iter = row_block.iter().zip(column_block);
-            while let Some(k) =
-                (&mut identity_iter).position(|(row_letter, column_letter)| {
-                    row_letter == column_letter
-                })
-            {
-                for _ in 0 .. k {
-                    write!(f, " ")?;
-                }
-                write!(f, "*")?;
-            }
-            write!(f, "\n\n")?;
-            i = block_end;
         }
-        Ok(())
+
+        write_blocks(
+            f,
+            &self.result.aligned_row_seq.data,
+            &self.result.aligned_column_seq.data,
+            self.result.aligned_row_seq.start,
+            self.result.aligned_column_seq.start,
+            self.max_width,
+            &self.style,
+        )
     }
 }
 
+impl WriteReport for PrettyPrintOne<'_> {}
+
+/// Order in which [`PrettyPrintMany`] prints the results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResultOrder {
+    /// Order of the given list.
+    Input,
+    /// Highest score first.
+    Score,
+    /// Leftmost start in the sequence above first, then in the sequence below.
+    Start,
+    /// Longest alignment first.
+    Length,
+}
+
 /// Pretty print in report formatting of all local alignment in a list of
 /// results.
 #[derive(Debug, Clone, Copy)]
@@ -417,14 +1098,61 @@ pub struct PrettyPrintMany<'a> {
     pub results: &'a [LocalAlignmentResult],
     /// Maximum width in terms of characters.
     pub max_width: usize,
+    /// Symbols and layout of the report.
+    pub style: PrettyStyle<'a>,
+    /// Order in which results are printed. Sorting is stable.
+    pub order: ResultOrder,
+    /// Whether to skip results that cover the same ranges with the same
+    /// aligned letters as an earlier one.
+    pub dedup: bool,
+    /// Maximum number of results printed, after sorting and deduplication.
+    pub limit: Option<usize>,
+}
+
+impl<'a> PrettyPrintMany<'a> {
+    /// The results that get printed, in printing order.
+    pub fn selected(&self) -> Vec<&'a LocalAlignmentResult> {
+        let mut selected: Vec<&LocalAlignmentResult> =
+            self.results.iter().collect();
+        match self.order {
+            ResultOrder::Input => (),
+            ResultOrder::Score => {
+                selected.sort_by_key(|result| std::cmp::Reverse(result.score))
+            },
+            ResultOrder::Start => selected.sort_by_key(|result| {
+                (result.aligned_row_seq.start, result.aligned_column_seq.start)
+            }),
+            ResultOrder::Length => selected.sort_by_key(|result| {
+                std::cmp::Reverse(result.aligned_row_seq.data.len())
+            }),
+        }
+        if self.dedup {
+            let mut kept: Vec<&LocalAlignmentResult> = Vec::new();
+            for result in selected {
+                let duplicate = kept.iter().any(|other| {
+                    other.aligned_row_seq == result.aligned_row_seq
+                        && other.aligned_column_seq == result.aligned_column_seq
+                });
+                if !duplicate {
+                    kept.push(result);
+                }
+            }
+            selected = kept;
+        }
+        if let Some(limit) = self.limit {
+            selected.truncate(limit);
+        }
+        selected
+    }
 }
 
 impl fmt::Display for PrettyPrintMany<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.results.is_empty() {
+        let selected = self.selected();
+        if selected.is_empty() {
             write!(f, "No local alignment found.")?;
         }
-        for (i, result) in self.results.iter().enumerate() {
+        for (i, result) in selected.into_iter().enumerate() {
             write!(f, "#### #### #### #### #### #### #### ####\n")?;
             write!(f, "Best local alignment #{i}\n")?;
             write!(f, "#### #### #### #### #### #### #### ####\n")?;
@@ -434,6 +1162,7 @@ impl fmt::Display for PrettyPrintMany<'_> {
                 row_seq_name: self.row_seq_name,
                 column_seq_name: self.column_seq_name,
                 max_width: self.max_width,
+                style: self.style,
             };
             write!(f, "{}\n", pretty_print_one)?;
         }
@@ -441,13 +1170,243 @@ impl fmt::Display for PrettyPrintMany<'_> {
     }
 }
 
+impl WriteReport for PrettyPrintMany<'_> {}
+
+/// Formats _one_ local alignment as an EMBOSS `water` report in `pair`
+/// format, for parsers of EMBOSS output. The sequence associated with a row
+/// display is the first one.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbossPrint<'a> {
+    /// Print name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Print name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished local alignment result.
+    pub result: &'a LocalAlignmentResult,
+}
+
+impl fmt::Display for EmbossPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_emboss_pair(
+            f,
+            "water",
+            [self.row_seq_name, self.column_seq_name],
+            [
+                &self.result.aligned_row_seq.data,
+                &self.result.aligned_column_seq.data,
+            ],
+            [
+                self.result.aligned_row_seq.start,
+                self.result.aligned_column_seq.start,
+            ],
+            self.result.score,
+        )
+    }
+}
+
+impl WriteReport for EmbossPrint<'_> {}
+
+/// Formats _one_ local alignment in CLUSTAL W format, readable by alignment
+/// viewers such as Jalview. Names are suffixed with the one-based inclusive
+/// range of the aligned slice, as in `name/11-27`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClustalPrint<'a> {
+    /// Print name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Print name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished local alignment result.
+    pub result: &'a LocalAlignmentResult,
+}
+
+impl fmt::Display for ClustalPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = &self.result.aligned_row_seq;
+        let column = &self.result.aligned_column_seq;
+        let row_name =
+            format!("{}/{}-{}", self.row_seq_name, row.start + 1, row.end);
+        let column_name = format!(
+            "{}/{}-{}",
+            self.column_seq_name,
+            column.start + 1,
+            column.end
+        );
+        write_clustal_pair(
+            f,
+            [&row_name, &column_name],
+            [&row.data, &column.data],
+        )
+    }
+}
+
+impl WriteReport for ClustalPrint<'_> {}
+
+/// Formats _one_ local alignment as two FASTA records of the aligned slices,
+/// gaps included.
+#[derive(Debug, Clone, Copy)]
+pub struct FastaPrint<'a> {
+    /// Name of the record of the sequence associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Name of the record of the sequence associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished local alignment result.
+    pub result: &'a LocalAlignmentResult,
+    /// Maximum number of letters per line, or `None` to write each sequence
+    /// on a single line.
+    pub line_width: Option<usize>,
+}
+
+impl fmt::Display for FastaPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_fasta_pair(
+            f,
+            [self.row_seq_name, self.column_seq_name],
+            [
+                &self.result.aligned_row_seq.data,
+                &self.result.aligned_column_seq.data,
+            ],
+            self.line_width,
+        )
+    }
+}
+
+impl WriteReport for FastaPrint<'_> {}
+
+/// Prints local alignments in the tab-separated format of BLAST `-outfmt 6`,
+/// one result per line, without header, taking the row sequence as the query.
+/// Coordinates are one-based and inclusive. E-values are computed over the
+/// search space of the two whole sequences.
+/**
+ * Example:
+```text
+query    subject    83.333    6    0    1    2    7    2    6    1.49e-5    22.2
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct TabularPrint<'a> {
+    /// Identifier of the sequence that was associated with a row display,
+    /// printed in the `qseqid` column.
+    pub row_seq_name: &'a str,
+    /// Whole length of the sequence that was associated with a row display.
+    pub row_seq_len: usize,
+    /// Identifier of the sequence that was associated with a column display,
+    /// printed in the `sseqid` column.
+    pub column_seq_name: &'a str,
+    /// Whole length of the sequence that was associated with a column display.
+    pub column_seq_len: usize,
+    /// Statistical parameters matching the scoring of the results.
+    pub statistics: KarlinAltschul,
+    /// Results to be printed, in order.
+    pub results: &'a [LocalAlignmentResult],
+}
+
+impl fmt::Display for TabularPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in self.results {
+            write_blast_tabular_line(
+                f,
+                [self.row_seq_name, self.column_seq_name],
+                result,
+                self.statistics.evalue(
+                    result.score,
+                    self.row_seq_len,
+                    self.column_seq_len,
+                ),
+                self.statistics.bit_score(result.score),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for TabularPrint<'_> {}
+
+/// Prints local alignments as a Multiple Alignment Format (MAF) file, one
+/// alignment block per result, for genome browser toolchains. Starts are
+/// zero-based and both sequences are on the forward strand.
+/**
+ * Example:
+```text
+##maf version=1
+
+a score=13
+s row    1 6 + 9 GTTGAC
+s column 1 5 + 8 GTT-AC
+
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct MafPrint<'a> {
+    /// Source name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Whole length of the sequence that was associated with a row display.
+    pub row_seq_len: usize,
+    /// Source name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// Whole length of the sequence that was associated with a column
+    /// display.
+    pub column_seq_len: usize,
+    /// Results to be printed, in order.
+    pub results: &'a [LocalAlignmentResult],
+}
+
+impl fmt::Display for MafPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "##maf version=1\n\n")?;
+        let name_width =
+            self.row_seq_name.len().max(self.column_seq_name.len());
+        for result in self.results {
+            write!(f, "a score={}\n", result.score)?;
+            for (name, seq, source_len) in [
+                (self.row_seq_name, &result.aligned_row_seq, self.row_seq_len),
+                (
+                    self.column_seq_name,
+                    &result.aligned_column_seq,
+                    self.column_seq_len,
+                ),
+            ] {
+                write!(
+                    f,
+                    "s {name:<name_width$} {} {} + {source_len} {}\n",
+                    seq.start,
+                    count_letters(&seq.data),
+                    seq.data.iter().collect::<String>(),
+                )?;
+            }
+            write!(f, "\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for MafPrint<'_> {}
+
 #[cfg(test)]
 mod test {
+    use crate::{
+        report::PrettyStyle,
+        search::KarlinAltschul,
+        trace::FillChoice,
+    };
+
     use super::{
+        assign_matrix_mapping_qualities,
         best_smith_waterman,
+        compute_sw_matrix,
+        compute_sw_matrix_traced,
+        mapping_quality,
+        merge_overlapping_hits,
+        smith_waterman_above,
+        sw_score_only,
+        top_k_smith_waterman,
         LocalAlignmentConfig,
         LocalAlignmentResult,
+        LocalScore,
         LocallyAlignedSeq,
+        MafPrint,
+        PrettyPrintMany,
+        ResultOrder,
+        TabularPrint,
     };
 
     #[test]
@@ -474,6 +1433,7 @@ mod test {
             score: 13,
             identity_numer: 5,
             identity_denom: 5,
+            mapping_quality: 60,
         }];
 
         let actual_result = best_smith_waterman(
@@ -484,4 +1444,238 @@ mod test {
 
         assert_eq!(actual_result, expected_result);
     }
+
+    #[test]
+    fn tabular_print_has_blast_columns() {
+        let row_seq = ['G', 'G', 'T', 'T', 'G', 'A', 'C', 'T', 'A'];
+        let column_seq = ['T', 'G', 'T', 'T', 'A', 'C', 'G', 'G'];
+        let config = LocalAlignmentConfig {
+            match_penalty: 3,
+            mismatch_penalty: -3,
+            gap_penalty: -2,
+        };
+        let results = best_smith_waterman(&row_seq, &column_seq, config);
+
+        let table = TabularPrint {
+            row_seq_name: "query",
+            row_seq_len: row_seq.len(),
+            column_seq_name: "subject",
+            column_seq_len: column_seq.len(),
+            statistics: KarlinAltschul::default(),
+            results: &results,
+        };
+        assert_eq!(
+            table.to_string(),
+            "query\tsubject\t83.333\t6\t0\t1\t2\t7\t2\t6\t1.49e-5\t22.2\n"
+        );
+    }
+
+    #[test]
+    fn maf_print_has_one_block_per_hit() {
+        let row_seq = ['G', 'G', 'T', 'T', 'G', 'A', 'C', 'T', 'A'];
+        let column_seq = ['T', 'G', 'T', 'T', 'A', 'C', 'G', 'G'];
+        let config = LocalAlignmentConfig {
+            match_penalty: 3,
+            mismatch_penalty: -3,
+            gap_penalty: -2,
+        };
+        let results = best_smith_waterman(&row_seq, &column_seq, config);
+
+        let maf = MafPrint {
+            row_seq_name: "row",
+            row_seq_len: row_seq.len(),
+            column_seq_name: "column",
+            column_seq_len: column_seq.len(),
+            results: &results,
+        };
+        assert_eq!(
+            maf.to_string(),
+            "##maf version=1\n\na score=13\ns row    1 6 + 9 GTTGAC\ns column \
+             1 5 + 8 GTT-AC\n\n"
+        );
+    }
+
+    #[test]
+    fn score_only_matches_the_best_alignment() {
+        let row_seq = ['G', 'G', 'T', 'T', 'G', 'A', 'C', 'T', 'A'];
+        let column_seq = ['T', 'G', 'T', 'T', 'A', 'C', 'G', 'G'];
+        let config = LocalAlignmentConfig {
+            match_penalty: 3,
+            mismatch_penalty: -3,
+            gap_penalty: -2,
+        };
+
+        assert_eq!(
+            sw_score_only(&row_seq, &column_seq, config),
+            LocalScore { score: 13, end: (7, 6) }
+        );
+    }
+
+    #[test]
+    fn top_k_limits_repetitive_ties() {
+        let row_seq: Vec<char> = "ACGTACGTACGTTTACGA".chars().collect();
+        let column_seq: Vec<char> = "ACGT".chars().collect();
+        let config = LocalAlignmentConfig::default();
+
+        let all = best_smith_waterman(&row_seq, &column_seq, config);
+        assert_eq!(all.len(), 3);
+        let top = top_k_smith_waterman(&row_seq, &column_seq, config, 2);
+        assert_eq!(top, all[.. 2]);
+
+        let top = top_k_smith_waterman(&row_seq, &column_seq, config, 4);
+        assert_eq!(top.len(), 4);
+        assert_eq!(top[.. 3], all[..]);
+        assert_eq!(top[3].aligned_row_seq.start, 14);
+        assert!(top[3].score < top[2].score);
+        assert!(top.iter().all(|result| result.mapping_quality == 0));
+    }
+
+    #[test]
+    fn threshold_reports_each_significant_match_once() {
+        let row_seq: Vec<char> = "GATTACACCCCCCTTGATTAC".chars().collect();
+        let column_seq: Vec<char> = "GATTACA".chars().collect();
+        let config = LocalAlignmentConfig::default();
+
+        let results = smith_waterman_above(&row_seq, &column_seq, config, 4);
+        let starts: Vec<_> = results
+            .iter()
+            .map(|result| (result.aligned_row_seq.start, result.score))
+            .collect();
+        assert_eq!(starts, vec![(0, 7), (15, 6)]);
+        assert_eq!(results[0].mapping_quality, 8);
+        // the runner-up counts even when only the best hit is reported
+        let mut best = best_smith_waterman(&row_seq, &column_seq, config);
+        assert_eq!(best.len(), 1);
+        assert_eq!(best[0].mapping_quality, 60);
+        let matrix = compute_sw_matrix(&row_seq, &column_seq, config);
+        assign_matrix_mapping_qualities(&mut best, &matrix, config);
+        assert_eq!(best[0].mapping_quality, 8);
+        assert!(
+            smith_waterman_above(&row_seq, &column_seq, config, 7).is_empty()
+        );
+    }
+
+    #[test]
+    fn mapping_quality_from_score_gap() {
+        assert_eq!(mapping_quality(10, None), 60);
+        assert_eq!(mapping_quality(10, Some(10)), 0);
+        assert_eq!(mapping_quality(10, Some(5)), 30);
+        assert_eq!(mapping_quality(0, None), 0);
+    }
+
+    #[test]
+    fn merge_two_overlapping_hits() {
+        let input_row_seq = ['A', 'C', 'G', 'T', 'A', 'C', 'G', 'T'];
+        let input_column_seq = ['C', 'G', 'T', 'A', 'C'];
+        let input_config = LocalAlignmentConfig::default();
+        let hit = |row_start, row_end, column_start, column_end, score| {
+            LocalAlignmentResult {
+                aligned_row_seq: LocallyAlignedSeq {
+                    start: row_start,
+                    end: row_end,
+                    data: input_row_seq[row_start .. row_end].to_vec(),
+                },
+                aligned_column_seq: LocallyAlignedSeq {
+                    start: column_start,
+                    end: column_end,
+                    data: input_column_seq[column_start .. column_end].to_vec(),
+                },
+                score,
+                identity_numer: 3,
+                identity_denom: 3,
+                mapping_quality: 0,
+            }
+        };
+        let input_results =
+            vec![hit(1, 4, 0, 3, 3), hit(2, 5, 1, 4, 3), hit(6, 7, 1, 2, 1)];
+
+        let actual_result = merge_overlapping_hits(
+            &input_row_seq[..],
+            &input_column_seq[..],
+            input_config,
+            &input_results,
+        );
+
+        assert_eq!(actual_result.len(), 2);
+        assert_eq!(
+            actual_result[0].aligned_row_seq.data,
+            vec!['C', 'G', 'T', 'A']
+        );
+        assert_eq!(
+            actual_result[0].aligned_column_seq.data,
+            vec!['C', 'G', 'T', 'A']
+        );
+        assert_eq!(actual_result[0].score, 4);
+        assert_eq!(actual_result[0].mapping_quality, 45);
+        assert_eq!(actual_result[1], input_results[2]);
+    }
+
+    #[test]
+    fn pretty_print_many_sorts_dedups_and_limits() {
+        let hit = |start: usize, letters: &str, score| {
+            let data: Vec<char> = letters.chars().collect();
+            LocalAlignmentResult {
+                aligned_row_seq: LocallyAlignedSeq {
+                    start,
+                    end: start + data.len(),
+                    data: data.clone(),
+                },
+                aligned_column_seq: LocallyAlignedSeq {
+                    start: 0,
+                    end: data.len(),
+                    data,
+                },
+                score,
+                identity_numer: 1,
+                identity_denom: 1,
+                mapping_quality: 0,
+            }
+        };
+        let input_results =
+            vec![hit(5, "AC", 2), hit(0, "ACGT", 4), hit(5, "AC", 2)];
+        let mut pretty_print = PrettyPrintMany {
+            row_seq_name: "a",
+            column_seq_name: "b",
+            results: &input_results,
+            max_width: 80,
+            style: PrettyStyle::default(),
+            order: ResultOrder::Input,
+            dedup: false,
+            limit: None,
+        };
+        assert_eq!(pretty_print.selected().len(), 3);
+
+        pretty_print.order = ResultOrder::Score;
+        pretty_print.dedup = true;
+        let selected = pretty_print.selected();
+        assert_eq!(selected, vec![&input_results[1], &input_results[0]]);
+
+        pretty_print.order = ResultOrder::Start;
+        pretty_print.limit = Some(1);
+        assert_eq!(pretty_print.selected(), vec![&input_results[1]]);
+    }
+
+    #[test]
+    fn zero_floor_is_recorded_before_ties() {
+        let config = LocalAlignmentConfig {
+            match_penalty: 3,
+            mismatch_penalty: -3,
+            gap_penalty: -3,
+        };
+        let (_, trace) = compute_sw_matrix_traced(&['A', 'C'], &['A'], config);
+        let step =
+            trace.steps.iter().find(|step| (step.i, step.j) == (2, 1)).unwrap();
+        assert_eq!(step.top, 0);
+        assert_eq!(step.score, 0);
+        assert_eq!(step.chosen, FillChoice::Zero);
+    }
+
+    #[test]
+    fn empty_row_sequence_has_no_hits() {
+        let config = LocalAlignmentConfig::default();
+        assert_eq!(sw_score_only(&[], &['A', 'C'], config).score, 0);
+        assert!(best_smith_waterman(&[], &['A', 'C'], config)
+            .iter()
+            .all(|result| result.score == 0));
+    }
 }
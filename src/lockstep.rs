@@ -0,0 +1,364 @@
+use std::{array, iter};
+
+use crate::{
+    global::{GlobalAlignmentConfig, ScoreOverflow},
+    letter::Letter,
+    local::LocalAlignmentConfig,
+    score::Score,
+};
+
+/// Number of pairs aligned together by the batch functions.
+pub const LANES: usize = 16;
+
+/// Letter filling row sequences shorter than the longest one of the batch.
+const ROW_PADDING: Letter = '\0';
+
+/// Letter filling column sequences shorter than the longest one of the batch.
+/// Different from [`ROW_PADDING`] so padding never matches padding.
+const COLUMN_PADDING: Letter = '\u{1}';
+
+/// A pair of sequences: the one associated with a row display, and the one
+/// associated with a column display.
+pub type SeqPair<'a> = (&'a [Letter], &'a [Letter]);
+
+/// Integer type of the cells of a lockstep matrix. Narrow types fit more
+/// lanes in a SIMD register, but saturate sooner.
+pub(crate) trait LaneScore: Copy + Ord {
+    /// Zero, the floor of local alignment cells.
+    const ZERO: Self;
+    /// Smallest value, reached only by saturation.
+    const MIN: Self;
+    /// Largest value, reached only by saturation.
+    const MAX: Self;
+
+    /// Converts a score, if it fits.
+    fn from_score(score: Score) -> Option<Self>;
+
+    /// Converts back into a score.
+    fn into_score(self) -> Score;
+
+    /// Adds two cells, saturating at the bounds instead of overflowing.
+    fn saturating_add(self, other: Self) -> Self;
+}
+
+macro_rules! impl_lane_score {
+    ($($ty:ty),*) => {
+        $(
+            impl LaneScore for $ty {
+                const ZERO: Self = 0;
+                const MIN: Self = <$ty>::MIN;
+                const MAX: Self = <$ty>::MAX;
+
+                fn from_score(score: Score) -> Option<Self> {
+                    Self::try_from(score).ok()
+                }
+
+                fn into_score(self) -> Score {
+                    Score::from(self)
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$ty>::saturating_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_lane_score!(i8, i16, i64);
+
+/// Computes `N` dynamic programming matrices at once, one pair per lane.
+///
+/// Every cell is computed for all lanes with the same instructions over
+/// `[S; N]` arrays, which the compiler turns into SIMD operations. Shorter
+/// sequences are padded up to the longest ones, and the padding is excluded
+/// from the scores.
+///
+/// Fails at the first cell of a lane that does not fit in `S`, or if a
+/// parameter does not fit: a cell at either bound of `S` is treated as
+/// saturated. Padding cells never fail, as no real cell depends on them.
+fn lockstep_scores<S: LaneScore, const N: usize>(
+    pairs: &[SeqPair; N],
+    scheme: GlobalAlignmentConfig,
+    local: bool,
+) -> Result<[Score; N], ScoreOverflow> {
+    let parameter =
+        |score| S::from_score(score).ok_or(ScoreOverflow { i: 0, j: 0 });
+    let match_penalty = parameter(scheme.match_penalty)?;
+    let mismatch_penalty = parameter(scheme.mismatch_penalty)?;
+    let gap_penalty = parameter(scheme.gap_penalty)?;
+    let row_lens: [usize; N] = array::from_fn(|lane| pairs[lane].0.len());
+    let column_lens: [usize; N] = array::from_fn(|lane| pairs[lane].1.len());
+    let height = row_lens.iter().copied().max().unwrap_or(0);
+    let width = column_lens.iter().copied().max().unwrap_or(0);
+    let columns: Vec<[Letter; N]> = (0 .. width)
+        .map(|j| {
+            array::from_fn(|lane| {
+                pairs[lane].1.get(j).copied().unwrap_or(COLUMN_PADDING)
+            })
+        })
+        .collect();
+    let border = |index: usize| {
+        if local {
+            Some(S::ZERO)
+        } else {
+            (index as Score)
+                .checked_mul(scheme.gap_penalty)
+                .and_then(S::from_score)
+                .filter(|border| *border != S::MIN && *border != S::MAX)
+        }
+    };
+
+    let mut previous: Vec<[S; N]> = (0 ..= width)
+        .map(|j| {
+            border(j).map(|cell| [cell; N]).ok_or(ScoreOverflow { i: 0, j })
+        })
+        .collect::<Result<_, _>>()?;
+    let mut scores: [Score; N] =
+        array::from_fn(|lane| previous[column_lens[lane]][lane].into_score());
+    let mut current = previous.clone();
+    for i in 1 ..= height {
+        let row_letters: [Letter; N] = array::from_fn(|lane| {
+            pairs[lane].0.get(i - 1).copied().unwrap_or(ROW_PADDING)
+        });
+        current[0] = [border(i).ok_or(ScoreOverflow { i, j: 0 })?; N];
+        let mut saturated = false;
+        for j in 1 ..= width {
+            let column_letters = &columns[j - 1];
+            let mut cell = [S::ZERO; N];
+            for lane in 0 .. N {
+                let pair = if row_letters[lane] == column_letters[lane] {
+                    match_penalty
+                } else {
+                    mismatch_penalty
+                };
+                let score = previous[j - 1][lane]
+                    .saturating_add(pair)
+                    .max(previous[j][lane].saturating_add(gap_penalty))
+                    .max(current[j - 1][lane].saturating_add(gap_penalty));
+                cell[lane] = if local { score.max(S::ZERO) } else { score };
+                let real = i <= row_lens[lane] && j <= column_lens[lane];
+                saturated |=
+                    real && (cell[lane] == S::MIN || cell[lane] == S::MAX);
+            }
+            current[j] = cell;
+            if local {
+                for lane in 0 .. N {
+                    if i <= row_lens[lane] && j <= column_lens[lane] {
+                        scores[lane] =
+                            scores[lane].max(cell[lane].into_score());
+                    }
+                }
+            }
+        }
+        if saturated {
+            let j = (1 ..= width)
+                .find(|&j| {
+                    (0 .. N).any(|lane| {
+                        i <= row_lens[lane]
+                            && j <= column_lens[lane]
+                            && (current[j][lane] == S::MIN
+                                || current[j][lane] == S::MAX)
+                    })
+                })
+                .unwrap_or(0);
+            return Err(ScoreOverflow { i, j });
+        }
+        if !local {
+            for lane in 0 .. N {
+                if row_lens[lane] == i {
+                    scores[lane] =
+                        current[column_lens[lane]][lane].into_score();
+                }
+            }
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Ok(scores)
+}
+
+/// Runs the lockstep computation with 8-bit cells, re-running it with 16-bit
+/// and then 64-bit cells whenever the narrower ones saturate. Fails only if
+/// 64-bit cells overflow too.
+fn adaptive_lockstep_scores<const N: usize>(
+    pairs: &[SeqPair; N],
+    scheme: GlobalAlignmentConfig,
+    local: bool,
+) -> Result<[Score; N], ScoreOverflow> {
+    lockstep_scores::<i8, N>(pairs, scheme, local)
+        .or_else(|_| lockstep_scores::<i16, N>(pairs, scheme, local))
+        .or_else(|_| lockstep_scores::<i64, N>(pairs, scheme, local))
+}
+
+/// Converts a local configuration into the equivalent scoring scheme.
+fn local_scheme(config: LocalAlignmentConfig) -> GlobalAlignmentConfig {
+    GlobalAlignmentConfig {
+        match_penalty: config.match_penalty,
+        mismatch_penalty: config.mismatch_penalty,
+        gap_penalty: config.gap_penalty,
+    }
+}
+
+/// Computes the Needleman-Wunsch scores of `N` independent pairs in lockstep,
+/// without alignments.
+///
+/// Cells are as narrow as the scores allow: 8-bit first, transparently
+/// re-running with wider cells on saturation. Fails if a score of some pair
+/// overflows even 64-bit cells.
+pub fn needleman_wunsch_scores_lockstep<const N: usize>(
+    pairs: &[SeqPair; N],
+    config: GlobalAlignmentConfig,
+) -> Result<[Score; N], ScoreOverflow> {
+    adaptive_lockstep_scores(pairs, config, false)
+}
+
+/// Computes the best Smith-Waterman scores of `N` independent pairs in
+/// lockstep, without alignments. Fails like
+/// [`needleman_wunsch_scores_lockstep`].
+pub fn smith_waterman_scores_lockstep<const N: usize>(
+    pairs: &[SeqPair; N],
+    config: LocalAlignmentConfig,
+) -> Result<[Score; N], ScoreOverflow> {
+    adaptive_lockstep_scores(pairs, local_scheme(config), true)
+}
+
+/// Computes the scores of any number of pairs, [`LANES`] pairs at a time.
+fn batch_scores(
+    pairs: &[SeqPair],
+    scheme: GlobalAlignmentConfig,
+    local: bool,
+) -> Result<Vec<Score>, ScoreOverflow> {
+    let mut scores = Vec::with_capacity(pairs.len());
+    for chunk in pairs.chunks(LANES) {
+        let lanes: [SeqPair; LANES] =
+            array::from_fn(|lane| chunk.get(lane).copied().unwrap_or_default());
+        let lane_scores = adaptive_lockstep_scores(&lanes, scheme, local)?;
+        scores.extend_from_slice(&lane_scores[.. chunk.len()]);
+    }
+    Ok(scores)
+}
+
+/// Sequence lengths the batch functions actually fill the matrices of, one
+/// entry per lane: each chunk of [`LANES`] pairs is padded up to its longest
+/// row and column sequences, and so are its unused lanes. Useful to bound the
+/// work of a batch before running it.
+pub fn batch_padded_lens<'a>(
+    pairs: &'a [SeqPair],
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    pairs.chunks(LANES).flat_map(|chunk| {
+        let height = chunk.iter().map(|(row, _)| row.len()).max().unwrap_or(0);
+        let width =
+            chunk.iter().map(|(_, column)| column.len()).max().unwrap_or(0);
+        iter::repeat_n((height, width), LANES)
+    })
+}
+
+/// Computes the Needleman-Wunsch scores of many pairs, aligning [`LANES`] of
+/// them simultaneously. Best suited to huge numbers of short pairs, such as
+/// barcodes or amplicons; pairs of similar lengths waste less padding.
+/// Fails if a score of some pair overflows.
+pub fn needleman_wunsch_scores_batch(
+    pairs: &[SeqPair],
+    config: GlobalAlignmentConfig,
+) -> Result<Vec<Score>, ScoreOverflow> {
+    batch_scores(pairs, config, false)
+}
+
+/// Computes the best Smith-Waterman scores of many pairs, aligning [`LANES`]
+/// of them simultaneously. Fails if a score of some pair overflows.
+pub fn smith_waterman_scores_batch(
+    pairs: &[SeqPair],
+    config: LocalAlignmentConfig,
+) -> Result<Vec<Score>, ScoreOverflow> {
+    batch_scores(pairs, local_scheme(config), true)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig, ScoreOverflow},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+        score::Score,
+    };
+
+    use super::{
+        lockstep_scores,
+        needleman_wunsch_scores_batch,
+        smith_waterman_scores_batch,
+        SeqPair,
+    };
+
+    #[test]
+    fn batch_scores_match_single_pair_alignments() {
+        let mut rng = Rng::new(35);
+        let seqs: Vec<Vec<char>> = (0 .. 42)
+            .map(|_| {
+                let length = rng.below(30);
+                random_dna(length, &mut rng)
+            })
+            .collect();
+        let pairs: Vec<SeqPair> = seqs
+            .chunks(2)
+            .map(|pair| (pair[0].as_slice(), pair[1].as_slice()))
+            .collect();
+
+        let global_config = GlobalAlignmentConfig::default();
+        let local_config = LocalAlignmentConfig::default();
+        let global =
+            needleman_wunsch_scores_batch(&pairs, global_config).unwrap();
+        let local = smith_waterman_scores_batch(&pairs, local_config).unwrap();
+        assert_eq!(global.len(), pairs.len());
+        for (index, (row_seq, column_seq)) in pairs.iter().enumerate() {
+            assert_eq!(
+                global[index],
+                needleman_wunsch(row_seq, column_seq, global_config).score
+            );
+            let expected =
+                best_smith_waterman(row_seq, column_seq, local_config)
+                    .first()
+                    .map_or(0, |result| result.score);
+            assert_eq!(local[index], expected);
+        }
+    }
+
+    #[test]
+    fn saturated_narrow_cells_are_rerun_wider() {
+        let mut rng = Rng::new(36);
+        let long = random_dna(300, &mut rng);
+        let short = random_dna(20, &mut rng);
+        let pairs: [SeqPair; 2] = [(&long, &long), (&short, &long[.. 20])];
+        let global_config = GlobalAlignmentConfig::default();
+        let local_config = LocalAlignmentConfig::default();
+
+        assert!(lockstep_scores::<i8, 2>(&pairs, global_config, false).is_err());
+        assert_eq!(
+            lockstep_scores::<i8, 1>(&[pairs[1]], global_config, false),
+            Ok([needleman_wunsch(&short, &long[.. 20], global_config).score])
+        );
+        assert_eq!(
+            needleman_wunsch_scores_batch(&pairs, global_config).unwrap()[0],
+            300
+        );
+        assert_eq!(
+            smith_waterman_scores_batch(&pairs, local_config).unwrap()[0],
+            300
+        );
+
+        // the border saturates at the first cell that does not fit
+        let padded: [SeqPair; 2] =
+            [(&long[.. 100], &long[.. 100]), (&short, &short)];
+        let narrow = lockstep_scores::<i8, 2>(&padded, global_config, false);
+        assert_eq!(narrow, Err(ScoreOverflow { i: 0, j: 64 }));
+
+        let extreme = GlobalAlignmentConfig {
+            match_penalty: Score::MAX / 2,
+            mismatch_penalty: 0,
+            gap_penalty: 0,
+        };
+        assert_eq!(
+            needleman_wunsch_scores_batch(&[(&short, &short)], extreme),
+            Err(ScoreOverflow { i: 3, j: 3 })
+        );
+    }
+}
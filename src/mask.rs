@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+use crate::{
+    global::{
+        traceback_nw_best_alignment,
+        GlobalAlignmentConfig,
+        GlobalAlignmentResult,
+    },
+    letter::Letter,
+    local::{
+        traceback_best_sw_alignment,
+        LocalAlignmentConfig,
+        LocalAlignmentResult,
+    },
+    matrix::AlignmentMatrix,
+    score::Score,
+};
+
+/// Intervals of either sequence whose letters must not take part in matches,
+/// e.g. known adapters, low-complexity regions or redacted spans.
+///
+/// A masked letter is always scored as a mismatch when paired, even against
+/// an identical letter, so alignments avoid matching inside masked regions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionMask {
+    /// Masked ranges of the row sequence, with 0-based indices.
+    pub row: Vec<Range<usize>>,
+    /// Masked ranges of the column sequence, with 0-based indices.
+    pub column: Vec<Range<usize>>,
+}
+
+impl RegionMask {
+    /// A mask that excludes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a masked range to the row sequence.
+    pub fn mask_row(mut self, range: Range<usize>) -> Self {
+        self.row.push(range);
+        self
+    }
+
+    /// Adds a masked range to the column sequence.
+    pub fn mask_column(mut self, range: Range<usize>) -> Self {
+        self.column.push(range);
+        self
+    }
+
+    /// Tests whether position `i` of the row sequence is masked.
+    pub fn is_row_masked(&self, i: usize) -> bool {
+        self.row.iter().any(|range| range.contains(&i))
+    }
+
+    /// Tests whether position `j` of the column sequence is masked.
+    pub fn is_column_masked(&self, j: usize) -> bool {
+        self.column.iter().any(|range| range.contains(&j))
+    }
+}
+
+/// Expands masked ranges into one flag per position of a sequence.
+fn masked_positions(ranges: &[Range<usize>], len: usize) -> Vec<bool> {
+    let mut masked = vec![false; len];
+    for range in ranges {
+        let end = range.end.min(len);
+        for flag in masked[range.start.min(end) .. end].iter_mut() {
+            *flag = true;
+        }
+    }
+    masked
+}
+
+/// Fills a score matrix where pairs involving masked letters are scored as
+/// mismatches. Local matrices are floored at zero.
+fn fill_masked_matrix(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    scheme: GlobalAlignmentConfig,
+    mask: &RegionMask,
+    local: bool,
+) -> AlignmentMatrix {
+    let row_masked = masked_positions(&mask.row, row_seq.len());
+    let column_masked = masked_positions(&mask.column, column_seq.len());
+    let mut matrix =
+        AlignmentMatrix::zeroed(row_seq.len() + 1, column_seq.len() + 1);
+    if !local {
+        for j in 1 ..= column_seq.len() {
+            matrix[[0, j]] = (j as Score) * scheme.gap_penalty;
+        }
+        for i in 1 ..= row_seq.len() {
+            matrix[[i, 0]] = (i as Score) * scheme.gap_penalty;
+        }
+    }
+    for i in 1 ..= row_seq.len() {
+        for j in 1 ..= column_seq.len() {
+            let matches = row_seq[i - 1] == column_seq[j - 1]
+                && !row_masked[i - 1]
+                && !column_masked[j - 1];
+            let no_gap_penalty = if matches {
+                scheme.match_penalty
+            } else {
+                scheme.mismatch_penalty
+            };
+            let no_gap_score = matrix[[i - 1, j - 1]] + no_gap_penalty;
+            let best_gap_score =
+                matrix[[i - 1, j]].max(matrix[[i, j - 1]]) + scheme.gap_penalty;
+            let score = no_gap_score.max(best_gap_score);
+            matrix[[i, j]] = if local { score.max(0) } else { score };
+        }
+    }
+    matrix
+}
+
+/// Executes Needleman-Wunsch with masked regions excluded from matches.
+pub fn masked_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    mask: &RegionMask,
+) -> GlobalAlignmentResult {
+    let matrix = fill_masked_matrix(row_seq, column_seq, config, mask, false);
+    traceback_nw_best_alignment(row_seq, column_seq, config, &matrix)
+}
+
+/// Computes the best Smith-Waterman alignments with masked regions excluded
+/// from matches.
+pub fn masked_best_smith_waterman(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: LocalAlignmentConfig,
+    mask: &RegionMask,
+) -> Vec<LocalAlignmentResult> {
+    let scheme = GlobalAlignmentConfig {
+        match_penalty: config.match_penalty,
+        mismatch_penalty: config.mismatch_penalty,
+        gap_penalty: config.gap_penalty,
+    };
+    let matrix = fill_masked_matrix(row_seq, column_seq, scheme, mask, true);
+    traceback_best_sw_alignment(row_seq, column_seq, config, &matrix)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::LocalAlignmentConfig,
+    };
+
+    use super::{
+        masked_best_smith_waterman,
+        masked_needleman_wunsch,
+        RegionMask,
+    };
+
+    #[test]
+    fn masked_adapter_is_not_matched() {
+        let row_seq: Vec<char> = "ADAPTERGATTACA".chars().collect();
+        let column_seq: Vec<char> = "ADAPTERCCCCCCC".chars().collect();
+
+        let results = masked_best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+            &RegionMask::new(),
+        );
+        assert_eq!(results[0].score, 7);
+        let mask = RegionMask::new().mask_row(0 .. 7);
+        let results = masked_best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+            &mask,
+        );
+        assert!(results.iter().all(|result| result.score < 7));
+
+        let config = GlobalAlignmentConfig::default();
+        assert_eq!(
+            masked_needleman_wunsch(
+                &row_seq,
+                &column_seq,
+                config,
+                &RegionMask::new()
+            ),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+        let masked = masked_needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            config,
+            &RegionMask::new().mask_column(0 .. 100),
+        );
+        assert_eq!(masked.score, -14);
+    }
+}
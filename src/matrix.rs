@@ -1,13 +1,58 @@
 use std::{
+    error::Error,
     fmt,
+    io,
     ops::{Index, IndexMut},
 };
 
 use crate::{
     letter::Letter,
+    report::WriteReport,
     score::{score_digit_count, Score},
 };
 
+/// Error raised when data given to build a matrix is not rectangular.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatrixShapeError {
+    /// The width is zero, or there are no rows at all.
+    ZeroWidth,
+    /// The buffer length is not a multiple of the width.
+    UnevenBuffer {
+        /// Length of the buffer.
+        len: usize,
+        /// Requested width.
+        width: usize,
+    },
+    /// A row is not as long as the first one.
+    RaggedRow {
+        /// Index of the offending row.
+        row: usize,
+        /// Length of the first row.
+        expected: usize,
+        /// Length of the offending row.
+        found: usize,
+    },
+}
+
+impl fmt::Display for MatrixShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroWidth => write!(f, "matrix would have no columns"),
+            Self::UnevenBuffer { len, width } => write!(
+                f,
+                "buffer of {len} scores cannot be split into rows of {width}"
+            ),
+            Self::RaggedRow { row, expected, found } => write!(
+                f,
+                "row {row} has {found} scores, but the first one has \
+                 {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for MatrixShapeError {}
+
 /// 2D Matrix of scores
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AlignmentMatrix {
@@ -22,6 +67,97 @@ impl AlignmentMatrix {
         Self { buf: vec![0; height * width], width }
     }
 
+    /// Builds a matrix of the given width over a buffer of scores in
+    /// row-major order, whose length must be a multiple of the width.
+    pub fn from_vec(
+        buf: Vec<Score>,
+        width: usize,
+    ) -> Result<Self, MatrixShapeError> {
+        if width == 0 {
+            Err(MatrixShapeError::ZeroWidth)
+        } else if !buf.len().is_multiple_of(width) {
+            Err(MatrixShapeError::UnevenBuffer { len: buf.len(), width })
+        } else {
+            Ok(Self { buf, width })
+        }
+    }
+
+    /// Builds a matrix from its rows, which must all have the same, non-zero
+    /// length.
+    pub fn from_rows(rows: &[&[Score]]) -> Result<Self, MatrixShapeError> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if width == 0 {
+            return Err(MatrixShapeError::ZeroWidth);
+        }
+        let mut buf = Vec::with_capacity(rows.len() * width);
+        for (row_index, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(MatrixShapeError::RaggedRow {
+                    row: row_index,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+            buf.extend_from_slice(row);
+        }
+        Ok(Self { buf, width })
+    }
+
+    /// Grows the matrix to the given dimensions, keeping every existing score
+    /// at the same two-dimensional index and setting new cells to zero.
+    /// Dimensions smaller than the current ones are kept as they are.
+    pub fn grow(&mut self, height: usize, width: usize) {
+        let old_height = self.height();
+        let old_width = self.width;
+        let height = height.max(old_height);
+        let width = width.max(old_width);
+        if width > old_width {
+            let mut buf = vec![0; height * width];
+            for (i, row) in self.buf.chunks(old_width).enumerate() {
+                buf[i * width .. i * width + old_width].copy_from_slice(row);
+            }
+            self.buf = buf;
+            self.width = width;
+        } else {
+            self.buf.resize(height * width, 0);
+        }
+    }
+
+    /// Sets every score to zero, keeping the dimensions.
+    pub fn reset(&mut self) {
+        self.buf.fill(0);
+    }
+
+    /// Changes the dimensions of the matrix and sets every score to zero,
+    /// reusing the existing buffer whenever it is large enough, so that one
+    /// matrix can serve alignments of varying sizes without reallocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero, like [`Self::from_vec`] rejects it.
+    pub fn resize(&mut self, height: usize, width: usize) {
+        assert!(width > 0, "{}", MatrixShapeError::ZeroWidth);
+        self.buf.clear();
+        self.buf.resize(height * width, 0);
+        self.width = width;
+    }
+
+    /// Number of cells the matrix holds without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Builds a matrix over an all-zero buffer whose length is a multiple of
+    /// `width`.
+    pub(crate) fn from_zeroed_buf(buf: Vec<Score>, width: usize) -> Self {
+        Self { buf, width }
+    }
+
+    /// Takes back the buffer of the matrix.
+    pub(crate) fn into_buf(self) -> Vec<Score> {
+        self.buf
+    }
+
     /// Number of lines of the matrix.
     pub fn height(&self) -> usize {
         self.buf.len() / self.width()
@@ -80,6 +216,27 @@ impl AlignmentMatrix {
         }
     }
 
+    /// Iterates over the rows of the matrix, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Score]> + '_ {
+        self.buf.chunks(self.width.max(1))
+    }
+
+    /// Iterates over the scores in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = Score> + '_ {
+        self.buf.iter().copied()
+    }
+
+    /// Iterates over the two-dimensional index and score of every cell, in
+    /// row-major order.
+    pub fn enumerate_cells(
+        &self,
+    ) -> impl Iterator<Item = (usize, usize, Score)> + '_ {
+        self.buf.iter().enumerate().map(|(k, &score)| {
+            let (i, j) = self.unpack_index(k);
+            (i, j, score)
+        })
+    }
+
     /// Returns the maximum score, if matrix is not empty.
     pub fn max(&self) -> Option<Score> {
         self.buf.iter().copied().max()
@@ -127,6 +284,19 @@ impl AlignmentMatrix {
             Vec::new()
         }
     }
+
+    /// Writes the scores as delimited text, one line per row, as formatted
+    /// by [`CsvPrint`]. A `delimiter` of `'\t'` gives TSV.
+    pub fn write_csv<W>(
+        &self,
+        writer: &mut W,
+        delimiter: char,
+    ) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        CsvPrint(self, delimiter).write_report(writer)
+    }
 }
 
 impl Index<(usize, usize)> for AlignmentMatrix {
@@ -280,6 +450,8 @@ impl fmt::Display for PrettyPrint<'_> {
     }
 }
 
+impl WriteReport for PrettyPrint<'_> {}
+
 /// Struct that prints an alignment matrix in textual format,
 /// like `PrettyPrint<'_>`, but displays letters identifying sequence elements.
 /**
@@ -418,3 +590,90 @@ impl fmt::Display for LabeledPrettyPrint<'_> {
         Ok(())
     }
 }
+
+impl WriteReport for LabeledPrettyPrint<'_> {}
+
+/// Struct that prints the scores of an alignment matrix as delimited text,
+/// one line per row, separated by the given delimiter: `','` for CSV or
+/// `'\t'` for TSV.
+/**
+ * Example:
+```text
+0,-2,-4
+-2,1,-1
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct CsvPrint<'a>(pub &'a AlignmentMatrix, pub char);
+
+impl fmt::Display for CsvPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(matrix, delimiter) = self;
+        for row in matrix.rows() {
+            for (j, score) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, "{delimiter}")?;
+                }
+                write!(f, "{score}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for CsvPrint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::{AlignmentMatrix, CsvPrint, MatrixShapeError};
+
+    #[test]
+    fn cells_are_visited_in_row_major_order() {
+        let mut matrix = AlignmentMatrix::zeroed(2, 3);
+        for (i, j) in [(0, 1), (1, 0), (1, 2)] {
+            assert!(matrix.set(i, j, (i * 10 + j) as i64));
+        }
+        let rows: Vec<&[i64]> = matrix.rows().collect();
+        assert_eq!(rows, [&[0, 1, 0][..], &[10, 0, 12][..]]);
+        assert_eq!(matrix.iter().sum::<i64>(), 23);
+        let cells: Vec<_> = matrix.enumerate_cells().collect();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[5], (1, 2, 12));
+        assert_eq!(AlignmentMatrix::zeroed(0, 0).rows().count(), 0);
+    }
+
+    #[test]
+    fn constructors_check_dimensions() {
+        let matrix =
+            AlignmentMatrix::from_rows(&[&[0, 1, 0], &[10, 0, 12]]).unwrap();
+        assert_eq!((matrix.height(), matrix.width()), (2, 3));
+        assert_eq!(matrix[[1, 2]], 12);
+        assert_eq!(
+            AlignmentMatrix::from_vec(vec![0, 1, 0, 10, 0, 12], 3),
+            Ok(matrix)
+        );
+        assert_eq!(
+            AlignmentMatrix::from_vec(vec![1, 2, 3], 2),
+            Err(MatrixShapeError::UnevenBuffer { len: 3, width: 2 })
+        );
+        assert_eq!(
+            AlignmentMatrix::from_rows(&[&[1, 2], &[3]]),
+            Err(MatrixShapeError::RaggedRow { row: 1, expected: 2, found: 1 })
+        );
+        assert_eq!(
+            AlignmentMatrix::from_rows(&[]),
+            Err(MatrixShapeError::ZeroWidth)
+        );
+    }
+
+    #[test]
+    fn scores_are_written_as_delimited_text() {
+        let matrix =
+            AlignmentMatrix::from_rows(&[&[0, -2, -4], &[-2, 1, -1]]).unwrap();
+        assert_eq!(CsvPrint(&matrix, ',').to_string(), "0,-2,-4\n-2,1,-1\n");
+        let mut tsv = Vec::new();
+        matrix.write_csv(&mut tsv, '\t').unwrap();
+        assert_eq!(tsv, b"0\t-2\t-4\n-2\t1\t-1\n");
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    banded::{fill_banded_matrix, traceback_banded, Band},
+    global::{
+        compute_nw_matrix,
+        traceback_nw_best_alignment,
+        GlobalAlignmentConfig,
+        GlobalAlignmentResult,
+    },
+    letter::Letter,
+    local::{
+        compute_sw_matrix,
+        traceback_best_sw_alignment,
+        LocalAlignmentConfig,
+        LocalAlignmentResult,
+    },
+    matrix::AlignmentMatrix,
+    score::Score,
+};
+
+/// Resources used by one alignment, for tuning band widths, thresholds and
+/// batch sizes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AlignmentMetrics {
+    /// Number of matrix cells computed.
+    pub cells: u64,
+    /// Largest number of bytes taken by score matrices at once.
+    pub peak_matrix_bytes: usize,
+    /// Wall-clock time taken to fill the matrix.
+    pub fill_time: Duration,
+    /// Wall-clock time taken by the traceback.
+    pub traceback_time: Duration,
+    /// Number of alignments traced back.
+    pub tracebacks: usize,
+}
+
+impl AlignmentMetrics {
+    /// Total wall-clock time, fill and traceback.
+    pub fn total_time(&self) -> Duration {
+        self.fill_time + self.traceback_time
+    }
+}
+
+/// Bytes of the scores of a full matrix.
+fn matrix_bytes(matrix: &AlignmentMatrix) -> usize {
+    matrix.height() * matrix.width() * mem::size_of::<Score>()
+}
+
+/// Executes Needleman-Wunsch like
+/// [`needleman_wunsch`](crate::global::needleman_wunsch), measuring it.
+pub fn needleman_wunsch_with_metrics(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+) -> (GlobalAlignmentResult, AlignmentMetrics) {
+    let start = Instant::now();
+    let matrix = compute_nw_matrix(row_seq, column_seq, config);
+    let fill_time = start.elapsed();
+    let start = Instant::now();
+    let result =
+        traceback_nw_best_alignment(row_seq, column_seq, config, &matrix);
+    let metrics = AlignmentMetrics {
+        cells: (matrix.height() * matrix.width()) as u64,
+        peak_matrix_bytes: matrix_bytes(&matrix),
+        fill_time,
+        traceback_time: start.elapsed(),
+        tracebacks: 1,
+    };
+    (result, metrics)
+}
+
+/// Computes the best Smith-Waterman alignments like
+/// [`best_smith_waterman`](crate::local::best_smith_waterman), measuring
+/// them. Every alignment with the best score counts as one traceback.
+pub fn best_smith_waterman_with_metrics(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: LocalAlignmentConfig,
+) -> (Vec<LocalAlignmentResult>, AlignmentMetrics) {
+    let start = Instant::now();
+    let matrix = compute_sw_matrix(row_seq, column_seq, config);
+    let fill_time = start.elapsed();
+    let start = Instant::now();
+    let results =
+        traceback_best_sw_alignment(row_seq, column_seq, config, &matrix);
+    let metrics = AlignmentMetrics {
+        cells: (matrix.height() * matrix.width()) as u64,
+        peak_matrix_bytes: matrix_bytes(&matrix),
+        fill_time,
+        traceback_time: start.elapsed(),
+        tracebacks: results.len(),
+    };
+    (results, metrics)
+}
+
+/// Executes the banded Needleman-Wunsch like
+/// [`banded_needleman_wunsch`](crate::banded::banded_needleman_wunsch),
+/// measuring it. Only the cells inside the band count.
+pub fn banded_needleman_wunsch_with_metrics(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+    band: Band,
+) -> (GlobalAlignmentResult, AlignmentMetrics) {
+    let start = Instant::now();
+    let (matrix, cells) = fill_banded_matrix(row_seq, column_seq, config, band);
+    let fill_time = start.elapsed();
+    let start = Instant::now();
+    let result = traceback_banded(row_seq, column_seq, config, &matrix);
+    let metrics = AlignmentMetrics {
+        cells,
+        peak_matrix_bytes: matrix.memory_bytes(),
+        fill_time,
+        traceback_time: start.elapsed(),
+        tracebacks: 1,
+    };
+    (result, metrics)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        banded::Band,
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{
+        banded_needleman_wunsch_with_metrics,
+        best_smith_waterman_with_metrics,
+        needleman_wunsch_with_metrics,
+    };
+
+    #[test]
+    fn metrics_account_for_the_computed_cells() {
+        let mut rng = Rng::new(49);
+        let row_seq = random_dna(60, &mut rng);
+        let column_seq = random_dna(50, &mut rng);
+        let config = GlobalAlignmentConfig::default();
+
+        let (result, full) =
+            needleman_wunsch_with_metrics(&row_seq, &column_seq, config);
+        assert_eq!(result, needleman_wunsch(&row_seq, &column_seq, config));
+        assert_eq!(full.cells, 61 * 51);
+        assert_eq!(full.peak_matrix_bytes, 61 * 51 * 8);
+        assert_eq!(full.tracebacks, 1);
+
+        let band = Band { lo: -15, hi: 5 };
+        let (_, banded) = banded_needleman_wunsch_with_metrics(
+            &row_seq,
+            &column_seq,
+            config,
+            band,
+        );
+        assert!(banded.cells < full.cells);
+        assert!(banded.peak_matrix_bytes < full.peak_matrix_bytes);
+
+        let local = LocalAlignmentConfig::default();
+        let (results, metrics) =
+            best_smith_waterman_with_metrics(&row_seq, &column_seq, local);
+        assert_eq!(results, best_smith_waterman(&row_seq, &column_seq, local));
+        assert_eq!(metrics.tracebacks, results.len());
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeSet;
+
+use crate::letter::Letter;
+
+/// Parameters of minimizer sketching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MinimizerConfig {
+    /// Length of the k-mers.
+    pub k: usize,
+    /// Number of consecutive k-mers in each window.
+    pub window: usize,
+}
+
+impl Default for MinimizerConfig {
+    fn default() -> Self {
+        Self { k: 11, window: 10 }
+    }
+}
+
+/// A k-mer selected as the smallest hash of some window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Minimizer {
+    /// Hash of the k-mer.
+    pub hash: u64,
+    /// Position of the k-mer in the sequence.
+    pub position: usize,
+}
+
+/// Hashes a k-mer of letters (FNV-1a followed by a final avalanche mix).
+pub fn hash_kmer(kmer: &[Letter]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &letter in kmer {
+        hash ^= u64::from(u32::from(letter));
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash
+}
+
+/// Computes the minimizers of a sequence, in position order.
+///
+/// Consecutive windows sharing the same minimizer only report it once. Ties
+/// inside a window are broken by the leftmost position.
+pub fn minimizers(seq: &[Letter], config: MinimizerConfig) -> Vec<Minimizer> {
+    if config.k == 0 || config.window == 0 || seq.len() < config.k {
+        return Vec::new();
+    }
+    let hashes: Vec<u64> = seq.windows(config.k).map(hash_kmer).collect();
+    let window = config.window.min(hashes.len());
+    let mut result: Vec<Minimizer> = Vec::new();
+    for start in 0 ..= hashes.len() - window {
+        let Some(minimizer) = hashes[start .. start + window]
+            .iter()
+            .enumerate()
+            .map(|(offset, &hash)| Minimizer { hash, position: start + offset })
+            .min_by_key(|minimizer| (minimizer.hash, minimizer.position))
+        else {
+            continue;
+        };
+        if result.last() != Some(&minimizer) {
+            result.push(minimizer);
+        }
+    }
+    result
+}
+
+/// Set of minimizer hashes summarizing a sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sketch {
+    hashes: BTreeSet<u64>,
+}
+
+impl Sketch {
+    /// Builds the sketch of a sequence.
+    pub fn new(seq: &[Letter], config: MinimizerConfig) -> Self {
+        Self {
+            hashes: minimizers(seq, config)
+                .into_iter()
+                .map(|minimizer| minimizer.hash)
+                .collect(),
+        }
+    }
+
+    /// Number of distinct minimizers in this sketch.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Whether this sketch has no minimizer at all.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Number of minimizers shared with another sketch.
+    pub fn shared(&self, other: &Self) -> usize {
+        self.hashes.intersection(&other.hashes).count()
+    }
+}
+
+/// Prefilter that skips full alignment of unrelated sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Prefilter {
+    /// Sketching parameters.
+    pub minimizer: MinimizerConfig,
+    /// Minimum number of shared minimizers for a pair to be aligned.
+    pub min_shared: usize,
+}
+
+impl Prefilter {
+    /// Whether a target with the given sketch is worth aligning against a
+    /// query with the given sketch.
+    pub fn accepts(&self, query: &Sketch, target: &Sketch) -> bool {
+        query.shared(target) >= self.min_shared
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{minimizers, MinimizerConfig, Sketch};
+
+    #[test]
+    fn shared_minimizers_of_related_sequences() {
+        let config = MinimizerConfig { k: 3, window: 2 };
+        let seq_a: Vec<char> = "ACGTTGCAACGTAGGT".chars().collect();
+        let seq_b: Vec<char> = "TTACGTTGCAACGTAG".chars().collect();
+        let seq_c: Vec<char> = "CCCCCCCCCCCCCCCC".chars().collect();
+
+        let sketch_a = Sketch::new(&seq_a, config);
+        let sketch_b = Sketch::new(&seq_b, config);
+        let sketch_c = Sketch::new(&seq_c, config);
+
+        assert!(sketch_a.shared(&sketch_b) >= sketch_a.len() / 2);
+        assert_eq!(sketch_a.shared(&sketch_c), 0);
+        assert_eq!(minimizers(&seq_c, config).len(), 13);
+    }
+}
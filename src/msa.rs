@@ -0,0 +1,531 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    align::{align, AlignmentMode},
+    global::{GlobalAlignmentConfig, GlobalAlignmentResult},
+    letter::{Letter, GAP},
+};
+
+/// Progressive multiple alignment guided by a tree of pairwise distances.
+pub mod progressive;
+
+/// Error raised when building an inconsistent multiple sequence alignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MsaError {
+    /// The number of identifiers and the number of rows differ.
+    IdCountMismatch {
+        /// Number of identifiers given.
+        ids: usize,
+        /// Number of rows given.
+        rows: usize,
+    },
+    /// A row does not have the same width as the others.
+    RaggedRow {
+        /// Identifier of the offending row.
+        id: String,
+        /// Width of the alignment.
+        expected: usize,
+        /// Width of the offending row.
+        found: usize,
+    },
+}
+
+impl fmt::Display for MsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IdCountMismatch { ids, rows } => {
+                write!(f, "got {ids} identifiers for {rows} rows")
+            },
+            Self::RaggedRow { id, expected, found } => write!(
+                f,
+                "row {id} has width {found}, but alignment has width \
+                 {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for MsaError {}
+
+/// A multiple sequence alignment: gapped rows of equal width, each with an
+/// identifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Msa {
+    ids: Vec<String>,
+    rows: Vec<Vec<Letter>>,
+    width: usize,
+}
+
+impl Msa {
+    /// Creates an alignment with no rows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an alignment from identifiers and gapped rows, checking that
+    /// all rows have the same width.
+    pub fn from_rows(
+        ids: Vec<String>,
+        rows: Vec<Vec<Letter>>,
+    ) -> Result<Self, MsaError> {
+        if ids.len() != rows.len() {
+            return Err(MsaError::IdCountMismatch {
+                ids: ids.len(),
+                rows: rows.len(),
+            });
+        }
+        let mut msa = Self::new();
+        for (id, row) in ids.into_iter().zip(rows) {
+            msa.push_row(id, row)?;
+        }
+        Ok(msa)
+    }
+
+    /// Creates a two-row alignment from a global alignment result.
+    pub fn from_global(
+        row_seq_name: &str,
+        column_seq_name: &str,
+        result: &GlobalAlignmentResult,
+    ) -> Result<Self, MsaError> {
+        Self::from_rows(
+            vec![row_seq_name.to_owned(), column_seq_name.to_owned()],
+            vec![
+                result.aligned_row_seq.clone(),
+                result.aligned_column_seq.clone(),
+            ],
+        )
+    }
+
+    /// Aligns every read to the reference independently, then stacks them in
+    /// the coordinates of the reference, the first row.
+    ///
+    /// Each read is placed within the reference (fitting mode). Letters a read
+    /// inserts between two reference letters get their own columns, where the
+    /// reference and the other reads have gaps. Reads are gaps outside of the
+    /// reference range they cover.
+    pub fn reference_guided(
+        reference_id: &str,
+        reference: &[Letter],
+        reads: &[(&str, &[Letter])],
+        config: GlobalAlignmentConfig,
+    ) -> Self {
+        // For every read: the reference range it covers, the letter facing each
+        // reference letter of the range, and the letters inserted before each
+        // reference position of the range (plus one slot after its end).
+        let mut placements = Vec::with_capacity(reads.len());
+        let mut insertion_widths = vec![0; reference.len() + 1];
+        for (_, read) in reads {
+            let alignment =
+                align(reference, read, AlignmentMode::Fitting, config);
+            let start = alignment.aligned_row_seq.start;
+            let span = alignment.aligned_row_seq.end - start;
+            let mut facing = Vec::with_capacity(span);
+            let mut insertions = vec![Vec::new(); span + 1];
+            for (&reference_letter, &read_letter) in alignment
+                .aligned_row_seq
+                .data
+                .iter()
+                .zip(&alignment.aligned_column_seq.data)
+            {
+                if reference_letter == GAP {
+                    insertions[facing.len()].push(read_letter);
+                } else {
+                    facing.push(read_letter);
+                }
+            }
+            for (offset, inserted) in insertions.iter().enumerate() {
+                let width = &mut insertion_widths[start + offset];
+                *width = (*width).max(inserted.len());
+            }
+            placements.push((start, facing, insertions));
+        }
+
+        let width = reference.len() + insertion_widths.iter().sum::<usize>();
+        let mut reference_row = Vec::with_capacity(width);
+        for (position, &insertion_width) in insertion_widths.iter().enumerate()
+        {
+            reference_row.extend((0 .. insertion_width).map(|_| GAP));
+            reference_row.extend(reference.get(position));
+        }
+        let mut msa = Self::new();
+        msa.ids.push(reference_id.to_owned());
+        msa.rows.push(reference_row);
+        msa.width = width;
+
+        for ((id, _), (start, facing, insertions)) in
+            reads.iter().zip(placements)
+        {
+            let mut row = Vec::with_capacity(width);
+            for (position, &insertion_width) in
+                insertion_widths.iter().enumerate()
+            {
+                let inserted = position
+                    .checked_sub(start)
+                    .and_then(|offset| insertions.get(offset))
+                    .map_or(&[][..], Vec::as_slice);
+                row.extend_from_slice(inserted);
+                row.extend((inserted.len() .. insertion_width).map(|_| GAP));
+                if position < reference.len() {
+                    let letter = position
+                        .checked_sub(start)
+                        .and_then(|offset| facing.get(offset))
+                        .copied()
+                        .unwrap_or(GAP);
+                    row.push(letter);
+                }
+            }
+            msa.ids.push((*id).to_owned());
+            msa.rows.push(row);
+        }
+        msa
+    }
+
+    /// Appends a row. The first row defines the width of the alignment.
+    pub fn push_row(
+        &mut self,
+        id: String,
+        row: Vec<Letter>,
+    ) -> Result<(), MsaError> {
+        if self.rows.is_empty() {
+            self.width = row.len();
+        } else if row.len() != self.width {
+            return Err(MsaError::RaggedRow {
+                id,
+                expected: self.width,
+                found: row.len(),
+            });
+        }
+        self.ids.push(id);
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Number of rows (sequences).
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Identifiers of all rows, in order.
+    pub fn ids(&self) -> &[String] {
+        &self.ids
+    }
+
+    /// Identifier of a row, if in bounds.
+    pub fn id(&self, i: usize) -> Option<&str> {
+        self.ids.get(i).map(String::as_str)
+    }
+
+    /// Gapped letters of a row, if in bounds.
+    pub fn row(&self, i: usize) -> Option<&[Letter]> {
+        self.rows.get(i).map(Vec::as_slice)
+    }
+
+    /// Iterates over `(identifier, gapped row)` pairs.
+    pub fn rows(&self) -> impl Iterator<Item = (&str, &[Letter])> + '_ {
+        self.ids
+            .iter()
+            .map(String::as_str)
+            .zip(self.rows.iter().map(Vec::as_slice))
+    }
+
+    /// Letter at a row and column, if in bounds.
+    pub fn get(&self, i: usize, j: usize) -> Option<Letter> {
+        self.rows.get(i)?.get(j).copied()
+    }
+
+    /// View of a column, if in bounds.
+    pub fn column(&self, j: usize) -> Option<Column<'_>> {
+        if j < self.width {
+            Some(Column { msa: self, j })
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over all columns, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = Column<'_>> + '_ {
+        (0 .. self.width).map(move |j| Column { msa: self, j })
+    }
+
+    /// Removes a row, returning its identifier and letters, if in bounds.
+    ///
+    /// Removing the last row resets the width to zero.
+    pub fn remove_row(&mut self, i: usize) -> Option<(String, Vec<Letter>)> {
+        if i >= self.rows.len() {
+            return None;
+        }
+        let removed = (self.ids.remove(i), self.rows.remove(i));
+        if self.rows.is_empty() {
+            self.width = 0;
+        }
+        Some(removed)
+    }
+
+    /// Removes a column, returning its letters, if in bounds.
+    pub fn remove_column(&mut self, j: usize) -> Option<Vec<Letter>> {
+        if j >= self.width {
+            return None;
+        }
+        self.width -= 1;
+        Some(self.rows.iter_mut().map(|row| row.remove(j)).collect())
+    }
+
+    /// Keeps only the columns for which `predicate` returns `true`, given the
+    /// column index and view.
+    pub fn retain_columns<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(usize, Column<'_>) -> bool,
+    {
+        let keep: Vec<bool> =
+            self.columns().enumerate().map(|(j, c)| predicate(j, c)).collect();
+        for row in &mut self.rows {
+            let mut j = 0;
+            row.retain(|_| {
+                let kept = keep[j];
+                j += 1;
+                kept
+            });
+        }
+        self.width = keep.iter().filter(|kept| **kept).count();
+    }
+
+    /// Removes columns whose gap fraction exceeds `max_gap_fraction`
+    /// (trimAl-style gap threshold), without modifying this alignment.
+    pub fn trim_gappy_columns(&self, max_gap_fraction: f64) -> TrimmedMsa {
+        let retained_columns: Vec<usize> = self
+            .columns()
+            .filter(|column| column.gap_fraction() <= max_gap_fraction)
+            .map(|column| column.index())
+            .collect();
+        let mut msa = self.clone();
+        msa.retain_columns(|_, column| {
+            column.gap_fraction() <= max_gap_fraction
+        });
+        TrimmedMsa { msa, retained_columns }
+    }
+
+    /// Extracts the pairwise alignment induced by two rows, dropping columns
+    /// where both rows are gaps. Score and identity are recomputed with the
+    /// given configuration. Returns `None` if a row index is out of bounds.
+    pub fn pairwise(
+        &self,
+        first: usize,
+        second: usize,
+        config: GlobalAlignmentConfig,
+    ) -> Option<GlobalAlignmentResult> {
+        let first_row = self.rows.get(first)?;
+        let second_row = self.rows.get(second)?;
+        let mut result = GlobalAlignmentResult {
+            aligned_row_seq: Vec::with_capacity(self.width),
+            aligned_column_seq: Vec::with_capacity(self.width),
+            score: 0,
+            identity_numer: 0,
+            identity_denom: 0,
+        };
+        for (&first_letter, &second_letter) in first_row.iter().zip(second_row)
+        {
+            if first_letter == GAP && second_letter == GAP {
+                continue;
+            }
+            result.aligned_row_seq.push(first_letter);
+            result.aligned_column_seq.push(second_letter);
+            if first_letter == GAP || second_letter == GAP {
+                result.score += config.gap_penalty;
+            } else {
+                result.identity_denom += 1;
+                if first_letter == second_letter {
+                    result.identity_numer += 1;
+                    result.score += config.match_penalty;
+                } else {
+                    result.score += config.mismatch_penalty;
+                }
+            }
+        }
+        result.identity_denom = result.identity_denom.max(1);
+        Some(result)
+    }
+}
+
+/// Outcome of trimming columns off an alignment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrimmedMsa {
+    /// The alignment with only the retained columns.
+    pub msa: Msa,
+    /// For each column of the trimmed alignment, the index of the column it
+    /// came from in the original alignment.
+    pub retained_columns: Vec<usize>,
+}
+
+/// View of a single column of an [`Msa`].
+#[derive(Debug, Clone, Copy)]
+pub struct Column<'a> {
+    msa: &'a Msa,
+    j: usize,
+}
+
+impl<'a> Column<'a> {
+    /// Index of this column in the alignment.
+    pub fn index(&self) -> usize {
+        self.j
+    }
+
+    /// Number of letters in this column, i.e. the number of rows.
+    pub fn len(&self) -> usize {
+        self.msa.rows.len()
+    }
+
+    /// Whether the alignment has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.msa.rows.is_empty()
+    }
+
+    /// Letter of a row in this column, if in bounds.
+    pub fn get(&self, i: usize) -> Option<Letter> {
+        self.msa.get(i, self.j)
+    }
+
+    /// Iterates over the letters of this column, from top to bottom.
+    pub fn iter(&self) -> impl Iterator<Item = Letter> + 'a {
+        let j = self.j;
+        self.msa.rows.iter().map(move |row| row[j])
+    }
+
+    /// Collects the letters of this column.
+    pub fn to_vec(&self) -> Vec<Letter> {
+        self.iter().collect()
+    }
+
+    /// Number of gaps in this column.
+    pub fn gap_count(&self) -> usize {
+        self.iter().filter(|letter| *letter == GAP).count()
+    }
+
+    /// Fraction of gaps in this column, zero if there are no rows.
+    pub fn gap_fraction(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.gap_count() as f64 / self.len() as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::{GlobalAlignmentConfig, GlobalAlignmentResult};
+
+    use super::{Msa, MsaError};
+
+    fn letters(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn column_access_and_removal() {
+        let mut msa = Msa::from_rows(
+            vec!["a".into(), "b".into(), "c".into()],
+            vec![letters("AC-GT"), letters("A--GT"), letters("ACTG-")],
+        )
+        .unwrap();
+
+        assert_eq!(msa.width(), 5);
+        assert_eq!(msa.column(1).unwrap().to_vec(), letters("C-C"));
+        assert_eq!(msa.column(2).unwrap().gap_count(), 2);
+
+        assert_eq!(msa.remove_column(2), Some(letters("--T")));
+        assert_eq!(msa.row(2), Some(&letters("ACG-")[..]));
+
+        msa.retain_columns(|_, column| column.gap_count() == 0);
+        assert_eq!(msa.width(), 2);
+        assert_eq!(msa.row(1), Some(&letters("AG")[..]));
+
+        let (id, _) = msa.remove_row(0).unwrap();
+        assert_eq!(id, "a");
+        assert_eq!(msa.ids(), &["b".to_owned(), "c".to_owned()]);
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        let error = Msa::from_rows(
+            vec!["a".into(), "b".into()],
+            vec![letters("ACGT"), letters("AC")],
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error,
+            MsaError::RaggedRow { id: "b".into(), expected: 4, found: 2 }
+        );
+    }
+
+    #[test]
+    fn trim_gappy_columns_keeps_index_map() {
+        let msa = Msa::from_rows(
+            vec!["a".into(), "b".into(), "c".into(), "d".into()],
+            vec![
+                letters("A-CG-T"),
+                letters("A-C--T"),
+                letters("ATC--T"),
+                letters("A-CGAT"),
+            ],
+        )
+        .unwrap();
+
+        let trimmed = msa.trim_gappy_columns(0.5);
+
+        assert_eq!(trimmed.retained_columns, vec![0, 2, 3, 5]);
+        assert_eq!(trimmed.msa.row(1), Some(&letters("AC-T")[..]));
+        assert_eq!(trimmed.msa.width(), 4);
+    }
+
+    #[test]
+    fn pairwise_projection_drops_shared_gaps() {
+        let msa = Msa::from_rows(
+            vec!["a".into(), "b".into(), "c".into()],
+            vec![letters("AC-GT-A"), letters("A--CTTA"), letters("ACTG-TA")],
+        )
+        .unwrap();
+
+        let expected_result = GlobalAlignmentResult {
+            aligned_row_seq: letters("ACGT-A"),
+            aligned_column_seq: letters("A-CTTA"),
+            score: 1 - 2 - 1 + 1 - 2 + 1,
+            identity_numer: 3,
+            identity_denom: 4,
+        };
+
+        assert_eq!(
+            msa.pairwise(0, 1, GlobalAlignmentConfig::default()),
+            Some(expected_result)
+        );
+        assert_eq!(msa.pairwise(0, 3, GlobalAlignmentConfig::default()), None);
+    }
+
+    #[test]
+    fn reads_are_stacked_in_reference_coordinates() {
+        let reference = letters("ACGTACGTAC");
+        let first = letters("GTAGGCGT");
+        let second = letters("TACGTAC");
+        let msa = Msa::reference_guided(
+            "ref",
+            &reference,
+            &[("first", &first), ("second", &second)],
+            GlobalAlignmentConfig::default(),
+        );
+
+        assert_eq!(msa.row_count(), 3);
+        assert_eq!(msa.row(0), Some(&letters("ACGTA--CGTAC")[..]));
+        assert_eq!(msa.row(1), Some(&letters("--GTAGGCGT--")[..]));
+        assert_eq!(msa.row(2), Some(&letters("---TA--CGTAC")[..]));
+    }
+}
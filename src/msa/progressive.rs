@@ -0,0 +1,378 @@
+use crate::{
+    global::GlobalAlignmentConfig,
+    heatmap::IdentityMatrix,
+    letter::{Letter, GAP},
+    score::{Score, ScoringScheme},
+};
+
+use super::Msa;
+
+/// Guide tree of a progressive alignment, telling in which order sequences
+/// and groups of sequences are merged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuideTree {
+    /// A single sequence, by its index in the input.
+    Leaf(usize),
+    /// Two subtrees merged together.
+    Join {
+        /// First subtree.
+        left: Box<GuideTree>,
+        /// Second subtree.
+        right: Box<GuideTree>,
+        /// Half the distance between the subtrees when they were joined.
+        height: f64,
+    },
+}
+
+impl GuideTree {
+    /// Builds a tree by UPGMA clustering of the distances (one minus the
+    /// identities) of a matrix: the two closest clusters are joined first,
+    /// ties going to the earliest clusters. Returns `None` if the matrix is
+    /// empty.
+    pub fn upgma(identities: &IdentityMatrix) -> Option<Self> {
+        let size = identities.len();
+        let mut clusters: Vec<Option<(Self, usize)>> =
+            (0 .. size).map(|i| Some((Self::Leaf(i), 1))).collect();
+        let mut distances: Vec<Vec<f64>> = (0 .. size)
+            .map(|i| {
+                (0 .. size)
+                    .map(|j| 1.0 - identities.get(i, j).unwrap_or(0.0))
+                    .collect()
+            })
+            .collect();
+        for _ in 1 .. size {
+            let mut closest = None::<(usize, usize)>;
+            for i in 0 .. size {
+                for j in i + 1 .. size {
+                    if clusters[i].is_none() || clusters[j].is_none() {
+                        continue;
+                    }
+                    let closer = closest
+                        .is_none_or(|(a, b)| distances[i][j] < distances[a][b]);
+                    if closer {
+                        closest = Some((i, j));
+                    }
+                }
+            }
+            let (a, b) = closest?;
+            let (left, left_size) = clusters[a].take()?;
+            let (right, right_size) = clusters[b].take()?;
+            let height = distances[a][b] / 2.0;
+            let merged: Vec<f64> = distances[a]
+                .iter()
+                .zip(&distances[b])
+                .map(|(to_left, to_right)| {
+                    (to_left * left_size as f64 + to_right * right_size as f64)
+                        / (left_size + right_size) as f64
+                })
+                .collect();
+            for (row, &distance) in distances.iter_mut().zip(&merged) {
+                row[a] = distance;
+            }
+            distances[a] = merged;
+            let join = Self::Join {
+                left: Box::new(left),
+                right: Box::new(right),
+                height,
+            };
+            clusters[a] = Some((join, left_size + right_size));
+        }
+        clusters.into_iter().flatten().next().map(|(tree, _)| tree)
+    }
+
+    /// Indices of the sequences under this tree, from left to right.
+    pub fn leaves(&self) -> Vec<usize> {
+        match self {
+            Self::Leaf(index) => vec![*index],
+            Self::Join { left, right, .. } => {
+                let mut leaves = left.leaves();
+                leaves.extend(right.leaves());
+                leaves
+            },
+        }
+    }
+}
+
+/// Statistics of one column of a multiple alignment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnStats {
+    /// Most common letter of the column, gaps excluded, ties going to the
+    /// topmost letter, or a gap if the column has only gaps.
+    pub consensus: Letter,
+    /// Fraction of the letters (gaps excluded) equal to the consensus.
+    pub conservation: f64,
+    /// Fraction of gaps in the column.
+    pub gap_fraction: f64,
+}
+
+/// Outcome of a progressive multiple alignment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MsaResult {
+    /// The gapped sequences, in input order.
+    pub msa: Msa,
+    /// Tree that guided the merges, `None` if there were no sequences.
+    pub tree: Option<GuideTree>,
+    /// Sum-of-pairs score of the alignment: every pair of rows is scored on
+    /// the columns where they are not both gaps.
+    pub score: Score,
+    /// Statistics of every column, from left to right.
+    pub column_stats: Vec<ColumnStats>,
+}
+
+/// Aligns many sequences progressively, as ClustalW does: pairwise identities
+/// give distances, a UPGMA guide tree is built from them, and groups are
+/// merged following the tree by aligning their profiles with sum-of-pairs
+/// scores. Gaps inserted in a group are never removed by later merges.
+pub fn progressive_align(
+    seqs: &[(&str, &[Letter])],
+    config: GlobalAlignmentConfig,
+) -> MsaResult {
+    let identities = IdentityMatrix::compute(seqs, config);
+    let tree = GuideTree::upgma(&identities);
+    let mut rows = match &tree {
+        Some(tree) => merge_tree(tree, seqs, config),
+        None => Vec::new(),
+    };
+    rows.sort_by_key(|(index, _)| *index);
+
+    // every row comes out of the same merges, so widths always agree
+    let msa = Msa {
+        ids: seqs.iter().map(|(name, _)| (*name).to_owned()).collect(),
+        width: rows.first().map_or(0, |(_, row)| row.len()),
+        rows: rows.into_iter().map(|(_, row)| row).collect(),
+    };
+    let score = sum_of_pairs(&msa, config);
+    let column_stats = msa
+        .columns()
+        .map(|column| {
+            let letters: Vec<Letter> =
+                column.iter().filter(|&letter| letter != GAP).collect();
+            let (consensus, count) = letters
+                .iter()
+                .map(|&letter| {
+                    let count = letters
+                        .iter()
+                        .filter(|&&other| other == letter)
+                        .count();
+                    (letter, count)
+                })
+                .rev()
+                .max_by_key(|&(_, count)| count)
+                .unwrap_or((GAP, 0));
+            ColumnStats {
+                consensus,
+                conservation: if letters.is_empty() {
+                    0.0
+                } else {
+                    count as f64 / letters.len() as f64
+                },
+                gap_fraction: column.gap_fraction(),
+            }
+        })
+        .collect();
+    MsaResult { msa, tree, score, column_stats }
+}
+
+/// Score of a pair of letters of a multiple alignment: two gaps score
+/// nothing, a single gap scores the gap penalty.
+fn pair_score(a: Letter, b: Letter, config: GlobalAlignmentConfig) -> Score {
+    match (a == GAP, b == GAP) {
+        (true, true) => 0,
+        (true, false) | (false, true) => config.gap_penalty,
+        (false, false) => config.score(a, b),
+    }
+}
+
+/// Sum-of-pairs score of a whole alignment.
+fn sum_of_pairs(msa: &Msa, config: GlobalAlignmentConfig) -> Score {
+    msa.columns()
+        .map(|column| {
+            let letters = column.to_vec();
+            let mut score = 0;
+            for (k, &a) in letters.iter().enumerate() {
+                for &b in &letters[k + 1 ..] {
+                    score += pair_score(a, b, config);
+                }
+            }
+            score
+        })
+        .sum()
+}
+
+/// A group of gapped rows, each with the input index of its sequence.
+type Group = Vec<(usize, Vec<Letter>)>;
+
+/// Aligns the groups of a tree bottom-up.
+fn merge_tree(
+    tree: &GuideTree,
+    seqs: &[(&str, &[Letter])],
+    config: GlobalAlignmentConfig,
+) -> Group {
+    match tree {
+        GuideTree::Leaf(index) => vec![(*index, seqs[*index].1.to_vec())],
+        GuideTree::Join { left, right, .. } => merge_groups(
+            merge_tree(left, seqs, config),
+            merge_tree(right, seqs, config),
+            config,
+        ),
+    }
+}
+
+/// Sum-of-pairs score of aligning a column of each group.
+fn column_score(
+    left: &Group,
+    i: usize,
+    right: &Group,
+    j: usize,
+    config: GlobalAlignmentConfig,
+) -> Score {
+    let mut score = 0;
+    for (_, left_row) in left {
+        for (_, right_row) in right {
+            score += pair_score(left_row[i], right_row[j], config);
+        }
+    }
+    score
+}
+
+/// Sum-of-pairs score of aligning a column of a group with a column of gaps
+/// in the other group, of `other_size` rows.
+fn gap_column_score(
+    group: &Group,
+    i: usize,
+    other_size: usize,
+    config: GlobalAlignmentConfig,
+) -> Score {
+    let letters = group.iter().filter(|(_, row)| row[i] != GAP).count();
+    (letters * other_size) as Score * config.gap_penalty
+}
+
+/// Aligns the profiles of two groups globally, then inserts the gap columns
+/// the alignment requires into each group. Ties are broken as in
+/// Needleman-Wunsch tracebacks: top, then left, then diagonal.
+fn merge_groups(
+    left: Group,
+    right: Group,
+    config: GlobalAlignmentConfig,
+) -> Group {
+    let left_width = left[0].1.len();
+    let right_width = right[0].1.len();
+    let width = right_width + 1;
+    let mut matrix = vec![0; (left_width + 1) * width];
+    for i in 1 ..= left_width {
+        matrix[i * width] = matrix[(i - 1) * width]
+            + gap_column_score(&left, i - 1, right.len(), config);
+    }
+    for j in 1 ..= right_width {
+        matrix[j] =
+            matrix[j - 1] + gap_column_score(&right, j - 1, left.len(), config);
+    }
+    for i in 1 ..= left_width {
+        for j in 1 ..= right_width {
+            let diagonal = matrix[(i - 1) * width + j - 1]
+                + column_score(&left, i - 1, &right, j - 1, config);
+            let top = matrix[(i - 1) * width + j]
+                + gap_column_score(&left, i - 1, right.len(), config);
+            let side = matrix[i * width + j - 1]
+                + gap_column_score(&right, j - 1, left.len(), config);
+            matrix[i * width + j] = diagonal.max(top).max(side);
+        }
+    }
+
+    // columns of the merged alignment, from the last: the column of each
+    // group, or `None` for a column of gaps
+    let mut columns = Vec::with_capacity(left_width + right_width);
+    let (mut i, mut j) = (left_width, right_width);
+    while i > 0 || j > 0 {
+        let current = matrix[i * width + j];
+        if i > 0
+            && (j == 0
+                || current
+                    == matrix[(i - 1) * width + j]
+                        + gap_column_score(&left, i - 1, right.len(), config))
+        {
+            i -= 1;
+            columns.push((Some(i), None));
+        } else if j > 0
+            && (i == 0
+                || current
+                    == matrix[i * width + j - 1]
+                        + gap_column_score(&right, j - 1, left.len(), config))
+        {
+            j -= 1;
+            columns.push((None, Some(j)));
+        } else {
+            i -= 1;
+            j -= 1;
+            columns.push((Some(i), Some(j)));
+        }
+    }
+    columns.reverse();
+
+    let expand = |(index, row): (usize, Vec<Letter>), left_side: bool| {
+        let expanded = columns
+            .iter()
+            .map(|&(left_column, right_column)| {
+                let column = if left_side { left_column } else { right_column };
+                column.map_or(GAP, |k| row[k])
+            })
+            .collect();
+        (index, expanded)
+    };
+    left.into_iter()
+        .map(|row| expand(row, true))
+        .chain(right.into_iter().map(|row| expand(row, false)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::GlobalAlignmentConfig;
+
+    use super::{progressive_align, GuideTree};
+
+    #[test]
+    fn related_sequences_are_merged_by_the_guide_tree() {
+        let seqs: Vec<(&str, Vec<char>)> = [
+            ("a", "GATTACAGATTACA"),
+            ("b", "GATTACAGTTACA"),
+            ("c", "CCGGTTCCGGTTAA"),
+            ("d", "GATTACAGATTAC"),
+        ]
+        .into_iter()
+        .map(|(name, seq)| (name, seq.chars().collect()))
+        .collect();
+        let input: Vec<(&str, &[char])> =
+            seqs.iter().map(|(name, seq)| (*name, &seq[..])).collect();
+        let config = GlobalAlignmentConfig::default();
+
+        let result = progressive_align(&input, config);
+        let tree = result.tree.as_ref().unwrap();
+        assert_eq!(tree.leaves().last(), Some(&2));
+        assert!(matches!(tree, GuideTree::Join { right, .. }
+            if **right == GuideTree::Leaf(2)));
+
+        let msa = &result.msa;
+        assert_eq!(msa.row_count(), 4);
+        assert_eq!(msa.ids()[1], "b");
+        for ((_, seq), (_, row)) in seqs.iter().zip(msa.rows()) {
+            let letters: Vec<char> =
+                row.iter().copied().filter(|&letter| letter != '-').collect();
+            assert_eq!(&letters, seq);
+        }
+        let row = |i| msa.row(i).unwrap().iter().collect::<String>();
+        assert_eq!(row(0), "--GATTACAGATTACA");
+        assert_eq!(row(1), "--GATTACAG-TTACA");
+        assert_eq!(row(3), "--GATTACAGATTAC-");
+
+        assert_eq!(result.column_stats.len(), msa.width());
+        assert_eq!(result.column_stats[2].consensus, 'G');
+        assert_eq!(result.column_stats[3].conservation, 0.75);
+        assert_eq!(result.column_stats[10].gap_fraction, 0.5);
+        assert_eq!(result.column_stats[0].gap_fraction, 0.75);
+
+        let empty = progressive_align(&[], config);
+        assert!(empty.msa.is_empty());
+        assert_eq!(empty.tree, None);
+    }
+}
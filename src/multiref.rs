@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use crate::{
+    letter::{Letter, GAP},
+    local::{
+        traceback_best_sw_alignment,
+        LocalAlignmentConfig,
+        LocalAlignmentResult,
+    },
+    matrix::AlignmentMatrix,
+};
+
+/// Several reference records joined into one sequence, separated by hard
+/// boundaries that local alignments never cross.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcatenatedReferences {
+    /// Records joined by one separator letter each.
+    seq: Vec<Letter>,
+    /// Identifiers of the records.
+    ids: Vec<String>,
+    /// Ranges of the records in the joined sequence.
+    ranges: Vec<Range<usize>>,
+}
+
+/// A local hit against one record of a [`ConcatenatedReferences`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceHit {
+    /// Index of the record hit, in insertion order.
+    pub record: usize,
+    /// The alignment, with the query displayed as a row and column positions
+    /// relative to the record.
+    pub result: LocalAlignmentResult,
+}
+
+impl ConcatenatedReferences {
+    /// Joins records, given as pairs of identifier and sequence.
+    pub fn new(records: &[(&str, &[Letter])]) -> Self {
+        let mut references = Self {
+            seq: Vec::new(),
+            ids: Vec::with_capacity(records.len()),
+            ranges: Vec::with_capacity(records.len()),
+        };
+        for &(id, seq) in records {
+            references.push(id, seq);
+        }
+        references
+    }
+
+    /// Appends a record.
+    pub fn push(&mut self, id: &str, seq: &[Letter]) {
+        if !self.ranges.is_empty() {
+            self.seq.push(GAP);
+        }
+        let start = self.seq.len();
+        self.seq.extend_from_slice(seq);
+        self.ids.push(id.to_owned());
+        self.ranges.push(start .. self.seq.len());
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Tests whether there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Identifier of a record.
+    pub fn id(&self, record: usize) -> Option<&str> {
+        self.ids.get(record).map(String::as_str)
+    }
+
+    /// Sequence of a record.
+    pub fn seq(&self, record: usize) -> Option<&[Letter]> {
+        self.ranges.get(record).map(|range| &self.seq[range.clone()])
+    }
+
+    /// Record containing a position of the joined sequence, if not a
+    /// separator.
+    fn record_at(&self, position: usize) -> Option<usize> {
+        let record = self.ranges.partition_point(|range| range.end <= position);
+        self.ranges
+            .get(record)
+            .filter(|range| range.contains(&position))
+            .map(|_| record)
+    }
+
+    /// Fills a Smith-Waterman matrix of the query against the joined
+    /// sequence, where the matrix column of every separator is forced to
+    /// zero, so that no alignment crosses a boundary.
+    fn fill_matrix(
+        &self,
+        query: &[Letter],
+        config: LocalAlignmentConfig,
+    ) -> AlignmentMatrix {
+        let mut matrix =
+            AlignmentMatrix::zeroed(query.len() + 1, self.seq.len() + 1);
+        for (j, &reference_letter) in self.seq.iter().enumerate() {
+            if self.record_at(j).is_none() {
+                continue;
+            }
+            for (i, &query_letter) in query.iter().enumerate() {
+                let no_gap_score = matrix[[i, j]]
+                    + if query_letter == reference_letter {
+                        config.match_penalty
+                    } else {
+                        config.mismatch_penalty
+                    };
+                let best_gap_score = matrix[[i, j + 1]].max(matrix[[i + 1, j]])
+                    + config.gap_penalty;
+                matrix[[i + 1, j + 1]] =
+                    no_gap_score.max(best_gap_score).max(0);
+            }
+        }
+        matrix
+    }
+
+    /// Computes the best local alignments of a query against all records at
+    /// once, reporting the record of each hit. Mapping qualities account for
+    /// equally good hits in other records.
+    pub fn best_smith_waterman(
+        &self,
+        query: &[Letter],
+        config: LocalAlignmentConfig,
+    ) -> Vec<ReferenceHit> {
+        let matrix = self.fill_matrix(query, config);
+        traceback_best_sw_alignment(query, &self.seq, config, &matrix)
+            .into_iter()
+            .filter(|result| result.score > 0)
+            .filter_map(|mut result| {
+                let record = self.record_at(result.aligned_column_seq.start)?;
+                let offset = self.ranges[record].start;
+                result.aligned_column_seq.start -= offset;
+                result.aligned_column_seq.end -= offset;
+                Some(ReferenceHit { record, result })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::local::{best_smith_waterman, LocalAlignmentConfig};
+
+    use super::ConcatenatedReferences;
+
+    #[test]
+    fn hits_are_attributed_to_records_without_crossing_them() {
+        let first: Vec<char> = "CCCCGATT".chars().collect();
+        let second: Vec<char> = "ACACCCCC".chars().collect();
+        let third: Vec<char> = "TTTGATTACATT".chars().collect();
+        let query: Vec<char> = "GATTACA".chars().collect();
+        let config = LocalAlignmentConfig::default();
+        let references = ConcatenatedReferences::new(&[
+            ("first", &first),
+            ("second", &second),
+            ("third", &third),
+        ]);
+        assert_eq!(references.len(), 3);
+        assert_eq!(references.id(1), Some("second"));
+        assert_eq!(references.seq(2), Some(&third[..]));
+
+        let hits = references.best_smith_waterman(&query, config);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].record, 2);
+        assert_eq!(hits[0].result.aligned_column_seq.start, 3);
+        assert_eq!(hits[0].result.aligned_column_seq.end, 10);
+        assert_eq!(
+            hits[0].result,
+            best_smith_waterman(&query, &third, config)[0]
+        );
+
+        let boundary: Vec<char> = "GATTACAC".chars().collect();
+        let hits = ConcatenatedReferences::new(&[
+            ("first", &first),
+            ("second", &second),
+        ])
+        .best_smith_waterman(&boundary, config);
+        assert!(hits.iter().all(|hit| hit.result.score == 4));
+    }
+}
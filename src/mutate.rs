@@ -0,0 +1,178 @@
+use crate::{
+    generate::Rng,
+    letter::{Letter, GAP},
+};
+
+/// Per-letter probabilities of each kind of mutation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MutationRates {
+    /// Probability of replacing a letter by a different one.
+    pub substitution: f64,
+    /// Probability of inserting a random letter before a position (or at the
+    /// end of the sequence).
+    pub insertion: f64,
+    /// Probability of deleting a letter.
+    pub deletion: f64,
+}
+
+impl Default for MutationRates {
+    fn default() -> Self {
+        Self { substitution: 0.01, insertion: 0.001, deletion: 0.001 }
+    }
+}
+
+/// A single edit applied to the original sequence.
+///
+/// Positions refer to the original sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edit {
+    /// The letter at `position` was replaced.
+    Substitution {
+        /// Position of the replaced letter.
+        position: usize,
+        /// Letter in the original sequence.
+        original: Letter,
+        /// Letter in the mutant.
+        replacement: Letter,
+    },
+    /// A letter was inserted right before `position`.
+    Insertion {
+        /// Position before which the letter was inserted.
+        position: usize,
+        /// Inserted letter.
+        letter: Letter,
+    },
+    /// The letter at `position` was removed.
+    Deletion {
+        /// Position of the removed letter.
+        position: usize,
+        /// Removed letter.
+        original: Letter,
+    },
+}
+
+/// Outcome of a mutation simulation: the mutant and the ground truth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutant {
+    /// The mutated sequence.
+    pub seq: Vec<Letter>,
+    /// Every edit applied, in original sequence order.
+    pub edits: Vec<Edit>,
+    /// The original sequence, gapped according to the true alignment.
+    pub aligned_original: Vec<Letter>,
+    /// The mutant sequence, gapped according to the true alignment.
+    pub aligned_mutant: Vec<Letter>,
+}
+
+/// Derives a mutated copy of `seq`, drawing new letters from `alphabet`.
+///
+/// For every position, an insertion may first happen before it; then the
+/// letter is either deleted, substituted by a different letter of the
+/// alphabet, or kept. An insertion may also happen at the very end.
+pub fn mutate(
+    seq: &[Letter],
+    rates: MutationRates,
+    alphabet: &[Letter],
+    rng: &mut Rng,
+) -> Mutant {
+    let mut mutant = Mutant {
+        seq: Vec::with_capacity(seq.len()),
+        edits: Vec::new(),
+        aligned_original: Vec::with_capacity(seq.len()),
+        aligned_mutant: Vec::with_capacity(seq.len()),
+    };
+
+    for position in 0 ..= seq.len() {
+        if rng.next_f64() < rates.insertion {
+            if let Some(&letter) = rng.choose(alphabet) {
+                mutant.seq.push(letter);
+                mutant.edits.push(Edit::Insertion { position, letter });
+                mutant.aligned_original.push(GAP);
+                mutant.aligned_mutant.push(letter);
+            }
+        }
+
+        let Some(&original) = seq.get(position) else {
+            break;
+        };
+        if rng.next_f64() < rates.deletion {
+            mutant.edits.push(Edit::Deletion { position, original });
+            mutant.aligned_original.push(original);
+            mutant.aligned_mutant.push(GAP);
+            continue;
+        }
+
+        let mut letter = original;
+        if rng.next_f64() < rates.substitution {
+            let candidates: Vec<Letter> = alphabet
+                .iter()
+                .copied()
+                .filter(|candidate| *candidate != original)
+                .collect();
+            if let Some(&replacement) = rng.choose(&candidates) {
+                mutant.edits.push(Edit::Substitution {
+                    position,
+                    original,
+                    replacement,
+                });
+                letter = replacement;
+            }
+        }
+        mutant.seq.push(letter);
+        mutant.aligned_original.push(original);
+        mutant.aligned_mutant.push(letter);
+    }
+
+    mutant
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng, DNA},
+        letter::GAP,
+    };
+
+    use super::{mutate, Edit, MutationRates};
+
+    #[test]
+    fn edits_explain_the_mutant() {
+        let mut rng = Rng::new(11);
+        let original = random_dna(200, &mut rng);
+        let rates = MutationRates {
+            substitution: 0.1,
+            insertion: 0.05,
+            deletion: 0.05,
+        };
+
+        let mutant = mutate(&original, rates, DNA, &mut rng);
+
+        let ungapped_original: Vec<char> = mutant
+            .aligned_original
+            .iter()
+            .copied()
+            .filter(|letter| *letter != GAP)
+            .collect();
+        let ungapped_mutant: Vec<char> = mutant
+            .aligned_mutant
+            .iter()
+            .copied()
+            .filter(|letter| *letter != GAP)
+            .collect();
+        assert_eq!(ungapped_original, original);
+        assert_eq!(ungapped_mutant, mutant.seq);
+
+        let insertions = mutant
+            .edits
+            .iter()
+            .filter(|edit| matches!(edit, Edit::Insertion { .. }))
+            .count();
+        let deletions = mutant
+            .edits
+            .iter()
+            .filter(|edit| matches!(edit, Edit::Deletion { .. }))
+            .count();
+        assert!(!mutant.edits.is_empty());
+        assert_eq!(mutant.seq.len() + deletions, original.len() + insertions);
+    }
+}
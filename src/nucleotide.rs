@@ -0,0 +1,218 @@
+use std::{error::Error, fmt};
+
+use crate::letter::GenericLetter;
+
+/// Base of a DNA sequence, or a gap in aligned sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dna {
+    /// Adenine.
+    A,
+    /// Cytosine.
+    C,
+    /// Guanine.
+    G,
+    /// Thymine.
+    T,
+    /// Gap in an aligned sequence, `-`.
+    Gap,
+}
+
+impl Dna {
+    /// Every base, followed by the gap.
+    pub const ALL: [Self; 5] = [Self::A, Self::C, Self::G, Self::T, Self::Gap];
+
+    /// Reads a base, in either case.
+    pub fn from_char(ch: char) -> Option<Self> {
+        let ch = ch.to_ascii_uppercase();
+        Self::ALL.into_iter().find(|base| base.to_char() == ch)
+    }
+}
+
+impl GenericLetter for Dna {
+    const GAP: Self = Self::Gap;
+
+    fn to_char(self) -> char {
+        match self {
+            Self::A => 'A',
+            Self::C => 'C',
+            Self::G => 'G',
+            Self::T => 'T',
+            Self::Gap => '-',
+        }
+    }
+}
+
+impl fmt::Display for Dna {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Base of an RNA sequence, or a gap in aligned sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rna {
+    /// Adenine.
+    A,
+    /// Cytosine.
+    C,
+    /// Guanine.
+    G,
+    /// Uracil.
+    U,
+    /// Gap in an aligned sequence, `-`.
+    Gap,
+}
+
+impl Rna {
+    /// Every base, followed by the gap.
+    pub const ALL: [Self; 5] = [Self::A, Self::C, Self::G, Self::U, Self::Gap];
+
+    /// Reads a base, in either case.
+    pub fn from_char(ch: char) -> Option<Self> {
+        let ch = ch.to_ascii_uppercase();
+        Self::ALL.into_iter().find(|base| base.to_char() == ch)
+    }
+}
+
+impl GenericLetter for Rna {
+    const GAP: Self = Self::Gap;
+
+    fn to_char(self) -> char {
+        match self {
+            Self::A => 'A',
+            Self::C => 'C',
+            Self::G => 'G',
+            Self::U => 'U',
+            Self::Gap => '-',
+        }
+    }
+}
+
+impl fmt::Display for Rna {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+// transcription: thymine becomes uracil
+impl From<Dna> for Rna {
+    fn from(base: Dna) -> Self {
+        match base {
+            Dna::A => Self::A,
+            Dna::C => Self::C,
+            Dna::G => Self::G,
+            Dna::T => Self::U,
+            Dna::Gap => Self::Gap,
+        }
+    }
+}
+
+// reverse transcription: uracil becomes thymine
+impl From<Rna> for Dna {
+    fn from(base: Rna) -> Self {
+        match base {
+            Rna::A => Self::A,
+            Rna::C => Self::C,
+            Rna::G => Self::G,
+            Rna::U => Self::T,
+            Rna::Gap => Self::Gap,
+        }
+    }
+}
+
+/// Converts a DNA sequence into RNA, so it can be aligned with transcripts.
+pub fn transcribe(seq: &[Dna]) -> Vec<Rna> {
+    seq.iter().copied().map(Rna::from).collect()
+}
+
+/// Converts an RNA sequence back into DNA.
+pub fn reverse_transcribe(seq: &[Rna]) -> Vec<Dna> {
+    seq.iter().copied().map(Dna::from).collect()
+}
+
+/// Error raised when a nucleotide sequence has an invalid base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidBase {
+    /// The offending character.
+    pub base: char,
+    /// Position (starting from 0) of the base in the sequence.
+    pub position: usize,
+}
+
+impl fmt::Display for InvalidBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid base {:?} at position {}", self.base, self.position)
+    }
+}
+
+impl Error for InvalidBase {}
+
+/// Parses a DNA sequence, reporting the first invalid base. Gaps are
+/// invalid, as they only belong in aligned sequences.
+pub fn parse_dna(text: &str) -> Result<Vec<Dna>, InvalidBase> {
+    text.chars()
+        .enumerate()
+        .map(|(position, base)| {
+            Dna::from_char(base)
+                .filter(|&parsed| parsed != Dna::Gap)
+                .ok_or(InvalidBase { base, position })
+        })
+        .collect()
+}
+
+/// Parses an RNA sequence, reporting the first invalid base. Gaps are
+/// invalid, as they only belong in aligned sequences.
+pub fn parse_rna(text: &str) -> Result<Vec<Rna>, InvalidBase> {
+    text.chars()
+        .enumerate()
+        .map(|(position, base)| {
+            Rna::from_char(base)
+                .filter(|&parsed| parsed != Rna::Gap)
+                .ok_or(InvalidBase { base, position })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        letter::GenericLetter,
+    };
+
+    use super::{
+        parse_dna,
+        parse_rna,
+        reverse_transcribe,
+        transcribe,
+        InvalidBase,
+        Rna,
+    };
+
+    #[test]
+    fn transcripts_align_against_converted_dna() {
+        let gene = parse_dna("GATTACA").unwrap();
+        let transcript = parse_rna("gauuca").unwrap();
+        assert_eq!(reverse_transcribe(&transcribe(&gene)), gene);
+        assert_eq!(
+            parse_rna("GATC"),
+            Err(InvalidBase { base: 'T', position: 2 })
+        );
+        assert_eq!(
+            parse_dna("GA-TACA"),
+            Err(InvalidBase { base: '-', position: 2 })
+        );
+        assert_eq!(parse_rna("-"), Err(InvalidBase { base: '-', position: 0 }));
+
+        let result = needleman_wunsch(
+            &transcribe(&gene),
+            &transcript,
+            GlobalAlignmentConfig::default(),
+        );
+        let chars = |seq: &[Rna]| {
+            seq.iter().map(|base| base.to_char()).collect::<String>()
+        };
+        assert_eq!(chars(&result.aligned_row_seq), "GAUUACA");
+        assert_eq!(chars(&result.aligned_column_seq), "GAUU-CA");
+    }
+}
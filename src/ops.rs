@@ -0,0 +1,161 @@
+use crate::{
+    cigar::{Cigar, CigarOp},
+    letter::{Letter, GAP},
+};
+
+/// Operation of one alignment column, taking the sequence associated with the
+/// row display as the reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlignOp {
+    /// Both letters aligned and equal.
+    Match,
+    /// Both letters aligned but different.
+    Mismatch,
+    /// Column letter aligned with a gap in the row sequence.
+    Insert,
+    /// Row letter aligned with a gap in the column sequence.
+    Delete,
+}
+
+impl AlignOp {
+    /// Whether this operation consumes a letter of the row sequence.
+    pub fn consumes_row(self) -> bool {
+        self != Self::Insert
+    }
+
+    /// Whether this operation consumes a letter of the column sequence.
+    pub fn consumes_column(self) -> bool {
+        self != Self::Delete
+    }
+}
+
+/// An alignment as a sequence of column operations, instead of two gapped
+/// letter vectors, so it can be analyzed without zipping letters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AlignmentOps {
+    /// Operations of every column, in order.
+    pub ops: Vec<AlignOp>,
+}
+
+impl AlignmentOps {
+    /// Converts two aligned sequences of equal length. Columns with two gaps
+    /// are skipped.
+    pub fn from_aligned(
+        aligned_row: &[Letter],
+        aligned_col: &[Letter],
+    ) -> Self {
+        let ops = aligned_row
+            .iter()
+            .zip(aligned_col)
+            .filter_map(|(&row_letter, &col_letter)| {
+                match (row_letter == GAP, col_letter == GAP) {
+                    (true, true) => None,
+                    (true, false) => Some(AlignOp::Insert),
+                    (false, true) => Some(AlignOp::Delete),
+                    (false, false) if row_letter == col_letter => {
+                        Some(AlignOp::Match)
+                    },
+                    (false, false) => Some(AlignOp::Mismatch),
+                }
+            })
+            .collect();
+        Self { ops }
+    }
+
+    /// Rebuilds the two aligned sequences from the aligned slices of the
+    /// original sequences. Returns `None` if the operations do not consume
+    /// exactly these slices, or if a match or mismatch disagrees with the
+    /// letters.
+    pub fn to_aligned(
+        &self,
+        row_seq: &[Letter],
+        column_seq: &[Letter],
+    ) -> Option<(Vec<Letter>, Vec<Letter>)> {
+        let mut row_letters = row_seq.iter().copied();
+        let mut column_letters = column_seq.iter().copied();
+        let mut aligned_row = Vec::with_capacity(self.ops.len());
+        let mut aligned_col = Vec::with_capacity(self.ops.len());
+        for &op in &self.ops {
+            let row_letter =
+                if op.consumes_row() { row_letters.next()? } else { GAP };
+            let col_letter =
+                if op.consumes_column() { column_letters.next()? } else { GAP };
+            let consistent = match op {
+                AlignOp::Match => row_letter == col_letter,
+                AlignOp::Mismatch => row_letter != col_letter,
+                AlignOp::Insert | AlignOp::Delete => true,
+            };
+            if !consistent {
+                return None;
+            }
+            aligned_row.push(row_letter);
+            aligned_col.push(col_letter);
+        }
+        if row_letters.next().is_some() || column_letters.next().is_some() {
+            return None;
+        }
+        Some((aligned_row, aligned_col))
+    }
+
+    /// Number of operations of each kind, in the order match, mismatch,
+    /// insert and delete.
+    pub fn counts(&self) -> [usize; 4] {
+        let mut counts = [0; 4];
+        for &op in &self.ops {
+            counts[op as usize] += 1;
+        }
+        counts
+    }
+
+    /// Encodes the operations as an extended CIGAR string, with `=` and `X`.
+    pub fn to_cigar(&self) -> Cigar {
+        let mut cigar = Cigar::default();
+        for &op in &self.ops {
+            let op = match op {
+                AlignOp::Match => CigarOp::Equal,
+                AlignOp::Mismatch => CigarOp::Mismatch,
+                AlignOp::Insert => CigarOp::Insertion,
+                AlignOp::Delete => CigarOp::Deletion,
+            };
+            cigar.push(op, 1);
+        }
+        cigar
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+    };
+
+    use super::{AlignOp, AlignmentOps};
+
+    #[test]
+    fn operations_convert_both_ways() {
+        let mut rng = Rng::new(69);
+        let row_seq = random_dna(40, &mut rng);
+        let column_seq = random_dna(36, &mut rng);
+        let result = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+
+        let ops = result.alignment_ops();
+        let [matches, mismatches, inserts, deletes] = ops.counts();
+        assert_eq!(matches as u32, result.identity_numer);
+        assert_eq!(matches + mismatches + inserts, column_seq.len());
+        assert_eq!(matches + mismatches + deletes, row_seq.len());
+        assert_eq!(ops.to_cigar(), result.extended_cigar());
+        assert_eq!(
+            ops.to_aligned(&row_seq, &column_seq),
+            Some((result.aligned_row_seq.clone(), result.aligned_column_seq))
+        );
+        assert_eq!(ops.to_aligned(&row_seq[1 ..], &column_seq), None);
+
+        let wrong = AlignmentOps { ops: vec![AlignOp::Match] };
+        assert_eq!(wrong.to_aligned(&['A'], &['C']), None);
+    }
+}
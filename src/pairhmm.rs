@@ -0,0 +1,392 @@
+use std::{error::Error, fmt};
+
+use crate::letter::{Letter, GAP};
+
+/// Error returned when a pair-HMM parameter is not a valid probability for
+/// its transition, which would turn the log-probabilities into NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidPairHmmConfig {
+    /// Name of the offending parameter.
+    pub parameter: &'static str,
+}
+
+impl fmt::Display for InvalidPairHmmConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pair-HMM parameter {} is out of range", self.parameter)
+    }
+}
+
+impl Error for InvalidPairHmmConfig {}
+
+/// Parameters of the three-state (match, gap in column, gap in row) pair
+/// hidden Markov model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PairHmmConfig {
+    /// Probability of opening a gap from the match state.
+    pub gap_open: f64,
+    /// Probability of staying in a gap state.
+    pub gap_extend: f64,
+    /// Probability that the match state emits two identical letters.
+    pub identity: f64,
+    /// Number of distinct letters, e.g. 4 for DNA and 20 for proteins.
+    pub alphabet_size: usize,
+}
+
+impl Default for PairHmmConfig {
+    fn default() -> Self {
+        Self {
+            gap_open: 0.05,
+            gap_extend: 0.4,
+            identity: 0.8,
+            alphabet_size: 4,
+        }
+    }
+}
+
+impl PairHmmConfig {
+    /// Checks that the parameters form a valid model: `gap_open` must be in
+    /// `[0, 0.5)`, since two gap states are opened from the match state,
+    /// `gap_extend` in `[0, 1)` and `identity` in `[0, 1]`.
+    pub fn validate(&self) -> Result<(), InvalidPairHmmConfig> {
+        if !(0.0 .. 0.5).contains(&self.gap_open) {
+            Err(InvalidPairHmmConfig { parameter: "gap_open" })
+        } else if !(0.0 .. 1.0).contains(&self.gap_extend) {
+            Err(InvalidPairHmmConfig { parameter: "gap_extend" })
+        } else if !(0.0 ..= 1.0).contains(&self.identity) {
+            Err(InvalidPairHmmConfig { parameter: "identity" })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Log-probability of the match state emitting the given pair.
+    fn log_match_emission(
+        &self,
+        row_letter: Letter,
+        column_letter: Letter,
+    ) -> f64 {
+        let size = self.alphabet_size.max(2) as f64;
+        if row_letter == column_letter {
+            (self.identity / size).ln()
+        } else {
+            ((1.0 - self.identity) / (size * (size - 1.0))).ln()
+        }
+    }
+
+    /// Log-probability of a gap state emitting a single letter.
+    fn log_gap_emission(&self) -> f64 {
+        -(self.alphabet_size.max(2) as f64).ln()
+    }
+}
+
+/// Logarithm of the sum of the exponentials, computed stably.
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.iter().map(|value| (value - max).exp()).sum::<f64>().ln()
+}
+
+/// Log-probabilities of the three states over every cell of the matrix.
+struct StateTables {
+    matched: Vec<f64>,
+    row_gap: Vec<f64>,
+    column_gap: Vec<f64>,
+    width: usize,
+}
+
+impl StateTables {
+    fn new(height: usize, width: usize) -> Self {
+        let cells = height * width;
+        Self {
+            matched: vec![f64::NEG_INFINITY; cells],
+            row_gap: vec![f64::NEG_INFINITY; cells],
+            column_gap: vec![f64::NEG_INFINITY; cells],
+            width,
+        }
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * self.width + j
+    }
+}
+
+/// Posterior probabilities that each pair of letters is aligned together,
+/// over all alignments weighted by the pair-HMM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PosteriorMatrix {
+    values: Vec<f64>,
+    width: usize,
+    height: usize,
+}
+
+impl PosteriorMatrix {
+    /// Number of letters of the row sequence.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of letters of the column sequence.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Probability that letter `i` of the row sequence is aligned with letter
+    /// `j` of the column sequence.
+    pub fn get(&self, i: usize, j: usize) -> Option<f64> {
+        if i < self.height && j < self.width {
+            Some(self.values[i * self.width + j])
+        } else {
+            None
+        }
+    }
+
+    /// Probability that letter `i` of the row sequence is aligned with a gap.
+    pub fn row_gap(&self, i: usize) -> Option<f64> {
+        if i < self.height {
+            Some(self.row_gap_unchecked(i))
+        } else {
+            None
+        }
+    }
+
+    /// Probability that letter `j` of the column sequence is aligned with a
+    /// gap.
+    pub fn column_gap(&self, j: usize) -> Option<f64> {
+        if j < self.width {
+            Some(self.column_gap_unchecked(j))
+        } else {
+            None
+        }
+    }
+
+    fn row_gap_unchecked(&self, i: usize) -> f64 {
+        let row = &self.values[i * self.width .. (i + 1) * self.width];
+        (1.0 - row.iter().sum::<f64>()).max(0.0)
+    }
+
+    fn column_gap_unchecked(&self, j: usize) -> f64 {
+        let sum: f64 =
+            (0 .. self.height).map(|i| self.values[i * self.width + j]).sum();
+        (1.0 - sum).max(0.0)
+    }
+}
+
+/// Computes the posterior probability of every letter pair with the forward
+/// and backward algorithms, in log space. Fails if the parameters are not
+/// valid, see [`PairHmmConfig::validate`].
+pub fn posterior_matrix(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: PairHmmConfig,
+) -> Result<PosteriorMatrix, InvalidPairHmmConfig> {
+    config.validate()?;
+    let height = row_seq.len() + 1;
+    let width = column_seq.len() + 1;
+    let stay_match = (1.0 - 2.0 * config.gap_open).ln();
+    let open = config.gap_open.ln();
+    let extend = config.gap_extend.ln();
+    let close = (1.0 - config.gap_extend).ln();
+    let gap_emission = config.log_gap_emission();
+
+    let mut forward = StateTables::new(height, width);
+    forward.matched[0] = 0.0;
+    for i in 0 .. height {
+        for j in 0 .. width {
+            let here = forward.index(i, j);
+            if i > 0 && j > 0 {
+                let previous = forward.index(i - 1, j - 1);
+                forward.matched[here] = config
+                    .log_match_emission(row_seq[i - 1], column_seq[j - 1])
+                    + log_sum_exp(&[
+                        forward.matched[previous] + stay_match,
+                        forward.row_gap[previous] + close,
+                        forward.column_gap[previous] + close,
+                    ]);
+            }
+            if i > 0 {
+                let previous = forward.index(i - 1, j);
+                forward.row_gap[here] = gap_emission
+                    + log_sum_exp(&[
+                        forward.matched[previous] + open,
+                        forward.row_gap[previous] + extend,
+                    ]);
+            }
+            if j > 0 {
+                let previous = forward.index(i, j - 1);
+                forward.column_gap[here] = gap_emission
+                    + log_sum_exp(&[
+                        forward.matched[previous] + open,
+                        forward.column_gap[previous] + extend,
+                    ]);
+            }
+        }
+    }
+    let last = forward.index(height - 1, width - 1);
+    let total = log_sum_exp(&[
+        forward.matched[last],
+        forward.row_gap[last],
+        forward.column_gap[last],
+    ]);
+
+    let mut backward = StateTables::new(height, width);
+    backward.matched[last] = 0.0;
+    backward.row_gap[last] = 0.0;
+    backward.column_gap[last] = 0.0;
+    for i in (0 .. height).rev() {
+        for j in (0 .. width).rev() {
+            let here = backward.index(i, j);
+            if here == last {
+                continue;
+            }
+            let diagonal = if i + 1 < height && j + 1 < width {
+                config.log_match_emission(row_seq[i], column_seq[j])
+                    + backward.matched[backward.index(i + 1, j + 1)]
+            } else {
+                f64::NEG_INFINITY
+            };
+            let down = if i + 1 < height {
+                gap_emission + backward.row_gap[backward.index(i + 1, j)]
+            } else {
+                f64::NEG_INFINITY
+            };
+            let right = if j + 1 < width {
+                gap_emission + backward.column_gap[backward.index(i, j + 1)]
+            } else {
+                f64::NEG_INFINITY
+            };
+            backward.matched[here] = log_sum_exp(&[
+                stay_match + diagonal,
+                open + down,
+                open + right,
+            ]);
+            backward.row_gap[here] =
+                log_sum_exp(&[close + diagonal, extend + down]);
+            backward.column_gap[here] =
+                log_sum_exp(&[close + diagonal, extend + right]);
+        }
+    }
+
+    let mut values = Vec::with_capacity(row_seq.len() * column_seq.len());
+    for i in 1 .. height {
+        for j in 1 .. width {
+            let here = forward.index(i, j);
+            values.push(
+                (forward.matched[here] + backward.matched[here] - total).exp(),
+            );
+        }
+    }
+    Ok(PosteriorMatrix {
+        values,
+        width: column_seq.len(),
+        height: row_seq.len(),
+    })
+}
+
+/// Maximum expected accuracy alignment, with the confidence of every column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeaAlignment {
+    /// The sequence that was associated with "row" display, aligned.
+    pub aligned_row_seq: Vec<Letter>,
+    /// The sequence that was associated with "column" display, aligned.
+    pub aligned_column_seq: Vec<Letter>,
+    /// Posterior probability of every column: of the pair for aligned letters,
+    /// of the gap for gapped columns.
+    pub confidence: Vec<f64>,
+    /// Expected number of correctly aligned letter pairs.
+    pub expected_accuracy: f64,
+}
+
+/// Computes the alignment maximizing the sum of posterior probabilities of its
+/// aligned pairs (maximum expected accuracy). Fails if the parameters are not
+/// valid, see [`PairHmmConfig::validate`].
+pub fn mea_alignment(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: PairHmmConfig,
+) -> Result<MeaAlignment, InvalidPairHmmConfig> {
+    let posteriors = posterior_matrix(row_seq, column_seq, config)?;
+    let height = row_seq.len() + 1;
+    let width = column_seq.len() + 1;
+    let mut table = vec![0.0; height * width];
+    for i in 1 .. height {
+        for j in 1 .. width {
+            let pair = posteriors.values[(i - 1) * column_seq.len() + j - 1];
+            table[i * width + j] = (table[(i - 1) * width + j - 1] + pair)
+                .max(table[(i - 1) * width + j])
+                .max(table[i * width + j - 1]);
+        }
+    }
+
+    let mut alignment = MeaAlignment {
+        aligned_row_seq: Vec::with_capacity(height + width),
+        aligned_column_seq: Vec::with_capacity(height + width),
+        confidence: Vec::with_capacity(height + width),
+        expected_accuracy: table[height * width - 1],
+    };
+    let (mut i, mut j) = (height - 1, width - 1);
+    while i > 0 || j > 0 {
+        let here = table[i * width + j];
+        if i > 0 && (j == 0 || here == table[(i - 1) * width + j]) {
+            i -= 1;
+            alignment.aligned_row_seq.push(row_seq[i]);
+            alignment.aligned_column_seq.push(GAP);
+            alignment.confidence.push(posteriors.row_gap_unchecked(i));
+        } else if i == 0 || here == table[i * width + j - 1] {
+            j -= 1;
+            alignment.aligned_row_seq.push(GAP);
+            alignment.aligned_column_seq.push(column_seq[j]);
+            alignment.confidence.push(posteriors.column_gap_unchecked(j));
+        } else {
+            i -= 1;
+            j -= 1;
+            alignment.aligned_row_seq.push(row_seq[i]);
+            alignment.aligned_column_seq.push(column_seq[j]);
+            alignment
+                .confidence
+                .push(posteriors.values[i * column_seq.len() + j]);
+        }
+    }
+    alignment.aligned_row_seq.reverse();
+    alignment.aligned_column_seq.reverse();
+    alignment.confidence.reverse();
+    Ok(alignment)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        mea_alignment,
+        posterior_matrix,
+        InvalidPairHmmConfig,
+        PairHmmConfig,
+    };
+
+    #[test]
+    fn identical_sequences_are_confidently_aligned() {
+        let seq: Vec<char> = "GATTACAGATTACA".chars().collect();
+        let config = PairHmmConfig::default();
+
+        let posteriors = posterior_matrix(&seq, &seq, config).unwrap();
+        for i in 0 .. seq.len() {
+            let diagonal = posteriors.get(i, i).unwrap();
+            assert!(diagonal > 0.5, "position {i}: {diagonal}");
+            assert!(posteriors.row_gap(i).unwrap() < 0.5);
+        }
+        assert_eq!(posteriors.row_gap(seq.len()), None);
+        assert_eq!(posteriors.column_gap(seq.len()), None);
+
+        let alignment = mea_alignment(&seq, &seq, config).unwrap();
+        assert_eq!(alignment.aligned_row_seq, seq);
+        assert_eq!(alignment.aligned_column_seq, seq);
+        assert!(alignment.confidence.iter().all(|p| (0.0 ..= 1.0).contains(p)));
+        assert!(alignment.expected_accuracy > 0.5 * seq.len() as f64);
+
+        let invalid = PairHmmConfig { gap_open: 0.5, ..config };
+        assert_eq!(
+            posterior_matrix(&seq, &seq, invalid),
+            Err(InvalidPairHmmConfig { parameter: "gap_open" })
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::{
+    heatmap::IdentityMatrix,
+    letter::Letter,
+    msa::Msa,
+    report::WriteReport,
+};
+
+/// Width of the name field of classic PHYLIP files.
+const NAME_WIDTH: usize = 10;
+
+/// Number of letters per line of an interleaved alignment.
+const LETTERS_PER_LINE: usize = 50;
+
+/// Number of letters per space-separated group of an interleaved alignment.
+const LETTERS_PER_GROUP: usize = 10;
+
+/// Writes a name in the fixed-width field of classic PHYLIP files, truncated
+/// or padded with spaces to exactly [`NAME_WIDTH`] characters.
+fn write_name(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    let name: String = name.chars().take(NAME_WIDTH).collect();
+    write!(f, "{name:<NAME_WIDTH$}")
+}
+
+/// Renders the distances of an identity matrix, i.e. one minus the
+/// identities, as a PHYLIP distance matrix, readable by `neighbor` or
+/// `fitch`.
+#[derive(Debug, Clone, Copy)]
+pub struct PhylipDistancePrint<'a> {
+    /// The matrix to be rendered.
+    pub matrix: &'a IdentityMatrix,
+    /// Whether to print only the lower triangle, without the diagonal,
+    /// instead of the whole square matrix.
+    pub lower_triangle: bool,
+}
+
+impl fmt::Display for PhylipDistancePrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.matrix.len();
+        writeln!(f, "{size:>5}")?;
+        for (i, name) in self.matrix.names().iter().enumerate() {
+            write_name(f, name)?;
+            let columns = if self.lower_triangle { i } else { size };
+            for j in 0 .. columns {
+                let identity = self.matrix.get(i, j).unwrap_or(0.0);
+                write!(f, " {:.6}", 1.0 - identity)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for PhylipDistancePrint<'_> {}
+
+/// Renders a multiple alignment in PHYLIP format, readable by `dnapars`,
+/// `dnaml` or RAxML. Gaps are always written as `-`, which is what PHYLIP
+/// programs read as a gap, so there is no [`PrettyStyle`] to configure them.
+///
+/// [`PrettyStyle`]: crate::report::PrettyStyle
+#[derive(Debug, Clone, Copy)]
+pub struct PhylipAlignmentPrint<'a> {
+    /// The alignment to be rendered.
+    pub msa: &'a Msa,
+    /// Whether to print blocks of [`LETTERS_PER_LINE`] columns of every row,
+    /// instead of each row whole on its own line.
+    pub interleaved: bool,
+}
+
+impl PhylipAlignmentPrint<'_> {
+    /// Writes columns of a row, in groups of [`LETTERS_PER_GROUP`] letters.
+    fn write_block(f: &mut fmt::Formatter<'_>, row: &[Letter]) -> fmt::Result {
+        for (k, group) in row.chunks(LETTERS_PER_GROUP).enumerate() {
+            if k > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", group.iter().collect::<String>())?;
+        }
+        writeln!(f)
+    }
+}
+
+impl fmt::Display for PhylipAlignmentPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>5} {}", self.msa.row_count(), self.msa.width())?;
+        if !self.interleaved {
+            for (name, row) in self.msa.rows() {
+                write_name(f, name)?;
+                writeln!(f, "{}", row.iter().collect::<String>())?;
+            }
+            return Ok(());
+        }
+        for start in (0 .. self.msa.width()).step_by(LETTERS_PER_LINE) {
+            let end = (start + LETTERS_PER_LINE).min(self.msa.width());
+            if start > 0 {
+                writeln!(f)?;
+            }
+            for (name, row) in self.msa.rows() {
+                if start == 0 {
+                    write_name(f, name)?;
+                } else {
+                    write!(f, "{:NAME_WIDTH$}", "")?;
+                }
+                Self::write_block(f, &row[start .. end])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for PhylipAlignmentPrint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::GlobalAlignmentConfig,
+        heatmap::IdentityMatrix,
+        msa::Msa,
+    };
+
+    use super::{PhylipAlignmentPrint, PhylipDistancePrint};
+
+    #[test]
+    fn matrices_and_alignments_are_printed() {
+        let first: Vec<char> = "GATTACA".chars().collect();
+        let second: Vec<char> = "GATTACC".chars().collect();
+        let matrix = IdentityMatrix::compute(
+            &[("first", &first), ("a_very_long_name", &second)],
+            GlobalAlignmentConfig::default(),
+        );
+        let square =
+            PhylipDistancePrint { matrix: &matrix, lower_triangle: false };
+        assert_eq!(
+            square.to_string(),
+            "    2\nfirst      0.000000 0.142857\na_very_lon 0.142857 \
+             0.000000\n"
+        );
+        let lower =
+            PhylipDistancePrint { matrix: &matrix, lower_triangle: true };
+        assert_eq!(
+            lower.to_string(),
+            "    2\nfirst     \na_very_lon 0.142857\n"
+        );
+
+        let mut msa = Msa::new();
+        msa.push_row(
+            "first".to_owned(),
+            "GATTACA-".repeat(8).chars().collect(),
+        )
+        .unwrap();
+        msa.push_row(
+            "second".to_owned(),
+            "GATT-CAA".repeat(8).chars().collect(),
+        )
+        .unwrap();
+        let sequential = PhylipAlignmentPrint { msa: &msa, interleaved: false };
+        let sequential = sequential.to_string();
+        assert!(sequential.starts_with("    2 64\nfirst     GATTACA-GATTACA-"));
+        assert_eq!(sequential.lines().count(), 3);
+        let interleaved = PhylipAlignmentPrint { msa: &msa, interleaved: true };
+        let lines: Vec<String> =
+            interleaved.to_string().lines().map(str::to_owned).collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[1],
+            "first     GATTACA-GA TTACA-GATT ACA-GATTAC A-GATTACA- GATTACA-GA"
+        );
+        assert_eq!(lines[3], "");
+        assert_eq!(lines[4], "          TTACA-GATT ACA-");
+    }
+}
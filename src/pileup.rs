@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::{Letter, GAP},
+    local::LocalAlignmentResult,
+};
+
+/// Observations accumulated over a single reference position.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PileupColumn {
+    /// How many times each letter was seen aligned to this position. Deletions
+    /// are recorded as gaps.
+    pub counts: BTreeMap<Letter, u32>,
+    /// How many alignments inserted letters right after this position.
+    pub insertions: u32,
+}
+
+impl PileupColumn {
+    /// Total number of alignments covering this position, deletions included.
+    pub fn depth(&self) -> u32 {
+        self.counts.values().sum()
+    }
+
+    /// Number of times `letter` was observed at this position.
+    pub fn count(&self, letter: Letter) -> u32 {
+        self.counts.get(&letter).copied().unwrap_or(0)
+    }
+}
+
+/// Per-position summary of many alignments against one reference.
+///
+/// The reference is always the sequence associated with the row display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pileup {
+    columns: Vec<PileupColumn>,
+}
+
+impl Pileup {
+    /// Creates an empty pileup over a reference of the given length.
+    pub fn new(reference_len: usize) -> Self {
+        Self { columns: vec![PileupColumn::default(); reference_len] }
+    }
+
+    /// Length of the reference.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Whether the reference is empty.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Accumulated observations at a reference position, if in bounds.
+    pub fn column(&self, position: usize) -> Option<&PileupColumn> {
+        self.columns.get(position)
+    }
+
+    /// Accumulated observations of all reference positions, in order.
+    pub fn columns(&self) -> &[PileupColumn] {
+        &self.columns
+    }
+
+    /// Depth of every reference position, e.g. for plotting.
+    pub fn coverage(&self) -> Vec<u32> {
+        self.columns.iter().map(PileupColumn::depth).collect()
+    }
+
+    /// Accumulates a global alignment of a read against the reference.
+    pub fn add_global(&mut self, result: &GlobalAlignmentResult) {
+        self.add_aligned(
+            &result.aligned_row_seq,
+            &result.aligned_column_seq,
+            0,
+        );
+    }
+
+    /// Accumulates a local alignment of a read against the reference.
+    pub fn add_local(&mut self, result: &LocalAlignmentResult) {
+        self.add_aligned(
+            &result.aligned_row_seq.data,
+            &result.aligned_column_seq.data,
+            result.aligned_row_seq.start,
+        );
+    }
+
+    /// Accumulates two gapped sequences, where `offset` is the reference
+    /// position of the first aligned reference letter. Positions past the end
+    /// of the reference are ignored.
+    fn add_aligned(
+        &mut self,
+        aligned_ref: &[Letter],
+        aligned_read: &[Letter],
+        offset: usize,
+    ) {
+        let mut position = offset;
+        for (&ref_letter, &read_letter) in aligned_ref.iter().zip(aligned_read)
+        {
+            if ref_letter == GAP {
+                if read_letter != GAP {
+                    if let Some(column) = position
+                        .checked_sub(1)
+                        .and_then(|previous| self.columns.get_mut(previous))
+                    {
+                        column.insertions += 1;
+                    }
+                }
+            } else {
+                if let Some(column) = self.columns.get_mut(position) {
+                    *column.counts.entry(read_letter).or_insert(0) += 1;
+                }
+                position += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::local::{LocalAlignmentResult, LocallyAlignedSeq};
+
+    use super::Pileup;
+
+    #[test]
+    fn coverage_of_local_reads() {
+        let mut pileup = Pileup::new(6);
+        pileup.add_local(&LocalAlignmentResult {
+            aligned_row_seq: LocallyAlignedSeq {
+                start: 1,
+                end: 4,
+                data: vec!['C', 'G', '-', 'T'],
+            },
+            aligned_column_seq: LocallyAlignedSeq {
+                start: 0,
+                end: 4,
+                data: vec!['C', 'G', 'A', 'T'],
+            },
+            score: 2,
+            identity_numer: 3,
+            identity_denom: 3,
+            mapping_quality: 0,
+        });
+        pileup.add_local(&LocalAlignmentResult {
+            aligned_row_seq: LocallyAlignedSeq {
+                start: 2,
+                end: 5,
+                data: vec!['G', 'T', 'A'],
+            },
+            aligned_column_seq: LocallyAlignedSeq {
+                start: 0,
+                end: 2,
+                data: vec!['G', '-', 'C'],
+            },
+            score: 0,
+            identity_numer: 1,
+            identity_denom: 2,
+            mapping_quality: 0,
+        });
+
+        assert_eq!(pileup.coverage(), vec![0, 1, 2, 2, 1, 0]);
+        assert_eq!(pileup.column(2).unwrap().insertions, 1);
+        assert_eq!(pileup.column(3).unwrap().count('-'), 1);
+        assert_eq!(pileup.column(4).unwrap().count('C'), 1);
+    }
+}
@@ -0,0 +1,253 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    letter::{GenericLetter, Letter},
+    score::{Score, ScoringScheme},
+};
+
+/// Residue of a protein sequence: one of the 20 standard amino acids, an
+/// ambiguity code, a stop, or a gap in aligned sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protein {
+    /// Alanine.
+    A,
+    /// Arginine.
+    R,
+    /// Asparagine.
+    N,
+    /// Aspartic acid.
+    D,
+    /// Cysteine.
+    C,
+    /// Glutamine.
+    Q,
+    /// Glutamic acid.
+    E,
+    /// Glycine.
+    G,
+    /// Histidine.
+    H,
+    /// Isoleucine.
+    I,
+    /// Leucine.
+    L,
+    /// Lysine.
+    K,
+    /// Methionine.
+    M,
+    /// Phenylalanine.
+    F,
+    /// Proline.
+    P,
+    /// Serine.
+    S,
+    /// Threonine.
+    T,
+    /// Tryptophan.
+    W,
+    /// Tyrosine.
+    Y,
+    /// Valine.
+    V,
+    /// Asparagine or aspartic acid.
+    B,
+    /// Glutamine or glutamic acid.
+    Z,
+    /// Any amino acid.
+    X,
+    /// Translation stop, `*`.
+    Stop,
+    /// Gap in an aligned sequence, `-`.
+    Gap,
+}
+
+impl Protein {
+    /// Number of residues, including the gap.
+    pub const COUNT: usize = 25;
+
+    /// Every residue, in the order of their indices.
+    pub const ALL: [Self; Self::COUNT] = [
+        Self::A,
+        Self::R,
+        Self::N,
+        Self::D,
+        Self::C,
+        Self::Q,
+        Self::E,
+        Self::G,
+        Self::H,
+        Self::I,
+        Self::L,
+        Self::K,
+        Self::M,
+        Self::F,
+        Self::P,
+        Self::S,
+        Self::T,
+        Self::W,
+        Self::Y,
+        Self::V,
+        Self::B,
+        Self::Z,
+        Self::X,
+        Self::Stop,
+        Self::Gap,
+    ];
+
+    /// Reads a one-letter residue code, in either case.
+    pub fn from_char(ch: char) -> Option<Self> {
+        let ch = ch.to_ascii_uppercase();
+        Self::ALL.into_iter().find(|residue| residue.to_char() == ch)
+    }
+
+    /// Index of the residue in [`Self::ALL`], for table lookups.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl GenericLetter for Protein {
+    const GAP: Self = Self::Gap;
+
+    fn to_char(self) -> char {
+        b"ARNDCQEGHILKMFPSTWYVBZX*-"[self.index()] as char
+    }
+}
+
+impl fmt::Display for Protein {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// Error raised when a protein sequence has an invalid residue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidResidue {
+    /// The offending character.
+    pub residue: char,
+    /// Position (starting from 0) of the residue in the sequence.
+    pub position: usize,
+}
+
+impl fmt::Display for InvalidResidue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid residue {:?} at position {}",
+            self.residue, self.position
+        )
+    }
+}
+
+impl Error for InvalidResidue {}
+
+/// Parses a protein sequence of one-letter codes, reporting the first
+/// invalid residue. Gaps are invalid, as they only belong in aligned
+/// sequences.
+pub fn parse_protein(text: &str) -> Result<Vec<Protein>, InvalidResidue> {
+    text.chars()
+        .enumerate()
+        .map(|(position, residue)| {
+            Protein::from_char(residue)
+                .filter(|&parsed| parsed != Protein::Gap)
+                .ok_or(InvalidResidue { residue, position })
+        })
+        .collect()
+}
+
+/// Substitution matrix over [`Protein`] residues, looked up by indexing a
+/// table directly instead of hashing letter pairs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProteinMatrix {
+    scores: [[Score; Protein::COUNT]; Protein::COUNT],
+    gap_penalty: Score,
+}
+
+impl ProteinMatrix {
+    /// Creates a table scoring every pair with `default_score`.
+    pub fn new(default_score: Score, gap_penalty: Score) -> Self {
+        Self {
+            scores: [[default_score; Protein::COUNT]; Protein::COUNT],
+            gap_penalty,
+        }
+    }
+
+    /// Copies the pair scores and the gap penalty of a scheme over `char`s,
+    /// such as a [`SubstitutionMatrix`](crate::score::SubstitutionMatrix)
+    /// filled with BLOSUM62.
+    pub fn from_scheme<S>(scheme: S) -> Self
+    where
+        S: ScoringScheme<Letter>,
+    {
+        let mut matrix = Self::new(0, scheme.gap_penalty());
+        for a in Protein::ALL {
+            for b in Protein::ALL {
+                matrix.scores[a.index()][b.index()] =
+                    scheme.score(a.to_char(), b.to_char());
+            }
+        }
+        matrix
+    }
+
+    /// Sets the score of a pair of residues, in both orders.
+    pub fn set(&mut self, a: Protein, b: Protein, score: Score) {
+        self.scores[a.index()][b.index()] = score;
+        self.scores[b.index()][a.index()] = score;
+    }
+}
+
+impl ScoringScheme<Protein> for ProteinMatrix {
+    fn score(&self, row_letter: Protein, column_letter: Protein) -> Score {
+        self.scores[row_letter.index()][column_letter.index()]
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::needleman_wunsch,
+        letter::GenericLetter,
+        score::SubstitutionMatrix,
+    };
+
+    use super::{parse_protein, InvalidResidue, Protein, ProteinMatrix};
+
+    #[test]
+    fn proteins_are_parsed_and_aligned_with_indexed_matrices() {
+        for residue in Protein::ALL {
+            assert_eq!(Protein::from_char(residue.to_char()), Some(residue));
+        }
+        assert_eq!(
+            parse_protein("mkw*"),
+            Ok(vec![Protein::M, Protein::K, Protein::W, Protein::Stop])
+        );
+        assert_eq!(
+            parse_protein("MKJW"),
+            Err(InvalidResidue { residue: 'J', position: 2 })
+        );
+        assert_eq!(
+            parse_protein("MK-W"),
+            Err(InvalidResidue { residue: '-', position: 2 })
+        );
+
+        let table = SubstitutionMatrix::new(-1, -4)
+            .with('W', 'W', 11)
+            .with('K', 'K', 5)
+            .with('K', 'R', 2)
+            .with('M', 'M', 5);
+        let matrix = ProteinMatrix::from_scheme(&table);
+        let row_seq = parse_protein("MKWK").unwrap();
+        let column_seq = parse_protein("MRW").unwrap();
+        let result = needleman_wunsch(&row_seq, &column_seq, &matrix);
+        let chars = |seq: &[Protein]| {
+            seq.iter().map(|residue| residue.to_char()).collect::<String>()
+        };
+        assert_eq!(chars(&result.aligned_row_seq), "MKWK");
+        assert_eq!(chars(&result.aligned_column_seq), "MRW-");
+        assert_eq!(result.score, 5 + 2 + 11 - 4);
+    }
+}
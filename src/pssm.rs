@@ -0,0 +1,285 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentResult},
+    letter::{GenericLetter, Letter, GAP},
+    local::{best_smith_waterman, LocalAlignmentResult, LocallyAlignedSeq},
+    score::{Score, ScoringScheme},
+};
+
+/// Error raised when a profile position does not score the whole alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PssmError {
+    /// Number of letters of the alphabet.
+    pub expected: usize,
+    /// Number of scores given for the position.
+    pub found: usize,
+}
+
+impl fmt::Display for PssmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "profile position has {} scores, but alphabet has {} letters",
+            self.found, self.expected
+        )
+    }
+}
+
+impl Error for PssmError {}
+
+/// Position-specific scoring matrix: a profile scoring every letter of an
+/// alphabet separately at each of its positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pssm {
+    alphabet: Vec<Letter>,
+    scores: Vec<Score>,
+    /// Score of aligning a letter outside of the alphabet with any position.
+    pub unknown_score: Score,
+    /// Score added for every gap, in the profile or in the sequence.
+    pub gap_penalty: Score,
+}
+
+impl Pssm {
+    /// Creates a profile with no positions over the given alphabet.
+    pub fn new(
+        alphabet: Vec<Letter>,
+        unknown_score: Score,
+        gap_penalty: Score,
+    ) -> Self {
+        Self { alphabet, scores: Vec::new(), unknown_score, gap_penalty }
+    }
+
+    /// Appends a position, scoring each letter of the alphabet in order.
+    pub fn push_position(&mut self, scores: &[Score]) -> Result<(), PssmError> {
+        if scores.len() != self.alphabet.len() {
+            return Err(PssmError {
+                expected: self.alphabet.len(),
+                found: scores.len(),
+            });
+        }
+        self.scores.extend_from_slice(scores);
+        Ok(())
+    }
+
+    /// Letters scored at each position.
+    pub fn alphabet(&self) -> &[Letter] {
+        &self.alphabet
+    }
+
+    /// Number of positions of the profile.
+    pub fn len(&self) -> usize {
+        self.scores.len().checked_div(self.alphabet.len()).unwrap_or(0)
+    }
+
+    /// Whether the profile has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Score of a letter at a position, or `None` if the position is past
+    /// the end of the profile.
+    pub fn score(&self, position: usize, letter: Letter) -> Option<Score> {
+        if position >= self.len() {
+            return None;
+        }
+        let width = self.alphabet.len();
+        match self.alphabet.iter().position(|&known| known == letter) {
+            Some(index) => Some(self.scores[position * width + index]),
+            None => Some(self.unknown_score),
+        }
+    }
+
+    /// Best scoring letter of every position, used to display the profile in
+    /// alignments. Ties go to the first letter of the alphabet.
+    pub fn consensus(&self) -> Vec<Letter> {
+        self.scores
+            .chunks(self.alphabet.len().max(1))
+            .map(|row| {
+                let (index, _) = row
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .max_by_key(|&(_, score)| score)
+                    .unwrap_or((0, &0));
+                self.alphabet[index]
+            })
+            .collect()
+    }
+}
+
+/// Letters of a profile alignment: profile positions in the row sequence and
+/// plain letters in the column sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProfileLetter {
+    Position(usize),
+    Letter(Letter),
+    Gap,
+}
+
+impl GenericLetter for ProfileLetter {
+    const GAP: Self = Self::Gap;
+
+    fn to_char(self) -> char {
+        match self {
+            Self::Letter(letter) => letter,
+            Self::Position(_) | Self::Gap => GAP,
+        }
+    }
+}
+
+impl ScoringScheme<ProfileLetter> for Pssm {
+    fn score(
+        &self,
+        row_letter: ProfileLetter,
+        column_letter: ProfileLetter,
+    ) -> Score {
+        match (row_letter, column_letter) {
+            // positions always come from the profile itself
+            (
+                ProfileLetter::Position(position),
+                ProfileLetter::Letter(letter),
+            ) => Pssm::score(self, position, letter)
+                .unwrap_or(self.unknown_score),
+            _ => self.gap_penalty,
+        }
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
+/// Profile positions and sequence letters as profile letters.
+fn profile_letters(pssm: &Pssm, seq: &[Letter]) -> [Vec<ProfileLetter>; 2] {
+    [
+        (0 .. pssm.len()).map(ProfileLetter::Position).collect(),
+        seq.iter().copied().map(ProfileLetter::Letter).collect(),
+    ]
+}
+
+/// Converts aligned profile letters back, showing positions as the consensus
+/// letter. Returns the letters and how many columns equal the consensus.
+fn display_aligned(
+    consensus: &[Letter],
+    aligned_row: &[ProfileLetter],
+    aligned_column: &[ProfileLetter],
+) -> (Vec<Letter>, Vec<Letter>, u32) {
+    let row: Vec<Letter> = aligned_row
+        .iter()
+        .map(|&letter| match letter {
+            ProfileLetter::Position(position) => consensus[position],
+            other => other.to_char(),
+        })
+        .collect();
+    let column: Vec<Letter> =
+        aligned_column.iter().map(|&letter| letter.to_char()).collect();
+    let identities = row
+        .iter()
+        .zip(&column)
+        .filter(|(row_letter, column_letter)| {
+            row_letter == column_letter && **row_letter != GAP
+        })
+        .count();
+    (row, column, identities as u32)
+}
+
+/// Globally aligns a sequence against a profile. The profile is displayed as
+/// the row sequence, by its consensus, and the identity counts letters equal
+/// to the consensus.
+pub fn pssm_needleman_wunsch(
+    pssm: &Pssm,
+    seq: &[Letter],
+) -> GlobalAlignmentResult {
+    let [profile, letters] = profile_letters(pssm, seq);
+    let result = needleman_wunsch(&profile, &letters, pssm);
+    let (aligned_row_seq, aligned_column_seq, identity_numer) = display_aligned(
+        &pssm.consensus(),
+        &result.aligned_row_seq,
+        &result.aligned_column_seq,
+    );
+    GlobalAlignmentResult {
+        aligned_row_seq,
+        aligned_column_seq,
+        score: result.score,
+        identity_numer,
+        identity_denom: result.identity_denom,
+    }
+}
+
+/// Computes the best local alignments of a sequence against a profile,
+/// displayed as in [`pssm_needleman_wunsch`].
+pub fn pssm_smith_waterman(
+    pssm: &Pssm,
+    seq: &[Letter],
+) -> Vec<LocalAlignmentResult> {
+    let [profile, letters] = profile_letters(pssm, seq);
+    let consensus = pssm.consensus();
+    best_smith_waterman(&profile, &letters, pssm)
+        .into_iter()
+        .map(|result| {
+            let (row, column, identity_numer) = display_aligned(
+                &consensus,
+                &result.aligned_row_seq.data,
+                &result.aligned_column_seq.data,
+            );
+            LocalAlignmentResult {
+                aligned_row_seq: LocallyAlignedSeq {
+                    start: result.aligned_row_seq.start,
+                    end: result.aligned_row_seq.end,
+                    data: row,
+                },
+                aligned_column_seq: LocallyAlignedSeq {
+                    start: result.aligned_column_seq.start,
+                    end: result.aligned_column_seq.end,
+                    data: column,
+                },
+                score: result.score,
+                identity_numer,
+                identity_denom: result.identity_denom,
+                mapping_quality: result.mapping_quality,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pssm_needleman_wunsch, pssm_smith_waterman, Pssm, PssmError};
+
+    #[test]
+    fn sequences_align_against_profiles() {
+        let mut pssm = Pssm::new(vec!['A', 'C', 'G', 'T'], -3, -4);
+        for scores in
+            [[4, -2, 3, -2], [-2, -2, -2, 5], [-1, 3, -1, 3], [5, -3, -3, -3]]
+        {
+            pssm.push_position(&scores).unwrap();
+        }
+        assert_eq!(
+            pssm.push_position(&[1, 2]),
+            Err(PssmError { expected: 4, found: 2 })
+        );
+        assert_eq!(pssm.len(), 4);
+        assert_eq!(pssm.consensus(), vec!['A', 'T', 'C', 'A']);
+        assert_eq!(pssm.score(3, 'A'), Some(5));
+        assert_eq!(pssm.score(3, 'N'), Some(-3));
+        assert_eq!(pssm.score(4, 'A'), None);
+
+        let seq: Vec<char> = "GTTNA".chars().collect();
+        let result = pssm_needleman_wunsch(&pssm, &seq);
+        assert_eq!(result.aligned_row_seq, vec!['A', 'T', 'C', '-', 'A']);
+        assert_eq!(result.aligned_column_seq, seq);
+        assert_eq!(result.score, 3 + 5 + 3 - 4 + 5);
+        assert_eq!(result.identity_numer, 2);
+
+        let seq: Vec<char> = "CCATTAGG".chars().collect();
+        let results = pssm_smith_waterman(&pssm, &seq);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].score, 4 + 5 + 3 + 5);
+        assert_eq!(results[0].aligned_column_seq.start, 2);
+        assert_eq!(
+            results[0].aligned_column_seq.data,
+            vec!['A', 'T', 'T', 'A']
+        );
+    }
+}
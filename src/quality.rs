@@ -0,0 +1,257 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    global::{needleman_wunsch, GlobalAlignmentResult},
+    letter::{GenericLetter, Letter, GAP},
+    local::{best_smith_waterman, LocalAlignmentResult, LocallyAlignedSeq},
+    score::{Score, ScoringScheme},
+};
+
+/// A letter of a sequencing read together with its Phred quality.
+///
+/// Only the letters take part in equality, so identities are counted as for
+/// plain letters.
+#[derive(Debug, Clone, Copy)]
+pub struct QualifiedLetter {
+    /// The letter itself.
+    pub letter: Letter,
+    /// Phred quality of the letter.
+    pub quality: u8,
+}
+
+impl PartialEq for QualifiedLetter {
+    fn eq(&self, other: &Self) -> bool {
+        self.letter == other.letter
+    }
+}
+
+impl Eq for QualifiedLetter {}
+
+impl GenericLetter for QualifiedLetter {
+    const GAP: Self = Self { letter: GAP, quality: 0 };
+
+    fn to_char(self) -> char {
+        self.letter
+    }
+}
+
+/// Probability that a letter with the given Phred quality was called right.
+pub fn base_confidence(quality: u8) -> f64 {
+    1.0 - 10f64.powf(-f64::from(quality) / 10.0)
+}
+
+/// Scoring scheme wrapper scaling the score of every letter pair by the
+/// confidence of both letters, so that doubtful letters contribute little to
+/// matches and mismatches alike. Gaps are scored by the wrapped scheme.
+///
+/// Scaled scores are rounded, so the wrapped scheme should use scores large
+/// enough (e.g. tens) for the scaling to be noticeable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QualityScoring<S> {
+    /// Scheme scoring the letters, regardless of their qualities.
+    pub scheme: S,
+}
+
+impl<S> ScoringScheme<QualifiedLetter> for QualityScoring<S>
+where
+    S: ScoringScheme,
+{
+    fn score(
+        &self,
+        row_letter: QualifiedLetter,
+        column_letter: QualifiedLetter,
+    ) -> Score {
+        let score = self.scheme.score(row_letter.letter, column_letter.letter);
+        let confidence = base_confidence(row_letter.quality)
+            * base_confidence(column_letter.quality);
+        (score as f64 * confidence).round() as Score
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.scheme.gap_penalty()
+    }
+}
+
+/// Error returned when a read does not have exactly one quality per letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityLengthMismatch {
+    /// Number of letters of the read.
+    pub letters: usize,
+    /// Number of qualities given for the read.
+    pub qualities: usize,
+}
+
+impl fmt::Display for QualityLengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "read has {} letters but {} qualities",
+            self.letters, self.qualities
+        )
+    }
+}
+
+impl Error for QualityLengthMismatch {}
+
+/// Pairs letters with their qualities, which must be as many as the letters.
+fn qualify(
+    seq: &[Letter],
+    qualities: &[u8],
+) -> Result<Vec<QualifiedLetter>, QualityLengthMismatch> {
+    if seq.len() != qualities.len() {
+        return Err(QualityLengthMismatch {
+            letters: seq.len(),
+            qualities: qualities.len(),
+        });
+    }
+    Ok(seq
+        .iter()
+        .zip(qualities)
+        .map(|(&letter, &quality)| QualifiedLetter { letter, quality })
+        .collect())
+}
+
+/// Drops the qualities of an aligned sequence.
+fn unqualify(aligned_seq: Vec<QualifiedLetter>) -> Vec<Letter> {
+    aligned_seq.into_iter().map(|letter| letter.letter).collect()
+}
+
+/// Globally aligns two reads given with their Phred qualities (one per
+/// letter, not ASCII-encoded), scaling the scores of letter pairs by base
+/// confidence, as [`QualityScoring`] does. Fails if a read does not have one
+/// quality per letter.
+pub fn quality_needleman_wunsch<S>(
+    row_seq: &[Letter],
+    row_qualities: &[u8],
+    column_seq: &[Letter],
+    column_qualities: &[u8],
+    scheme: S,
+) -> Result<GlobalAlignmentResult, QualityLengthMismatch>
+where
+    S: ScoringScheme,
+{
+    let result = needleman_wunsch(
+        &qualify(row_seq, row_qualities)?,
+        &qualify(column_seq, column_qualities)?,
+        QualityScoring { scheme },
+    );
+    Ok(GlobalAlignmentResult {
+        aligned_row_seq: unqualify(result.aligned_row_seq),
+        aligned_column_seq: unqualify(result.aligned_column_seq),
+        score: result.score,
+        identity_numer: result.identity_numer,
+        identity_denom: result.identity_denom,
+    })
+}
+
+/// Computes the best local alignments of two reads given with their Phred
+/// qualities, like [`quality_needleman_wunsch`] does for global alignments.
+pub fn quality_smith_waterman<S>(
+    row_seq: &[Letter],
+    row_qualities: &[u8],
+    column_seq: &[Letter],
+    column_qualities: &[u8],
+    scheme: S,
+) -> Result<Vec<LocalAlignmentResult>, QualityLengthMismatch>
+where
+    S: ScoringScheme,
+{
+    let unqualify_slice =
+        |aligned: LocallyAlignedSeq<QualifiedLetter>| LocallyAlignedSeq {
+            start: aligned.start,
+            end: aligned.end,
+            data: unqualify(aligned.data),
+        };
+    let results = best_smith_waterman(
+        &qualify(row_seq, row_qualities)?,
+        &qualify(column_seq, column_qualities)?,
+        QualityScoring { scheme },
+    );
+    Ok(results
+        .into_iter()
+        .map(|result| LocalAlignmentResult {
+            aligned_row_seq: unqualify_slice(result.aligned_row_seq),
+            aligned_column_seq: unqualify_slice(result.aligned_column_seq),
+            score: result.score,
+            identity_numer: result.identity_numer,
+            identity_denom: result.identity_denom,
+            mapping_quality: result.mapping_quality,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::LocalAlignmentConfig,
+    };
+
+    use super::{
+        quality_needleman_wunsch,
+        quality_smith_waterman,
+        QualityLengthMismatch,
+    };
+
+    #[test]
+    fn doubtful_letters_weigh_less() {
+        let config = GlobalAlignmentConfig {
+            match_penalty: 10,
+            mismatch_penalty: -10,
+            gap_penalty: -20,
+        };
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GATCACA".chars().collect();
+        let confident = [40; 7];
+        let doubtful = [40, 40, 40, 3, 40, 40, 40];
+
+        let plain = needleman_wunsch(&row_seq, &column_seq, config);
+        let trusted = quality_needleman_wunsch(
+            &row_seq,
+            &confident,
+            &column_seq,
+            &confident,
+            config,
+        )
+        .unwrap();
+        assert_eq!(trusted, plain);
+
+        let noisy = quality_needleman_wunsch(
+            &row_seq,
+            &confident,
+            &column_seq,
+            &doubtful,
+            config,
+        )
+        .unwrap();
+        assert_eq!(noisy.aligned_column_seq, column_seq);
+        assert_eq!(noisy.identity_numer, 6);
+        assert_eq!(noisy.score, 60 - 5);
+
+        let local = quality_smith_waterman(
+            &row_seq,
+            &confident,
+            &column_seq,
+            &doubtful,
+            LocalAlignmentConfig {
+                match_penalty: 10,
+                mismatch_penalty: -10,
+                gap_penalty: -20,
+            },
+        )
+        .unwrap();
+        assert_eq!(local[0].score, 55);
+        assert_eq!(local[0].aligned_row_seq.data, row_seq);
+
+        assert_eq!(
+            quality_needleman_wunsch(
+                &row_seq,
+                &confident[.. 6],
+                &column_seq,
+                &confident,
+                config,
+            ),
+            Err(QualityLengthMismatch { letters: 7, qualities: 6 })
+        );
+    }
+}
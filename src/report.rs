@@ -0,0 +1,745 @@
+use std::{error::Error, fmt, io};
+
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::{Letter, NormalizeLetter, GAP},
+    local::{LocalAlignmentResult, LocallyAlignedSeq},
+    score::Score,
+};
+
+/// JSON documents of alignment results, in a stable schema for web frontends.
+pub mod json;
+
+/// Where the line of match markers goes, relative to the two sequence lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MarkerPosition {
+    /// Before the sequence above.
+    Above,
+    /// Between the two sequences, as BLAST does.
+    Between,
+    /// After the sequence below.
+    Below,
+}
+
+/// How much of the report header is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HeaderVerbosity {
+    /// No header and no block titles, only the aligned sequences.
+    Quiet,
+    /// Only the names of the sequences.
+    Brief,
+    /// Names, ranges, identity, score and block titles.
+    Full,
+}
+
+/// Symbols and layout shared by the report formatters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyStyle<'a> {
+    /// Marker of a column where both letters match.
+    pub match_marker: char,
+    /// Marker of a column where letters differ and none is a gap.
+    pub mismatch_marker: char,
+    /// Marker of a column with a gap.
+    pub gap_marker: char,
+    /// Where the marker line is printed.
+    pub marker_position: MarkerPosition,
+    /// Text written after each block.
+    pub block_separator: &'a str,
+    /// How much of the header is printed.
+    pub header: HeaderVerbosity,
+    /// Character gaps are printed as.
+    pub gap_symbol: Letter,
+}
+
+impl Default for PrettyStyle<'_> {
+    fn default() -> Self {
+        Self {
+            match_marker: '*',
+            mismatch_marker: ' ',
+            gap_marker: ' ',
+            marker_position: MarkerPosition::Below,
+            block_separator: "\n",
+            header: HeaderVerbosity::Full,
+            gap_symbol: GAP,
+        }
+    }
+}
+
+impl PrettyStyle<'_> {
+    /// Marker of an alignment column.
+    pub fn marker(&self, row_letter: Letter, column_letter: Letter) -> char {
+        if row_letter == GAP || column_letter == GAP {
+            self.gap_marker
+        } else if row_letter == column_letter {
+            self.match_marker
+        } else {
+            self.mismatch_marker
+        }
+    }
+
+    /// Character a letter of an aligned sequence is printed as.
+    pub fn display_letter(&self, letter: Letter) -> Letter {
+        if letter == GAP {
+            self.gap_symbol
+        } else {
+            letter
+        }
+    }
+}
+
+/// Report formatters that can be streamed into an [`io::Write`] sink.
+///
+/// Writing goes straight from the formatter into the sink, without building
+/// an intermediate `String`. The formatters issue many small writes, so
+/// unbuffered sinks such as files or sockets should be wrapped in an
+/// [`io::BufWriter`].
+pub trait WriteReport: fmt::Display {
+    /// Writes the whole report into the given sink.
+    fn write_report<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write + ?Sized,
+    {
+        write!(writer, "{self}")
+    }
+}
+
+/// Number of characters needed to render the largest coordinate of a report.
+pub(crate) fn coordinate_width(max_coordinate: usize) -> usize {
+    max_coordinate.to_string().len()
+}
+
+/// Counts the letters of a gapped sequence, i.e. everything but gaps.
+pub(crate) fn count_letters(aligned_seq: &[Letter]) -> usize {
+    aligned_seq.iter().filter(|letter| **letter != GAP).count()
+}
+
+/// Column counts of a pairwise alignment, as reported by BLAST tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct ColumnCounts {
+    /// Number of columns, gaps included.
+    pub(crate) length: usize,
+    /// Columns with two identical letters.
+    pub(crate) identities: usize,
+    /// Columns with two different letters.
+    pub(crate) mismatches: usize,
+    /// Runs of consecutive gaps in either sequence.
+    pub(crate) gap_opens: usize,
+}
+
+impl ColumnCounts {
+    /// Percentage of identical columns.
+    pub(crate) fn percent_identity(&self) -> f64 {
+        if self.length == 0 {
+            0.0
+        } else {
+            100.0 * self.identities as f64 / self.length as f64
+        }
+    }
+}
+
+/// Counts the columns of two aligned sequences of equal length.
+pub(crate) fn count_columns(
+    aligned_row: &[Letter],
+    aligned_col: &[Letter],
+) -> ColumnCounts {
+    let mut counts = ColumnCounts::default();
+    let mut previous = (false, false);
+    for (&row_letter, &col_letter) in aligned_row.iter().zip(aligned_col) {
+        let gaps = (row_letter == GAP, col_letter == GAP);
+        counts.length += 1;
+        if (gaps.0 && !previous.0) || (gaps.1 && !previous.1) {
+            counts.gap_opens += 1;
+        }
+        if !gaps.0 && !gaps.1 {
+            if row_letter == col_letter {
+                counts.identities += 1;
+            } else {
+                counts.mismatches += 1;
+            }
+        }
+        previous = gaps;
+    }
+    counts
+}
+
+/// Writes one hit in the tab-separated format of BLAST `-outfmt 6`, taking the
+/// row sequence as the query: the twelve standard columns `qseqid`, `sseqid`,
+/// `pident`, `length`, `mismatch`, `gapopen`, `qstart`, `qend`, `sstart`,
+/// `send`, `evalue` and `bitscore`. Coordinates are one-based and inclusive.
+pub(crate) fn write_blast_tabular_line(
+    f: &mut fmt::Formatter<'_>,
+    [query_id, subject_id]: [&str; 2],
+    result: &LocalAlignmentResult,
+    evalue: f64,
+    bit_score: f64,
+) -> fmt::Result {
+    let query = &result.aligned_row_seq;
+    let subject = &result.aligned_column_seq;
+    let counts = count_columns(&query.data, &subject.data);
+    writeln!(
+        f,
+        "{}\t{}\t{:.3}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.2e}\t{:.1}",
+        query_id,
+        subject_id,
+        counts.percent_identity(),
+        counts.length,
+        counts.mismatches,
+        counts.gap_opens,
+        query.start + 1,
+        query.end,
+        subject.start + 1,
+        subject.end,
+        evalue,
+        bit_score,
+    )
+}
+
+/// Writes one sequence line of a wrapped alignment block, surrounded by the
+/// one-based coordinates of its first and last letters in the original
+/// sequence (gaps are skipped), like EMBOSS does. `position` is the number of
+/// letters of the original sequence that precede the block. Returns the
+/// position right after the block.
+pub(crate) fn write_ruled_line(
+    f: &mut fmt::Formatter<'_>,
+    block: &[Letter],
+    position: usize,
+    coord_width: usize,
+    style: &PrettyStyle,
+) -> Result<usize, fmt::Error> {
+    let letter_count = count_letters(block);
+    let first = if letter_count > 0 { position + 1 } else { position };
+    let end = position + letter_count;
+    write!(f, "{first:>coord_width$} ")?;
+    for &letter in block {
+        write!(f, "{}", style.display_letter(letter))?;
+    }
+    writeln!(f, " {end}")?;
+    Ok(end)
+}
+
+/// Writes the marker line of a block, one marker per column.
+fn write_marker_line(
+    f: &mut fmt::Formatter<'_>,
+    row_block: &[Letter],
+    column_block: &[Letter],
+    coord_width: usize,
+    style: &PrettyStyle,
+) -> fmt::Result {
+    write!(f, "{:coord_width$} ", "")?;
+    for (row_letter, column_letter) in row_block.iter().zip(column_block) {
+        write!(f, "{}", style.marker(*row_letter, *column_letter))?;
+    }
+    writeln!(f)
+}
+
+/// Writes a pair of aligned sequences wrapped into blocks of at most
+/// `max_width` columns. `row_start` and `column_start` are the number of
+/// letters of each original sequence preceding the alignment.
+pub(crate) fn write_blocks(
+    f: &mut fmt::Formatter<'_>,
+    aligned_row_seq: &[Letter],
+    aligned_column_seq: &[Letter],
+    row_start: usize,
+    column_start: usize,
+    max_width: usize,
+    style: &PrettyStyle,
+) -> fmt::Result {
+    let length = aligned_row_seq.len().min(aligned_column_seq.len());
+    let coord_width = coordinate_width(
+        (row_start + count_letters(aligned_row_seq))
+            .max(column_start + count_letters(aligned_column_seq)),
+    );
+    let mut row_pos = row_start;
+    let mut column_pos = column_start;
+    let mut i = 0;
+    while i < length {
+        let block_start = i;
+        let block_end = length.min(block_start + max_width.max(1));
+        let row_block = &aligned_row_seq[block_start .. block_end];
+        let column_block = &aligned_column_seq[block_start .. block_end];
+        if style.header == HeaderVerbosity::Full {
+            writeln!(f, "# block : {block_start}..{block_end}")?;
+        }
+        if style.marker_position == MarkerPosition::Above {
+            write_marker_line(f, row_block, column_block, coord_width, style)?;
+        }
+        row_pos = write_ruled_line(f, row_block, row_pos, coord_width, style)?;
+        if style.marker_position == MarkerPosition::Between {
+            write_marker_line(f, row_block, column_block, coord_width, style)?;
+        }
+        column_pos =
+            write_ruled_line(f, column_block, column_pos, coord_width, style)?;
+        if style.marker_position == MarkerPosition::Below {
+            write_marker_line(f, row_block, column_block, coord_width, style)?;
+        }
+        write!(f, "{}", style.block_separator)?;
+        i = block_end;
+    }
+    Ok(())
+}
+
+/// Number of columns per block of EMBOSS pair reports.
+const EMBOSS_BLOCK_WIDTH: usize = 50;
+
+/// Width of the name field of EMBOSS pair reports.
+const EMBOSS_NAME_WIDTH: usize = 13;
+
+/// Writes a pair alignment in the `pair` format of EMBOSS `needle` and
+/// `water`: a file header naming `program`, the alignment header with its
+/// statistics, blocks of 50 columns with a markup line, and the footer.
+/// `row_start` and `column_start` are the number of letters of each original
+/// sequence preceding the alignment. Letters are written as they are, since
+/// EMBOSS readers expect gaps as `-` regardless of any
+/// [`PrettyStyle::gap_symbol`].
+pub(crate) fn write_emboss_pair(
+    f: &mut fmt::Formatter<'_>,
+    program: &str,
+    names: [&str; 2],
+    aligned_seqs: [&[Letter]; 2],
+    starts: [usize; 2],
+    score: Score,
+) -> fmt::Result {
+    let rule = "#".repeat(40);
+    writeln!(f, "{rule}")?;
+    writeln!(f, "# Program: {program}")?;
+    writeln!(f, "# Align_format: pair")?;
+    writeln!(f, "# Report_file: stdout")?;
+    writeln!(f, "{rule}")?;
+    writeln!(f)?;
+
+    let [aligned_row, aligned_column] = aligned_seqs;
+    let counts = count_columns(aligned_row, aligned_column);
+    let gaps = counts.length - counts.identities - counts.mismatches;
+    let percent = |count: usize| {
+        if counts.length == 0 {
+            0.0
+        } else {
+            100.0 * count as f64 / counts.length as f64
+        }
+    };
+    let equals = format!("#{}", "=".repeat(39));
+    writeln!(f, "{equals}")?;
+    writeln!(f, "#")?;
+    writeln!(f, "# Aligned_sequences: 2")?;
+    writeln!(f, "# 1: {}", names[0])?;
+    writeln!(f, "# 2: {}", names[1])?;
+    writeln!(f, "#")?;
+    writeln!(f, "# Length: {}", counts.length)?;
+    for (label, count) in [
+        ("Identity:", counts.identities),
+        ("Similarity:", counts.identities),
+        ("Gaps:", gaps),
+    ] {
+        writeln!(
+            f,
+            "# {label:<11}{count:>6}/{} ({:.1}%)",
+            counts.length,
+            percent(count)
+        )?;
+    }
+    writeln!(f, "# Score: {score}.0")?;
+    writeln!(f, "#")?;
+    writeln!(f, "#")?;
+    writeln!(f, "{equals}")?;
+    writeln!(f)?;
+
+    let style = PrettyStyle {
+        match_marker: '|',
+        mismatch_marker: '.',
+        gap_marker: ' ',
+        ..PrettyStyle::default()
+    };
+    let names = names
+        .map(|name| name.chars().take(EMBOSS_NAME_WIDTH).collect::<String>());
+    let mut positions = starts;
+    let length = counts.length;
+    for block_start in (0 .. length).step_by(EMBOSS_BLOCK_WIDTH) {
+        let block_end = length.min(block_start + EMBOSS_BLOCK_WIDTH);
+        let blocks = [
+            &aligned_row[block_start .. block_end],
+            &aligned_column[block_start .. block_end],
+        ];
+        for k in 0 .. 2 {
+            let letter_count = count_letters(blocks[k]);
+            let first = positions[k] + usize::from(letter_count > 0);
+            positions[k] += letter_count;
+            write!(f, "{:<EMBOSS_NAME_WIDTH$}{first:>7} ", names[k])?;
+            write!(f, "{}", blocks[k].iter().collect::<String>())?;
+            writeln!(f, "{:>width$}", positions[k], width = 7)?;
+            if k == 0 {
+                write!(f, "{:width$}", "", width = EMBOSS_NAME_WIDTH + 8)?;
+                for (&row_letter, &column_letter) in
+                    blocks[0].iter().zip(blocks[1])
+                {
+                    write!(f, "{}", style.marker(row_letter, column_letter))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        writeln!(f)?;
+    }
+    writeln!(f)?;
+    let dashes = format!("#{}", "-".repeat(39));
+    writeln!(f, "{dashes}")?;
+    writeln!(f, "{dashes}")
+}
+
+/// Number of columns per block of CLUSTAL W files.
+const CLUSTAL_BLOCK_WIDTH: usize = 60;
+
+/// Spaces between the longest name and the sequences of CLUSTAL W files.
+const CLUSTAL_NAME_PADDING: usize = 6;
+
+/// Writes a pair alignment in CLUSTAL W format: the `CLUSTAL` header, then
+/// blocks of 60 columns, each sequence line starting with its name, followed
+/// by a conservation line marking identical columns with `*`. Gaps are kept
+/// as `-`, the only gap character CLUSTAL readers accept, instead of going
+/// through [`PrettyStyle::display_letter`].
+pub(crate) fn write_clustal_pair(
+    f: &mut fmt::Formatter<'_>,
+    names: [&str; 2],
+    aligned_seqs: [&[Letter]; 2],
+) -> fmt::Result {
+    writeln!(f, "CLUSTAL W multiple sequence alignment")?;
+    writeln!(f)?;
+    let style = PrettyStyle {
+        match_marker: '*',
+        mismatch_marker: ' ',
+        gap_marker: ' ',
+        ..PrettyStyle::default()
+    };
+    let name_width =
+        names.iter().map(|name| name.chars().count()).max().unwrap_or(0)
+            + CLUSTAL_NAME_PADDING;
+    let [aligned_row, aligned_column] = aligned_seqs;
+    let length = aligned_row.len().min(aligned_column.len());
+    for block_start in (0 .. length).step_by(CLUSTAL_BLOCK_WIDTH) {
+        let block_end = length.min(block_start + CLUSTAL_BLOCK_WIDTH);
+        let blocks = [
+            &aligned_row[block_start .. block_end],
+            &aligned_column[block_start .. block_end],
+        ];
+        writeln!(f)?;
+        for (name, block) in names.iter().zip(blocks) {
+            write!(f, "{name:<name_width$}")?;
+            writeln!(f, "{}", block.iter().collect::<String>())?;
+        }
+        write!(f, "{:name_width$}", "")?;
+        for (&row_letter, &column_letter) in blocks[0].iter().zip(blocks[1]) {
+            write!(f, "{}", style.marker(row_letter, column_letter))?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+/// Writes two aligned sequences, gaps included, as two FASTA records. Lines
+/// are wrapped at `line_width` letters if given. Gaps are kept as `-`, which
+/// aligned FASTA readers require, whatever [`PrettyStyle::gap_symbol`] says.
+pub(crate) fn write_fasta_pair(
+    f: &mut fmt::Formatter<'_>,
+    names: [&str; 2],
+    aligned_seqs: [&[Letter]; 2],
+    line_width: Option<usize>,
+) -> fmt::Result {
+    for (name, seq) in names.iter().zip(aligned_seqs) {
+        writeln!(f, ">{name}")?;
+        let width = line_width.unwrap_or(seq.len()).max(1);
+        for line in seq.chunks(width) {
+            writeln!(f, "{}", line.iter().collect::<String>())?;
+        }
+    }
+    Ok(())
+}
+
+/// Error raised when a report cannot be read back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportParseError {
+    /// The last sequence line has no partner line of the other sequence.
+    UnpairedLine {
+        /// Line number (starting from 1) of the sequence line.
+        line: usize,
+    },
+    /// The coordinates of a sequence line do not match its letters or the
+    /// previous block.
+    InvalidCoordinates {
+        /// Line number (starting from 1) of the sequence line.
+        line: usize,
+    },
+    /// The two sequence lines of a block have different lengths.
+    UnequalBlocks {
+        /// Line number (starting from 1) of the second sequence line.
+        line: usize,
+    },
+    /// The score in the header is not an integer.
+    InvalidScore {
+        /// Line number (starting from 1) of the score line.
+        line: usize,
+    },
+}
+
+impl fmt::Display for ReportParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnpairedLine { line } => {
+                write!(f, "sequence line {line} has no pair")
+            },
+            Self::InvalidCoordinates { line } => {
+                write!(f, "invalid coordinates at line {line}")
+            },
+            Self::UnequalBlocks { line } => {
+                write!(f, "block ending at line {line} has unequal lengths")
+            },
+            Self::InvalidScore { line } => {
+                write!(f, "invalid score at line {line}")
+            },
+        }
+    }
+}
+
+impl Error for ReportParseError {}
+
+/// Contents of a report read back by [`parse`]. Parts of the header that
+/// were not printed are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedReport {
+    /// Name of the sequence above, associated with a row display.
+    pub row_seq_name: Option<String>,
+    /// Name of the sequence below, associated with a column display.
+    pub column_seq_name: Option<String>,
+    /// Score of the alignment.
+    pub score: Option<Score>,
+    /// Letters of the row sequence preceding the alignment.
+    pub row_start: usize,
+    /// Letters of the column sequence preceding the alignment.
+    pub column_start: usize,
+    /// Sequence above, with gaps.
+    pub aligned_row_seq: Vec<Letter>,
+    /// Sequence below, with gaps.
+    pub aligned_column_seq: Vec<Letter>,
+}
+
+impl ParsedReport {
+    /// Identity fraction, counted as the tracebacks do.
+    fn identity_fraction(&self) -> (u32, u32) {
+        let counts =
+            count_columns(&self.aligned_row_seq, &self.aligned_column_seq);
+        let denom = (counts.identities + counts.mismatches) as u32;
+        (counts.identities as u32, denom.max(1))
+    }
+
+    /// Converts into a global alignment result. A missing score is zero.
+    pub fn into_global(self) -> GlobalAlignmentResult {
+        let (identity_numer, identity_denom) = self.identity_fraction();
+        GlobalAlignmentResult {
+            score: self.score.unwrap_or(0),
+            identity_numer,
+            identity_denom,
+            aligned_row_seq: self.aligned_row_seq,
+            aligned_column_seq: self.aligned_column_seq,
+        }
+    }
+
+    /// Converts into a local alignment result. A missing score is zero.
+    /// Reports do not carry mapping qualities, so it is zero as well.
+    pub fn into_local(self) -> LocalAlignmentResult {
+        let (identity_numer, identity_denom) = self.identity_fraction();
+        LocalAlignmentResult {
+            aligned_row_seq: LocallyAlignedSeq {
+                start: self.row_start,
+                end: self.row_start + count_letters(&self.aligned_row_seq),
+                data: self.aligned_row_seq,
+            },
+            aligned_column_seq: LocallyAlignedSeq {
+                start: self.column_start,
+                end: self.column_start
+                    + count_letters(&self.aligned_column_seq),
+                data: self.aligned_column_seq,
+            },
+            score: self.score.unwrap_or(0),
+            identity_numer,
+            identity_denom,
+            mapping_quality: 0,
+        }
+    }
+}
+
+/// Splits a sequence line of a block into its first coordinate, letters and
+/// last coordinate.
+fn split_ruled_line(line: &str) -> Option<(usize, &str, usize)> {
+    let mut parts = line.split_whitespace();
+    let first = parts.next()?.parse().ok()?;
+    let letters = parts.next()?;
+    let end = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((first, letters, end))
+}
+
+/// Reads back the text of a global
+/// [`PrettyPrint`](crate::global::PrettyPrint) or a local
+/// [`PrettyPrintOne`](crate::local::PrettyPrintOne), with any style, as long
+/// as markers are not digits and the block separator does not look like a
+/// sequence line.
+///
+/// Sequence lines are told apart by their coordinates, and alternate between
+/// the sequence above and below. Header lines are read when present; other
+/// lines are skipped.
+pub fn parse(text: &str) -> Result<ParsedReport, ReportParseError> {
+    parse_with_gap(text, GAP)
+}
+
+/// Reads back a report like [`parse`], where gaps were printed as
+/// `gap_symbol` (see [`PrettyStyle::gap_symbol`]). Gaps of the parsed report
+/// are always [`GAP`].
+pub fn parse_with_gap(
+    text: &str,
+    gap_symbol: Letter,
+) -> Result<ParsedReport, ReportParseError> {
+    let mut report = ParsedReport::default();
+    let mut positions = [None::<usize>; 2];
+    let mut pending_len = None;
+    let mut last_line = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        if let Some(name) = line.strip_prefix("# sequence above : ") {
+            report.row_seq_name = Some(name.to_owned());
+        } else if let Some(name) = line.strip_prefix("# sequence below : ") {
+            report.column_seq_name = Some(name.to_owned());
+        } else if let Some(score) = line.strip_prefix("# score          : ") {
+            report.score = Some(score.trim().parse().map_err(|_| {
+                ReportParseError::InvalidScore { line: line_number }
+            })?);
+        } else if let Some((first, letters, end)) = split_ruled_line(line) {
+            let letters: Vec<Letter> = letters
+                .chars()
+                .map(|letter| letter.normalize_letter_with_gap(gap_symbol))
+                .collect();
+            let letter_count = count_letters(&letters);
+            let invalid =
+                ReportParseError::InvalidCoordinates { line: line_number };
+            let start = match letter_count {
+                0 => Some(first),
+                _ => first.checked_sub(1),
+            };
+            let Some(start) = start
+                .filter(|start| start.checked_add(letter_count) == Some(end))
+            else {
+                return Err(invalid);
+            };
+            let k = usize::from(pending_len.is_some());
+            match positions[k] {
+                None if k == 0 => report.row_start = start,
+                None => report.column_start = start,
+                Some(position) if position != start => return Err(invalid),
+                Some(_) => (),
+            }
+            positions[k] = Some(end);
+            match pending_len.take() {
+                None => {
+                    pending_len = Some(letters.len());
+                    report.aligned_row_seq.extend(letters);
+                },
+                Some(row_len) => {
+                    if row_len != letters.len() {
+                        return Err(ReportParseError::UnequalBlocks {
+                            line: line_number,
+                        });
+                    }
+                    report.aligned_column_seq.extend(letters);
+                },
+            }
+            last_line = line_number;
+        }
+    }
+    if pending_len.is_some() {
+        return Err(ReportParseError::UnpairedLine { line: last_line });
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{needleman_wunsch, GlobalAlignmentConfig, PrettyPrint},
+        local::{best_smith_waterman, LocalAlignmentConfig, PrettyPrintOne},
+    };
+
+    use super::{
+        parse,
+        parse_with_gap,
+        HeaderVerbosity,
+        MarkerPosition,
+        PrettyStyle,
+        ReportParseError,
+    };
+
+    #[test]
+    fn pretty_prints_are_read_back() {
+        let mut rng = Rng::new(78);
+        let row_seq = random_dna(70, &mut rng);
+        let column_seq = random_dna(64, &mut rng);
+
+        let global = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+        let text = PrettyPrint {
+            row_seq_name: "first",
+            column_seq_name: "second",
+            result: &global,
+            max_width: 25,
+            style: PrettyStyle::default(),
+        }
+        .to_string();
+        let parsed = parse(&text).unwrap();
+        assert_eq!(parsed.row_seq_name.as_deref(), Some("first"));
+        assert_eq!(parsed.column_seq_name.as_deref(), Some("second"));
+        assert_eq!(parsed.into_global(), global);
+
+        let mut local = best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+        )
+        .swap_remove(0);
+        let style = PrettyStyle {
+            marker_position: MarkerPosition::Between,
+            header: HeaderVerbosity::Quiet,
+            gap_symbol: '.',
+            ..PrettyStyle::default()
+        };
+        let text = PrettyPrintOne {
+            row_seq_name: "first",
+            column_seq_name: "second",
+            result: &local,
+            max_width: 7,
+            style,
+        }
+        .to_string();
+        assert!(!text.contains('-'));
+        let parsed = parse_with_gap(&text, '.').unwrap();
+        assert_eq!(parsed.score, None);
+        local.score = 0;
+        local.mapping_quality = 0;
+        assert_eq!(parsed.into_local(), local);
+
+        let truncated: Vec<&str> = text.lines().take(1).collect();
+        assert_eq!(
+            parse_with_gap(&truncated.join("\n"), '.'),
+            Err(ReportParseError::UnpairedLine { line: 1 })
+        );
+        assert_eq!(
+            parse("0 A 1\n0 A 1\n"),
+            Err(ReportParseError::InvalidCoordinates { line: 1 })
+        );
+        let huge = format!("{} AC {}\n1 AC 2\n", usize::MAX, usize::MAX);
+        assert_eq!(
+            parse(&huge),
+            Err(ReportParseError::InvalidCoordinates { line: 1 })
+        );
+    }
+}
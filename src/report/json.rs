@@ -0,0 +1,215 @@
+use std::fmt;
+
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::{Letter, GAP},
+    local::LocalAlignmentResult,
+    report::WriteReport,
+};
+
+/// Identifier of the schema of the emitted documents. It changes whenever a
+/// field is removed or changes meaning; fields may be added without changing
+/// it.
+pub const SCHEMA: &str = "seq_align.report/1";
+
+/// Renders a string as a JSON string literal.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            ch if ch.is_control() => {
+                literal.push_str(&format!("\\u{:04x}", ch as u32))
+            },
+            ch => literal.push(ch),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// An alignment result of any kind, to be rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultRef<'a> {
+    /// A global alignment, spanning both sequences whole.
+    Global(&'a GlobalAlignmentResult),
+    /// A local alignment, spanning slices of both sequences.
+    Local(&'a LocalAlignmentResult),
+}
+
+/// Renders one alignment as a JSON object:
+///
+/// ```text
+/// {
+///   "mode": "global" | "local",
+///   "row": {"name": string, "start": int, "end": int, "aligned": string},
+///   "column": {"name": string, "start": int, "end": int, "aligned": string},
+///   "score": int,
+///   "identity": number,
+///   "identity_numer": int,
+///   "identity_denom": int,
+///   "cigar": string
+/// }
+/// ```
+///
+/// Ranges are zero-based and end-exclusive, over the original sequences.
+/// Aligned sequences contain gaps as `-`. The CIGAR string takes the row
+/// sequence as the reference and tells matches (`=`) and mismatches (`X`)
+/// apart.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonAlignment<'a> {
+    /// Name of the sequence that was associated with a row display.
+    pub row_seq_name: &'a str,
+    /// Name of the sequence that was associated with a column display.
+    pub column_seq_name: &'a str,
+    /// An already finished alignment result.
+    pub result: ResultRef<'a>,
+}
+
+impl JsonAlignment<'_> {
+    /// Writes the object describing one of the aligned sequences.
+    fn write_seq(
+        f: &mut fmt::Formatter<'_>,
+        name: &str,
+        start: usize,
+        aligned: &[Letter],
+    ) -> fmt::Result {
+        let letters = aligned.iter().filter(|letter| **letter != GAP).count();
+        write!(
+            f,
+            "{{\"name\":{},\"start\":{start},\"end\":{},\"aligned\":{}}}",
+            json_string(name),
+            start + letters,
+            json_string(&aligned.iter().collect::<String>()),
+        )
+    }
+}
+
+impl fmt::Display for JsonAlignment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (mode, row, column, starts, score, numer, denom, cigar) = match self
+            .result
+        {
+            ResultRef::Global(result) => (
+                "global",
+                &result.aligned_row_seq[..],
+                &result.aligned_column_seq[..],
+                (0, 0),
+                result.score,
+                result.identity_numer,
+                result.identity_denom,
+                result.extended_cigar(),
+            ),
+            ResultRef::Local(result) => (
+                "local",
+                &result.aligned_row_seq.data[..],
+                &result.aligned_column_seq.data[..],
+                (result.aligned_row_seq.start, result.aligned_column_seq.start),
+                result.score,
+                result.identity_numer,
+                result.identity_denom,
+                result.extended_cigar(),
+            ),
+        };
+        write!(f, "{{\"mode\":\"{mode}\",\"row\":")?;
+        Self::write_seq(f, self.row_seq_name, starts.0, row)?;
+        write!(f, ",\"column\":")?;
+        Self::write_seq(f, self.column_seq_name, starts.1, column)?;
+        write!(
+            f,
+            ",\"score\":{score},\"identity\":{},\"identity_numer\":{numer},\"\
+             identity_denom\":{denom},\"cigar\":{}}}",
+            f64::from(numer) / f64::from(denom),
+            json_string(&cigar.to_string()),
+        )
+    }
+}
+
+impl WriteReport for JsonAlignment<'_> {}
+
+/// Renders many alignments as one JSON document, followed by a new line:
+///
+/// ```text
+/// {"schema": "seq_align.report/1", "alignments": [...]}
+/// ```
+///
+/// where each alignment is an object as described in [`JsonAlignment`].
+#[derive(Debug, Clone, Copy)]
+pub struct JsonPrint<'a> {
+    /// Alignments to be rendered, in order.
+    pub alignments: &'a [JsonAlignment<'a>],
+}
+
+impl fmt::Display for JsonPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{\"schema\":{},\"alignments\":[", json_string(SCHEMA))?;
+        for (k, alignment) in self.alignments.iter().enumerate() {
+            if k > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{alignment}")?;
+        }
+        writeln!(f, "]}}")
+    }
+}
+
+impl WriteReport for JsonPrint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::{best_smith_waterman, LocalAlignmentConfig},
+    };
+
+    use super::{json_string, JsonAlignment, JsonPrint, ResultRef};
+
+    #[test]
+    fn alignments_are_rendered_in_the_documented_schema() {
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GATCCA".chars().collect();
+        let global = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            GlobalAlignmentConfig::default(),
+        );
+        let local = best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+        );
+        let alignments = [
+            JsonAlignment {
+                row_seq_name: "first",
+                column_seq_name: "second \"quoted\"",
+                result: ResultRef::Global(&global),
+            },
+            JsonAlignment {
+                row_seq_name: "first",
+                column_seq_name: "second",
+                result: ResultRef::Local(&local[0]),
+            },
+        ];
+        assert_eq!(
+            alignments[0].to_string(),
+            concat!(
+                r#"{"mode":"global","row":{"name":"first","start":0,"end":7,"#,
+                r#""aligned":"GATTACA"},"column":{"name":"second \"quoted\"","#,
+                r#""start":0,"end":6,"aligned":"GATC-CA"},"score":2,"#,
+                r#""identity":0.8333333333333334,"identity_numer":5,"#,
+                r#""identity_denom":6,"cigar":"3=1X1D2="}"#,
+            )
+        );
+        let document = JsonPrint { alignments: &alignments }.to_string();
+        assert!(document
+            .starts_with("{\"schema\":\"seq_align.report/1\",\"alignments\""));
+        assert!(document.contains("{\"mode\":\"local\""));
+        assert!(document.ends_with("]}\n"));
+        assert_eq!(json_string("a\tb\u{1}"), "\"a\\tb\\u0001\"");
+    }
+}
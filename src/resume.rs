@@ -0,0 +1,440 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    global::{
+        compute_nw_matrix_cell,
+        fill_nw_matrix_base,
+        traceback_nw_best_alignment,
+        GlobalAlignmentConfig,
+        GlobalAlignmentResult,
+    },
+    letter::Letter,
+    matrix::AlignmentMatrix,
+    score::Score,
+};
+
+/// First bytes of every snapshot, identifying the format and its version.
+const MAGIC: &[u8; 8] = b"SQNWFIL1";
+
+/// First bytes of every matrix snapshot, identifying the format and its
+/// version.
+const MATRIX_MAGIC: &[u8; 8] = b"SQNWMAT1";
+
+/// Error while reading a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot could not be read.
+    Io(io::Error),
+    /// The data is not a snapshot of a fill, or is corrupt.
+    Malformed {
+        /// What is wrong with the data.
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read snapshot: {error}"),
+            Self::Malformed { message } => {
+                write!(f, "malformed snapshot: {message}")
+            },
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Malformed { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+fn malformed(message: &'static str) -> SnapshotError {
+    SnapshotError::Malformed { message }
+}
+
+fn write_u64<W: Write>(output: &mut W, value: u64) -> io::Result<()> {
+    output.write_all(&value.to_le_bytes())
+}
+
+fn read_u64<R: Read + ?Sized>(input: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_len<R: Read + ?Sized>(input: &mut R) -> Result<usize, SnapshotError> {
+    usize::try_from(read_u64(input)?).map_err(|_| malformed("length overflow"))
+}
+
+fn write_seq<W: Write>(output: &mut W, seq: &[Letter]) -> io::Result<()> {
+    write_u64(output, seq.len() as u64)?;
+    for &letter in seq {
+        output.write_all(&u32::from(letter).to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_seq<R: Read + ?Sized>(
+    input: &mut R,
+) -> Result<Vec<Letter>, SnapshotError> {
+    let len = read_len(input)?;
+    let mut seq = Vec::new();
+    for _ in 0 .. len {
+        let mut bytes = [0; 4];
+        input.read_exact(&mut bytes)?;
+        let letter = char::from_u32(u32::from_le_bytes(bytes))
+            .ok_or_else(|| malformed("invalid letter"))?;
+        seq.push(letter);
+    }
+    Ok(seq)
+}
+
+/// Writes a file next to `path` and then renames it over `path`, so an
+/// interrupted save never destroys the previous file.
+fn save_atomically<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let mut file = File::create(&temporary)?;
+    write(&mut file)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+/// A Needleman-Wunsch fill computed a few rows at a time, which can be saved
+/// to disk between steps and resumed later, e.g. by another process after
+/// the first one was preempted.
+///
+/// Snapshots hold the sequences, the scheme and every completed row, so they
+/// are as large as the part of the matrix filled so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumableNeedlemanWunsch {
+    row_seq: Vec<Letter>,
+    column_seq: Vec<Letter>,
+    config: GlobalAlignmentConfig,
+    matrix: AlignmentMatrix,
+    completed_rows: usize,
+}
+
+impl ResumableNeedlemanWunsch {
+    /// Starts a fill, with only the first matrix row computed.
+    pub fn new(
+        row_seq: &[Letter],
+        column_seq: &[Letter],
+        config: GlobalAlignmentConfig,
+    ) -> Self {
+        let mut matrix =
+            AlignmentMatrix::zeroed(row_seq.len() + 1, column_seq.len() + 1);
+        fill_nw_matrix_base(row_seq, column_seq, &config, &mut matrix);
+        Self {
+            row_seq: row_seq.to_vec(),
+            column_seq: column_seq.to_vec(),
+            config,
+            matrix,
+            completed_rows: 1,
+        }
+    }
+
+    /// Number of matrix rows computed so far, including the first one.
+    pub fn completed_rows(&self) -> usize {
+        self.completed_rows
+    }
+
+    /// Tests whether the whole matrix is computed.
+    pub fn is_complete(&self) -> bool {
+        self.completed_rows == self.matrix.height()
+    }
+
+    /// Computes up to `rows` more matrix rows. Returns whether the whole
+    /// matrix is computed.
+    pub fn step(&mut self, rows: usize) -> bool {
+        let end =
+            self.completed_rows.saturating_add(rows).min(self.matrix.height());
+        for i in self.completed_rows .. end {
+            for j in 0 .. self.column_seq.len() {
+                compute_nw_matrix_cell(
+                    &self.row_seq,
+                    &self.column_seq,
+                    &self.config,
+                    &mut self.matrix,
+                    i - 1,
+                    j,
+                    None,
+                );
+            }
+        }
+        self.completed_rows = end;
+        self.is_complete()
+    }
+
+    /// Computes the remaining rows and returns the alignment.
+    pub fn finish(mut self) -> GlobalAlignmentResult {
+        self.step(usize::MAX);
+        traceback_nw_best_alignment(
+            &self.row_seq,
+            &self.column_seq,
+            self.config,
+            &self.matrix,
+        )
+    }
+
+    /// Writes a snapshot of the fill. Integers are little-endian.
+    pub fn write_snapshot<W>(&self, output: &mut W) -> io::Result<()>
+    where
+        W: Write + ?Sized,
+    {
+        let mut output = BufWriter::new(output);
+        output.write_all(MAGIC)?;
+        for score in [
+            self.config.match_penalty,
+            self.config.mismatch_penalty,
+            self.config.gap_penalty,
+        ] {
+            output.write_all(&score.to_le_bytes())?;
+        }
+        write_seq(&mut output, &self.row_seq)?;
+        write_seq(&mut output, &self.column_seq)?;
+        write_u64(&mut output, self.completed_rows as u64)?;
+        for i in 0 .. self.completed_rows {
+            for j in 0 .. self.matrix.width() {
+                output.write_all(&self.matrix[[i, j]].to_le_bytes())?;
+            }
+        }
+        output.flush()
+    }
+
+    /// Reads a snapshot written by [`Self::write_snapshot`]. The input should
+    /// be buffered.
+    pub fn read_snapshot<R>(input: &mut R) -> Result<Self, SnapshotError>
+    where
+        R: Read + ?Sized,
+    {
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(malformed("not a fill snapshot"));
+        }
+        let mut read_score = || -> io::Result<Score> {
+            let mut bytes = [0; 8];
+            input.read_exact(&mut bytes)?;
+            Ok(Score::from_le_bytes(bytes))
+        };
+        let config = GlobalAlignmentConfig {
+            match_penalty: read_score()?,
+            mismatch_penalty: read_score()?,
+            gap_penalty: read_score()?,
+        };
+        let row_seq = read_seq(input)?;
+        let column_seq = read_seq(input)?;
+        let mut fill = Self::new(&row_seq, &column_seq, config);
+        let completed_rows = read_len(input)?;
+        if completed_rows == 0 || completed_rows > fill.matrix.height() {
+            return Err(malformed("completed rows out of bounds"));
+        }
+        for i in 0 .. completed_rows {
+            for j in 0 .. fill.matrix.width() {
+                let mut bytes = [0; 8];
+                input.read_exact(&mut bytes)?;
+                fill.matrix[[i, j]] = Score::from_le_bytes(bytes);
+            }
+        }
+        fill.completed_rows = completed_rows;
+        Ok(fill)
+    }
+
+    /// Saves a snapshot to a file. The snapshot is written next to the file
+    /// first and then renamed over it, so an interrupted save never destroys
+    /// the previous snapshot.
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        save_atomically(path.as_ref(), |file| self.write_snapshot(file))
+    }
+
+    /// Loads a snapshot saved by [`Self::save`].
+    pub fn load<P>(path: P) -> Result<Self, SnapshotError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::read_snapshot(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+/// Writes a snapshot of a filled matrix: its width, its height and every
+/// score in row-major order. Integers are little-endian, so a snapshot can be
+/// tracebacked on another machine. A matrix without columns is stored with
+/// height zero.
+///
+/// Only the scores are stored: tracing an alignment back from the loaded
+/// matrix, e.g. with [`traceback_nw_best_alignment`], needs the sequences and
+/// the scoring scheme it was filled with, which the caller must keep.
+pub fn write_matrix_snapshot<W>(
+    matrix: &AlignmentMatrix,
+    output: &mut W,
+) -> io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    let mut output = BufWriter::new(output);
+    output.write_all(MATRIX_MAGIC)?;
+    write_u64(&mut output, matrix.width() as u64)?;
+    // the height of a matrix without columns is unknown
+    let height = if matrix.width() == 0 { 0 } else { matrix.height() };
+    write_u64(&mut output, height as u64)?;
+    for score in matrix.iter() {
+        output.write_all(&score.to_le_bytes())?;
+    }
+    output.flush()
+}
+
+/// Reads a matrix snapshot written by [`write_matrix_snapshot`]. The input
+/// should be buffered.
+pub fn read_matrix_snapshot<R>(
+    input: &mut R,
+) -> Result<AlignmentMatrix, SnapshotError>
+where
+    R: Read + ?Sized,
+{
+    let mut magic = [0; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != MATRIX_MAGIC {
+        return Err(malformed("not a matrix snapshot"));
+    }
+    let width = read_len(input)?;
+    let height = read_len(input)?;
+    if width == 0 {
+        return match height {
+            0 => Ok(AlignmentMatrix::zeroed(0, 0)),
+            _ => Err(malformed("rows without columns")),
+        };
+    }
+    let len = width
+        .checked_mul(height)
+        .ok_or_else(|| malformed("length overflow"))?;
+    let mut buf = Vec::new();
+    for _ in 0 .. len {
+        let mut bytes = [0; 8];
+        input.read_exact(&mut bytes)?;
+        buf.push(Score::from_le_bytes(bytes));
+    }
+    AlignmentMatrix::from_vec(buf, width)
+        .map_err(|_| malformed("invalid matrix dimensions"))
+}
+
+/// Saves a matrix snapshot to a file, written next to it first and then
+/// renamed over it, like [`ResumableNeedlemanWunsch::save`].
+pub fn save_matrix<P>(matrix: &AlignmentMatrix, path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    save_atomically(path.as_ref(), |file| write_matrix_snapshot(matrix, file))
+}
+
+/// Loads a matrix snapshot saved by [`save_matrix`].
+pub fn load_matrix<P>(path: P) -> Result<AlignmentMatrix, SnapshotError>
+where
+    P: AsRef<Path>,
+{
+    read_matrix_snapshot(&mut BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{
+            compute_nw_matrix,
+            needleman_wunsch,
+            traceback_nw_best_alignment,
+            GlobalAlignmentConfig,
+        },
+        matrix::AlignmentMatrix,
+    };
+
+    use super::{
+        read_matrix_snapshot,
+        write_matrix_snapshot,
+        ResumableNeedlemanWunsch,
+        SnapshotError,
+    };
+
+    #[test]
+    fn resumed_fill_matches_uninterrupted_alignment() {
+        let mut rng = Rng::new(48);
+        let row_seq = random_dna(40, &mut rng);
+        let column_seq = random_dna(35, &mut rng);
+        let config = GlobalAlignmentConfig::default();
+
+        let mut fill =
+            ResumableNeedlemanWunsch::new(&row_seq, &column_seq, config);
+        assert!(!fill.step(15));
+        assert_eq!(fill.completed_rows(), 16);
+        let mut snapshot = Vec::new();
+        fill.write_snapshot(&mut snapshot).unwrap();
+
+        let resumed =
+            ResumableNeedlemanWunsch::read_snapshot(&mut &snapshot[..])
+                .unwrap();
+        assert_eq!(resumed, fill);
+        assert_eq!(
+            resumed.finish(),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+        assert!(matches!(
+            ResumableNeedlemanWunsch::read_snapshot(&mut &snapshot[1 ..]),
+            Err(SnapshotError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn matrix_snapshot_round_trips() {
+        let mut rng = Rng::new(1325);
+        let row_seq = random_dna(30, &mut rng);
+        let column_seq = random_dna(25, &mut rng);
+        let config = GlobalAlignmentConfig::default();
+        let matrix = compute_nw_matrix(&row_seq, &column_seq, config);
+
+        let mut snapshot = Vec::new();
+        write_matrix_snapshot(&matrix, &mut snapshot).unwrap();
+        assert_eq!(snapshot.len(), 8 + 16 + 8 * 31 * 26);
+        let loaded = read_matrix_snapshot(&mut &snapshot[..]).unwrap();
+        assert_eq!(loaded, matrix);
+        assert_eq!(
+            traceback_nw_best_alignment(&row_seq, &column_seq, config, &loaded),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+        assert!(matches!(
+            read_matrix_snapshot(&mut &snapshot[.. snapshot.len() - 1]),
+            Err(SnapshotError::Io(_))
+        ));
+        snapshot[8] = 0;
+        assert!(matches!(
+            read_matrix_snapshot(&mut &snapshot[..]),
+            Err(SnapshotError::Malformed { .. })
+        ));
+
+        let empty = AlignmentMatrix::zeroed(0, 0);
+        let mut snapshot = Vec::new();
+        write_matrix_snapshot(&empty, &mut snapshot).unwrap();
+        assert_eq!(read_matrix_snapshot(&mut &snapshot[..]).unwrap(), empty);
+    }
+}
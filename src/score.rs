@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use crate::letter::Letter;
+
 /// Score is an 64-bit signed integer (allows negative values).
 pub type Score = i64;
 
@@ -11,3 +15,151 @@ pub fn score_digit_count(score: Score) -> u32 {
         1
     }
 }
+
+/// Scoring of aligned letter pairs and gaps, accepted by the aligners in
+/// place of a plain match/mismatch configuration.
+pub trait ScoringScheme<L = Letter> {
+    /// Score of aligning two letters, the first from the row sequence.
+    fn score(&self, row_letter: L, column_letter: L) -> Score;
+
+    /// Score added for every gap letter.
+    fn gap_penalty(&self) -> Score;
+}
+
+impl<L, S> ScoringScheme<L> for &S
+where
+    S: ScoringScheme<L> + ?Sized,
+{
+    fn score(&self, row_letter: L, column_letter: L) -> Score {
+        (**self).score(row_letter, column_letter)
+    }
+
+    fn gap_penalty(&self) -> Score {
+        (**self).gap_penalty()
+    }
+}
+
+/// Table of pair scores, such as BLOSUM or PAM for proteins. Pairs missing
+/// from the table get a default score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutionMatrix {
+    scores: HashMap<(Letter, Letter), Score>,
+    default_score: Score,
+    gap_penalty: Score,
+}
+
+impl SubstitutionMatrix {
+    /// Creates an empty table, scoring every pair with `default_score`.
+    pub fn new(default_score: Score, gap_penalty: Score) -> Self {
+        Self { scores: HashMap::new(), default_score, gap_penalty }
+    }
+
+    /// Sets the score of a pair of letters, in both orders.
+    pub fn set(&mut self, a: Letter, b: Letter, score: Score) {
+        self.scores.insert((a, b), score);
+        self.scores.insert((b, a), score);
+    }
+
+    /// Sets the score of a pair and returns the table, for chaining.
+    pub fn with(mut self, a: Letter, b: Letter, score: Score) -> Self {
+        self.set(a, b, score);
+        self
+    }
+}
+
+impl ScoringScheme for SubstitutionMatrix {
+    fn score(&self, row_letter: Letter, column_letter: Letter) -> Score {
+        self.scores
+            .get(&(row_letter, column_letter))
+            .copied()
+            .unwrap_or(self.default_score)
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.gap_penalty
+    }
+}
+
+/// Scoring scheme wrapper comparing letters regardless of ASCII case, so
+/// soft-masked (lowercase) regions do not produce spurious mismatches.
+///
+/// Only scoring is affected: aligned sequences keep their original case, and
+/// identity still counts exactly equal letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CaseInsensitive<S> {
+    /// Scheme scoring the uppercased letters and gaps.
+    pub scheme: S,
+}
+
+impl<S> ScoringScheme for CaseInsensitive<S>
+where
+    S: ScoringScheme,
+{
+    fn score(&self, row_letter: Letter, column_letter: Letter) -> Score {
+        self.scheme.score(
+            row_letter.to_ascii_uppercase(),
+            column_letter.to_ascii_uppercase(),
+        )
+    }
+
+    fn gap_penalty(&self) -> Score {
+        self.scheme.gap_penalty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        global::{needleman_wunsch, GlobalAlignmentConfig},
+        local::best_smith_waterman,
+    };
+
+    use super::{CaseInsensitive, SubstitutionMatrix};
+
+    #[test]
+    fn substitution_matrix_drives_both_aligners() {
+        let row_seq = ['W', 'L', 'K', 'V'];
+        let column_seq = ['W', 'I', 'K', 'V'];
+        let plain = SubstitutionMatrix::new(-1, -2)
+            .with('W', 'W', 1)
+            .with('K', 'K', 1)
+            .with('V', 'V', 1);
+        assert_eq!(
+            needleman_wunsch(&row_seq, &column_seq, &plain),
+            needleman_wunsch(
+                &row_seq,
+                &column_seq,
+                GlobalAlignmentConfig::default()
+            )
+        );
+
+        let similar = plain.clone().with('L', 'I', 2);
+        assert_eq!(needleman_wunsch(&row_seq, &column_seq, &similar).score, 5);
+        let local = best_smith_waterman(&row_seq, &column_seq, &similar);
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].score, 5);
+        assert_eq!(local[0].aligned_row_seq.start, 0);
+    }
+
+    #[test]
+    fn soft_masked_letters_match_ignoring_case() {
+        let row_seq: Vec<char> = "GATtaca".chars().collect();
+        let column_seq: Vec<char> = "GATTACA".chars().collect();
+        let config = GlobalAlignmentConfig::default();
+        assert!(needleman_wunsch(&row_seq, &column_seq, config).score < 7);
+
+        let result = needleman_wunsch(
+            &row_seq,
+            &column_seq,
+            CaseInsensitive { scheme: config },
+        );
+        assert_eq!(result.score, 7);
+        assert_eq!(result.aligned_row_seq, row_seq);
+        let local = best_smith_waterman(
+            &row_seq,
+            &column_seq,
+            CaseInsensitive { scheme: config },
+        );
+        assert_eq!(local[0].score, 7);
+    }
+}
@@ -0,0 +1,412 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fmt,
+};
+
+#[cfg(feature = "instrument")]
+use crate::instrument::Span;
+use crate::{
+    encoding::{encoded_local_score, Alphabet, Code, ScoreTable},
+    letter::Letter,
+    local::{
+        best_smith_waterman,
+        sw_score_only,
+        LocalAlignmentConfig,
+        LocalAlignmentResult,
+    },
+    minimizer::{Prefilter, Sketch},
+    report::{write_blast_tabular_line, WriteReport},
+    score::Score,
+};
+
+/// Karlin-Altschul statistical parameters, used to turn scores into E-values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KarlinAltschul {
+    /// Scale of the scoring system.
+    pub lambda: f64,
+    /// Search space correction.
+    pub k: f64,
+}
+
+impl Default for KarlinAltschul {
+    /// Parameters of +1/-1 scoring over uniformly distributed nucleotides.
+    fn default() -> Self {
+        Self { lambda: 3.0f64.ln(), k: 0.33 }
+    }
+}
+
+impl KarlinAltschul {
+    /// Expected number of hits with at least the given score, by chance, in a
+    /// search space of `query_len` against `database_len` letters.
+    pub fn evalue(
+        &self,
+        score: Score,
+        query_len: usize,
+        database_len: usize,
+    ) -> f64 {
+        self.k
+            * query_len as f64
+            * database_len as f64
+            * (-self.lambda * score as f64).exp()
+    }
+
+    /// Normalized score in bits, comparable across scoring systems.
+    pub fn bit_score(&self, score: Score) -> f64 {
+        (self.lambda * score as f64 - self.k.ln()) / 2.0f64.ln()
+    }
+}
+
+/// Parameters of a query-vs-database search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchConfig {
+    /// Scoring of the local alignments.
+    pub alignment: LocalAlignmentConfig,
+    /// Statistical parameters matching `alignment`.
+    pub statistics: KarlinAltschul,
+    /// Maximum number of hits reported.
+    pub top_k: usize,
+    /// If present, hits with a greater E-value are dropped.
+    pub max_evalue: Option<f64>,
+    /// If present, targets sharing too few minimizers with the query are not
+    /// aligned at all.
+    pub prefilter: Option<Prefilter>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            alignment: LocalAlignmentConfig::default(),
+            statistics: KarlinAltschul::default(),
+            top_k: 10,
+            max_evalue: None,
+            prefilter: None,
+        }
+    }
+}
+
+/// A database record searched against a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target<'a> {
+    /// Identifier of the record.
+    pub id: &'a str,
+    /// Sequence of the record.
+    pub seq: &'a [Letter],
+}
+
+/// A database record that survived the search, with its best alignment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit<'a> {
+    /// Position of the target in the searched database.
+    pub target_index: usize,
+    /// The target record itself.
+    pub target: Target<'a>,
+    /// Best local alignment, with the query displayed as a row.
+    pub alignment: LocalAlignmentResult,
+    /// Expected number of chance hits at least as good as this one.
+    pub evalue: f64,
+}
+
+/// Target kept in the top-K heap after the score-only pass.
+#[derive(Debug, Clone, Copy)]
+struct Candidate<'a> {
+    score: Score,
+    target_index: usize,
+    target: Target<'a>,
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate<'_> {
+    /// Better candidates are greater: higher score, then earlier target.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.target_index.cmp(&self.target_index))
+    }
+}
+
+/// Searches a query against a database of target records.
+///
+/// Targets rejected by the minimizer prefilter, if any, are skipped. Every
+/// other target is first scored without traceback, keeping only the `top_k`
+/// best positive scores. The survivors are then fully aligned and reported from
+/// best to worst, with E-values computed over the whole database length.
+pub fn search<'a, I>(
+    query: &[Letter],
+    targets: I,
+    config: SearchConfig,
+) -> Vec<SearchHit<'a>>
+where
+    I: IntoIterator<Item = Target<'a>>,
+{
+    #[cfg(feature = "instrument")]
+    let mut span = Span::enter("search", 0);
+    let mut heap = BinaryHeap::with_capacity(config.top_k + 1);
+    let mut database_len = 0;
+    let query_sketch = config
+        .prefilter
+        .map(|prefilter| Sketch::new(query, prefilter.minimizer));
+    let mut scorer = QueryScorer::new(query, config.alignment);
+
+    for (target_index, target) in targets.into_iter().enumerate() {
+        #[cfg(feature = "instrument")]
+        span.add_item();
+        database_len += target.seq.len();
+        if config.top_k == 0 {
+            continue;
+        }
+        if let (Some(prefilter), Some(query_sketch)) =
+            (config.prefilter, &query_sketch)
+        {
+            let target_sketch = Sketch::new(target.seq, prefilter.minimizer);
+            if !prefilter.accepts(query_sketch, &target_sketch) {
+                continue;
+            }
+        }
+        #[cfg(feature = "instrument")]
+        span.add_cells((query.len() + 1) * (target.seq.len() + 1));
+        let score = scorer.score(target.seq);
+        // nothing aligns locally, so there is no hit to report
+        if score <= 0 {
+            continue;
+        }
+        heap.push(Reverse(Candidate { score, target_index, target }));
+        if heap.len() > config.top_k {
+            heap.pop();
+        }
+    }
+
+    let mut candidates: Vec<Candidate> =
+        heap.into_iter().map(|Reverse(candidate)| candidate).collect();
+    candidates.sort_by(|a, b| b.cmp(a));
+
+    let mut hits = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        let evalue = config.statistics.evalue(
+            candidate.score,
+            query.len(),
+            database_len,
+        );
+        if config.max_evalue.is_some_and(|max_evalue| evalue > max_evalue) {
+            continue;
+        }
+        let Some(alignment) =
+            best_smith_waterman(query, candidate.target.seq, config.alignment)
+                .into_iter()
+                .next()
+        else {
+            continue;
+        };
+        hits.push(SearchHit {
+            target_index: candidate.target_index,
+            target: candidate.target,
+            alignment,
+            evalue,
+        });
+    }
+    hits
+}
+
+/// Prints search hits in the tab-separated format of BLAST `-outfmt 6`, one
+/// hit per line, without header. Coordinates are one-based and inclusive.
+/**
+ * Example:
+```text
+query    close    100.000    6    0    0    1    6    2    7    4.35e-2    11.1
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct BlastTabularPrint<'a> {
+    /// Identifier of the query, printed in the `qseqid` column.
+    pub query_id: &'a str,
+    /// Hits to be printed, as returned by [`search`].
+    pub hits: &'a [SearchHit<'a>],
+    /// Statistical parameters the search used, for bit scores.
+    pub statistics: KarlinAltschul,
+}
+
+impl fmt::Display for BlastTabularPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for hit in self.hits {
+            write_blast_tabular_line(
+                f,
+                [self.query_id, hit.target.id],
+                &hit.alignment,
+                hit.evalue,
+                self.statistics.bit_score(hit.alignment.score),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for BlastTabularPrint<'_> {}
+
+/// Scores targets against one query with the table-driven kernel over
+/// compacted letters. The query is encoded once for all targets, and the
+/// alphabet grows with the letters of the targets, the table being rebuilt
+/// only when new letters come. Targets whose letters no longer fit in the
+/// alphabet are scored with [`sw_score_only`].
+#[derive(Debug)]
+struct QueryScorer<'a> {
+    query: &'a [Letter],
+    config: LocalAlignmentConfig,
+    encoded: Option<(Alphabet, Vec<Code>, ScoreTable)>,
+}
+
+impl<'a> QueryScorer<'a> {
+    /// Encodes the query, if its letters fit in an alphabet.
+    fn new(query: &'a [Letter], config: LocalAlignmentConfig) -> Self {
+        let encoded = Alphabet::from_seqs(&[query]).and_then(|alphabet| {
+            let query_codes = alphabet.encode(query)?;
+            let table = ScoreTable::new(
+                &alphabet,
+                config.match_penalty,
+                config.mismatch_penalty,
+            );
+            Some((alphabet, query_codes, table))
+        });
+        Self { query, config, encoded }
+    }
+
+    /// Computes the best Smith-Waterman score of the query against a target.
+    fn score(&mut self, target: &[Letter]) -> Score {
+        if let Some((alphabet, query_codes, table)) = &mut self.encoded {
+            let len = alphabet.len();
+            let target_codes: Option<Vec<Code>> =
+                target.iter().map(|&letter| alphabet.insert(letter)).collect();
+            if alphabet.len() != len {
+                *table = ScoreTable::new(
+                    alphabet,
+                    self.config.match_penalty,
+                    self.config.mismatch_penalty,
+                );
+            }
+            if let Some(target_codes) = target_codes {
+                return encoded_local_score(
+                    query_codes,
+                    &target_codes,
+                    table,
+                    self.config.gap_penalty,
+                );
+            }
+        }
+        sw_score_only(self.query, target, self.config).score
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::minimizer::{MinimizerConfig, Prefilter};
+
+    use super::{search, BlastTabularPrint, SearchConfig, Target};
+
+    #[test]
+    fn top_two_targets() {
+        let query = ['A', 'C', 'G', 'T', 'A', 'C'];
+        let close = ['T', 'A', 'C', 'G', 'T', 'A', 'C', 'T'];
+        let far = ['G', 'G', 'G', 'G'];
+        let partial = ['C', 'G', 'T', 'T'];
+        let targets = [
+            Target { id: "far", seq: &far[..] },
+            Target { id: "close", seq: &close[..] },
+            Target { id: "partial", seq: &partial[..] },
+        ];
+        let config = SearchConfig { top_k: 2, ..SearchConfig::default() };
+
+        let hits = search(&query[..], targets, config);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].target.id, "close");
+        assert_eq!(hits[0].alignment.score, 6);
+        assert_eq!(hits[1].target.id, "partial");
+        assert_eq!(hits[1].alignment.score, 3);
+        assert!(hits[0].evalue < hits[1].evalue);
+
+        let table = BlastTabularPrint {
+            query_id: "query",
+            hits: &hits,
+            statistics: config.statistics,
+        }
+        .to_string();
+        let mut lines = table.lines();
+        assert_eq!(
+            lines.next(),
+            Some("query\tclose\t100.000\t6\t0\t0\t1\t6\t2\t7\t4.35e-2\t11.1")
+        );
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[test]
+    fn unrelated_targets_are_not_hits() {
+        let query = ['A', 'C', 'G', 'T'];
+        let unrelated = ['W', 'W', 'W'];
+        let targets = [Target { id: "unrelated", seq: &unrelated[..] }];
+
+        let hits = search(&query[..], targets, SearchConfig::default());
+
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn prefilter_skips_unrelated_targets() {
+        let query = ['A', 'C', 'G', 'T', 'A', 'C'];
+        let close = ['T', 'A', 'C', 'G', 'T', 'A', 'C', 'T'];
+        let partial = ['C', 'G', 'T', 'T'];
+        let targets = [
+            Target { id: "close", seq: &close[..] },
+            Target { id: "partial", seq: &partial[..] },
+        ];
+        let config = SearchConfig {
+            prefilter: Some(Prefilter {
+                minimizer: MinimizerConfig { k: 4, window: 1 },
+                min_shared: 1,
+            }),
+            ..SearchConfig::default()
+        };
+
+        let hits = search(&query[..], targets, config);
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target.id, "close");
+    }
+
+    #[test]
+    fn hits_at_the_first_letters_are_traced_back() {
+        let query = ['A', 'C', 'G', 'T'];
+        let target = ['A', 'C', 'G', 'T', 'T'];
+        // too many distinct letters to be encoded, so scored as plain letters
+        let wide_query: Vec<char> =
+            (0x100 .. 0x300).filter_map(char::from_u32).collect();
+        let wide_target = &wide_query[.. 10];
+        let config = SearchConfig::default();
+
+        let hits =
+            search(&query[..], [Target { id: "t", seq: &target[..] }], config);
+        assert_eq!(hits[0].alignment.score, 4);
+        assert_eq!(hits[0].alignment.aligned_row_seq.start, 0);
+        assert_eq!(hits[0].alignment.aligned_column_seq.start, 0);
+
+        let hits = search(
+            &wide_query[..],
+            [Target { id: "wide", seq: wide_target }],
+            config,
+        );
+        assert_eq!(hits[0].alignment.score, 10);
+        assert_eq!(hits[0].alignment.aligned_row_seq.start, 0);
+        assert_eq!(hits[0].alignment.aligned_column_seq.start, 0);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    align::{
+        fill_free_ends_matrix,
+        traceback_free_ends,
+        Alignment,
+        AlignmentMode,
+        FreeEndGaps,
+    },
+    global::GlobalAlignmentConfig,
+    letter::Letter,
+};
+
+/// End gaps of a semi-global alignment: only the overhangs of the reference
+/// are free.
+const FREE_REFERENCE_ENDS: FreeEndGaps = FreeEndGaps {
+    row_leading: true,
+    row_trailing: true,
+    column_leading: false,
+    column_trailing: false,
+};
+
+/// Aligns a short read end to end within a longer reference (glocal
+/// alignment): gaps before and after the read in the reference are free, but
+/// every letter of the read is aligned. This is the
+/// [`AlignmentMode::Fitting`] mode of [`align`](crate::align::align), and the
+/// result is tagged as such.
+///
+/// The reference is displayed as a row and the read as a column, so the
+/// aligned range of the reference tells where the read was placed.
+pub fn fitting(
+    reference: &[Letter],
+    read: &[Letter],
+    config: GlobalAlignmentConfig,
+) -> Alignment {
+    best_fitting(reference, read, config).swap_remove(0)
+}
+
+/// Computes every best placement of a read within a reference, like
+/// [`fitting`], ordered by position in the reference. Repetitive
+/// references may yield many placements.
+pub fn best_fitting(
+    reference: &[Letter],
+    read: &[Letter],
+    config: GlobalAlignmentConfig,
+) -> Vec<Alignment> {
+    let matrix =
+        fill_free_ends_matrix(reference, read, &config, FREE_REFERENCE_ENDS);
+    let last_column = read.len();
+    let best_score = (0 ..= reference.len())
+        .map(|i| matrix[[i, last_column]])
+        .max()
+        .unwrap_or(0);
+    (0 ..= reference.len())
+        .filter(|&i| matrix[[i, last_column]] == best_score)
+        .map(|i| {
+            traceback_free_ends(
+                reference,
+                read,
+                &config,
+                FREE_REFERENCE_ENDS,
+                AlignmentMode::Fitting,
+                &matrix,
+                (i, last_column),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        align::{align, AlignmentMode},
+        global::GlobalAlignmentConfig,
+    };
+
+    use super::{best_fitting, fitting};
+
+    #[test]
+    fn read_is_placed_at_every_best_position() {
+        let config = GlobalAlignmentConfig::default();
+        let reference: Vec<char> = "CCGATTACACCCGATTACACC".chars().collect();
+        let read: Vec<char> = "GATTACA".chars().collect();
+
+        let placements = best_fitting(&reference, &read, config);
+        assert_eq!(placements.len(), 2);
+        let ranges: Vec<_> = placements
+            .iter()
+            .map(|alignment| {
+                alignment.aligned_row_seq.start .. alignment.aligned_row_seq.end
+            })
+            .collect();
+        assert_eq!(ranges, vec![2 .. 9, 12 .. 19]);
+        assert!(placements.iter().all(|alignment| alignment.score == 7));
+
+        let single = fitting(&reference, &read, config);
+        assert_eq!(single, placements[0]);
+        assert_eq!(single.mode, AlignmentMode::Fitting);
+        assert_eq!(
+            single,
+            align(&reference, &read, AlignmentMode::Fitting, config)
+        );
+    }
+}
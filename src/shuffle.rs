@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::{generate::Rng, letter::Letter};
+
+/// Shuffles a slice in place, uniformly (Fisher-Yates).
+pub fn shuffle_in_place<T>(items: &mut [T], rng: &mut Rng) {
+    for i in (1 .. items.len()).rev() {
+        let j = rng.below(i + 1);
+        items.swap(i, j);
+    }
+}
+
+/// Returns a uniformly shuffled copy of a sequence, preserving its letter
+/// composition only.
+pub fn shuffle(seq: &[Letter], rng: &mut Rng) -> Vec<Letter> {
+    let mut shuffled = seq.to_vec();
+    shuffle_in_place(&mut shuffled, rng);
+    shuffled
+}
+
+/// Returns a shuffled copy of a sequence that preserves the exact count of
+/// every k-let (substring of length `k`), as well as the first and last
+/// `k - 1` letters.
+///
+/// This is the Altschul-Erickson algorithm generalized to any `k`: a random
+/// Eulerian path is drawn over the graph whose vertices are the (k-1)-lets of
+/// the sequence and whose edges are its k-lets. With `k <= 1`, this is a plain
+/// uniform shuffle.
+pub fn shuffle_klet(seq: &[Letter], k: usize, rng: &mut Rng) -> Vec<Letter> {
+    if k <= 1 {
+        return shuffle(seq, rng);
+    }
+    if seq.len() <= k {
+        return seq.to_vec();
+    }
+
+    let prefix_len = k - 1;
+    let mut vertex_ids: HashMap<&[Letter], usize> = HashMap::new();
+    let mut vertex_of_position = Vec::with_capacity(seq.len() - prefix_len + 1);
+    for window in seq.windows(prefix_len) {
+        let next_id = vertex_ids.len();
+        let id = *vertex_ids.entry(window).or_insert(next_id);
+        vertex_of_position.push(id);
+    }
+
+    let vertex_count = vertex_ids.len();
+    let mut edges: Vec<Vec<(usize, Letter)>> = vec![Vec::new(); vertex_count];
+    for position in 0 .. vertex_of_position.len() - 1 {
+        let source = vertex_of_position[position];
+        let target = vertex_of_position[position + 1];
+        edges[source].push((target, seq[position + prefix_len]));
+    }
+
+    let root = vertex_of_position[vertex_of_position.len() - 1];
+    let last_edges = random_arborescence(&edges, root, rng);
+
+    for (vertex, vertex_edges) in edges.iter_mut().enumerate() {
+        match last_edges[vertex] {
+            Some(last_edge) => {
+                let end = vertex_edges.len() - 1;
+                vertex_edges.swap(last_edge, end);
+                shuffle_in_place(&mut vertex_edges[.. end], rng);
+            },
+            None => shuffle_in_place(vertex_edges, rng),
+        }
+    }
+
+    let mut shuffled = Vec::with_capacity(seq.len());
+    shuffled.extend_from_slice(&seq[.. prefix_len]);
+    let mut next_edge = vec![0; vertex_count];
+    let mut current = vertex_of_position[0];
+    while let Some(&(target, letter)) = edges[current].get(next_edge[current]) {
+        next_edge[current] += 1;
+        shuffled.push(letter);
+        current = target;
+    }
+    shuffled
+}
+
+/// Draws a random spanning arborescence oriented towards `root`, using
+/// Wilson's loop-erased random walks. Returns, for every vertex but the root,
+/// the index of its outgoing edge belonging to the arborescence, i.e. the edge
+/// that must be traversed last when leaving that vertex.
+fn random_arborescence(
+    edges: &[Vec<(usize, Letter)>],
+    root: usize,
+    rng: &mut Rng,
+) -> Vec<Option<usize>> {
+    let mut in_tree = vec![false; edges.len()];
+    let mut last_edges = vec![None; edges.len()];
+    in_tree[root] = true;
+
+    for start in 0 .. edges.len() {
+        let mut current = start;
+        while !in_tree[current] {
+            let edge = rng.below(edges[current].len());
+            last_edges[current] = Some(edge);
+            current = edges[current][edge].0;
+        }
+        let mut current = start;
+        while !in_tree[current] {
+            in_tree[current] = true;
+            if let Some(edge) = last_edges[current] {
+                current = edges[current][edge].0;
+            }
+        }
+    }
+
+    last_edges
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::generate::Rng;
+
+    use super::{shuffle, shuffle_klet};
+
+    fn klet_counts(seq: &[char], k: usize) -> HashMap<Vec<char>, usize> {
+        let mut counts = HashMap::new();
+        for window in seq.windows(k) {
+            *counts.entry(window.to_vec()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn dinucleotide_shuffle_preserves_counts() {
+        let seq: Vec<char> = "ACGTTGCAACGTAGGTCCATGAAT".chars().collect();
+        let mut rng = Rng::new(3);
+
+        for _ in 0 .. 10 {
+            let shuffled = shuffle_klet(&seq, 2, &mut rng);
+            assert_eq!(shuffled.len(), seq.len());
+            assert_eq!(shuffled[0], seq[0]);
+            assert_eq!(shuffled.last(), seq.last());
+            assert_eq!(klet_counts(&shuffled, 2), klet_counts(&seq, 2));
+        }
+
+        let mut uniform = shuffle(&seq, &mut rng);
+        let mut sorted = seq.clone();
+        uniform.sort();
+        sorted.sort();
+        assert_eq!(uniform, sorted);
+    }
+}
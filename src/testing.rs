@@ -0,0 +1,201 @@
+use crate::{
+    generate::{random_uniform, Rng},
+    global::{needleman_wunsch, GlobalAlignmentConfig},
+    letter::{Letter, GAP},
+    local::{best_smith_waterman, LocalAlignmentConfig},
+    score::Score,
+};
+
+/// Runs a property over `cases` pseudo-random cases, all derived from `seed`.
+///
+/// Every case gets its own generator, seeded from the case number, so a
+/// failing case can be replayed alone with `Rng::new(seed ^ case)`.
+pub fn run_cases<F>(seed: u64, cases: u64, mut property: F)
+where
+    F: FnMut(&mut Rng),
+{
+    for case in 0 .. cases {
+        let mut rng = Rng::new(seed ^ case);
+        property(&mut rng);
+    }
+}
+
+/// Generates a sequence of length up to `max_len` (inclusive) with letters
+/// drawn from `alphabet`.
+pub fn arbitrary_sequence(
+    rng: &mut Rng,
+    max_len: usize,
+    alphabet: &[Letter],
+) -> Vec<Letter> {
+    let length = rng.below(max_len + 1);
+    random_uniform(length, alphabet, rng)
+}
+
+/// Generates a scoring configuration where matches are rewarded, and
+/// mismatches and gaps are penalized.
+pub fn arbitrary_global_config(rng: &mut Rng) -> GlobalAlignmentConfig {
+    GlobalAlignmentConfig {
+        match_penalty: 1 + rng.below(5) as Score,
+        mismatch_penalty: -(rng.below(6) as Score),
+        gap_penalty: -1 - rng.below(5) as Score,
+    }
+}
+
+/// Generates a local scoring configuration, with the same ranges as
+/// [`arbitrary_global_config`].
+pub fn arbitrary_local_config(rng: &mut Rng) -> LocalAlignmentConfig {
+    let config = arbitrary_global_config(rng);
+    LocalAlignmentConfig {
+        match_penalty: config.match_penalty,
+        mismatch_penalty: config.mismatch_penalty,
+        gap_penalty: config.gap_penalty,
+    }
+}
+
+/// Scores a pair of gapped sequences column by column.
+pub(crate) fn rescore(
+    aligned_row_seq: &[Letter],
+    aligned_column_seq: &[Letter],
+    match_penalty: Score,
+    mismatch_penalty: Score,
+    gap_penalty: Score,
+) -> Score {
+    aligned_row_seq
+        .iter()
+        .zip(aligned_column_seq)
+        .map(|(row_letter, column_letter)| {
+            if *row_letter == GAP || *column_letter == GAP {
+                gap_penalty
+            } else if row_letter == column_letter {
+                match_penalty
+            } else {
+                mismatch_penalty
+            }
+        })
+        .sum()
+}
+
+/// Removes gaps from an aligned sequence.
+pub(crate) fn ungapped(aligned_seq: &[Letter]) -> Vec<Letter> {
+    aligned_seq.iter().copied().filter(|letter| *letter != GAP).collect()
+}
+
+/// Asserts the invariants of a global alignment of the given inputs:
+///
+/// - removing gaps gives back the inputs;
+/// - both aligned sequences have the same length, with no gap-only column;
+/// - the reported score is the column-by-column score of the alignment;
+/// - swapping the arguments does not change the score.
+///
+/// # Panics
+///
+/// Panics describing the first violated invariant.
+pub fn assert_global_invariants(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: GlobalAlignmentConfig,
+) {
+    let result = needleman_wunsch(row_seq, column_seq, config);
+    assert_eq!(ungapped(&result.aligned_row_seq), row_seq, "row sequence");
+    assert_eq!(
+        ungapped(&result.aligned_column_seq),
+        column_seq,
+        "column sequence"
+    );
+    assert_eq!(
+        result.aligned_row_seq.len(),
+        result.aligned_column_seq.len(),
+        "aligned lengths"
+    );
+    assert!(
+        result
+            .aligned_row_seq
+            .iter()
+            .zip(&result.aligned_column_seq)
+            .all(|(row_letter, column_letter)| *row_letter != GAP
+                || *column_letter != GAP),
+        "gap-only column"
+    );
+    assert_eq!(
+        rescore(
+            &result.aligned_row_seq,
+            &result.aligned_column_seq,
+            config.match_penalty,
+            config.mismatch_penalty,
+            config.gap_penalty,
+        ),
+        result.score,
+        "score of the alignment columns"
+    );
+    let swapped = needleman_wunsch(column_seq, row_seq, config);
+    assert_eq!(swapped.score, result.score, "score under argument swap");
+}
+
+/// Asserts the invariants of the best local alignments of the given inputs:
+///
+/// - removing gaps gives back the aligned ranges of the inputs;
+/// - every reported score is positive (if any) and equal to the
+///   column-by-column score of its alignment;
+/// - swapping the arguments does not change the best score.
+///
+/// # Panics
+///
+/// Panics describing the first violated invariant.
+pub fn assert_local_invariants(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: LocalAlignmentConfig,
+) {
+    let results = best_smith_waterman(row_seq, column_seq, config);
+    for result in &results {
+        let row = &result.aligned_row_seq;
+        let column = &result.aligned_column_seq;
+        assert_eq!(ungapped(&row.data), &row_seq[row.start .. row.end]);
+        assert_eq!(
+            ungapped(&column.data),
+            &column_seq[column.start .. column.end]
+        );
+        assert_eq!(
+            rescore(
+                &row.data,
+                &column.data,
+                config.match_penalty,
+                config.mismatch_penalty,
+                config.gap_penalty,
+            ),
+            result.score,
+            "score of the alignment columns"
+        );
+    }
+    let best = results.first().map_or(0, |result| result.score);
+    let swapped = best_smith_waterman(column_seq, row_seq, config);
+    let swapped_best = swapped.first().map_or(0, |result| result.score);
+    assert!(best >= 0, "negative local score");
+    assert_eq!(swapped_best, best, "best score under argument swap");
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generate::DNA;
+
+    use super::{
+        arbitrary_global_config,
+        arbitrary_local_config,
+        arbitrary_sequence,
+        assert_global_invariants,
+        assert_local_invariants,
+        run_cases,
+    };
+
+    #[test]
+    fn alignments_uphold_invariants() {
+        run_cases(0x5eed, 200, |rng| {
+            let row_seq = arbitrary_sequence(rng, 12, DNA);
+            let column_seq = arbitrary_sequence(rng, 12, DNA);
+            let global_config = arbitrary_global_config(rng);
+            let local_config = arbitrary_local_config(rng);
+            assert_global_invariants(&row_seq, &column_seq, global_config);
+            assert_local_invariants(&row_seq, &column_seq, local_config);
+        });
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+
+use crate::{
+    letter::Letter,
+    matrix::{AlignmentMatrix, LabeledPrettyPrint},
+    report::WriteReport,
+    score::Score,
+};
+
+/// Which candidate was chosen when filling a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FillChoice {
+    /// Letters aligned against each other, from the top-left cell.
+    Diagonal,
+    /// Gap in the column sequence, from the top cell.
+    Top,
+    /// Gap in the row sequence, from the left cell.
+    Left,
+    /// Local alignment restarted from zero.
+    Zero,
+}
+
+impl fmt::Display for FillChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Diagonal => "diagonal",
+            Self::Top => "top",
+            Self::Left => "left",
+            Self::Zero => "zero",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Record of a single cell being filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellStep {
+    /// Row of the filled cell.
+    pub i: usize,
+    /// Column of the filled cell.
+    pub j: usize,
+    /// Candidate coming from the top-left cell (match or mismatch).
+    pub diagonal: Score,
+    /// Candidate coming from the top cell (gap).
+    pub top: Score,
+    /// Candidate coming from the left cell (gap).
+    pub left: Score,
+    /// Candidate that was chosen.
+    pub chosen: FillChoice,
+    /// Final score of the cell.
+    pub score: Score,
+}
+
+impl CellStep {
+    /// Creates the record of a cell given its candidates and final score. Ties
+    /// are attributed in the same preference order used by tracebacks: top,
+    /// left, then diagonal.
+    pub fn new(
+        i: usize,
+        j: usize,
+        diagonal: Score,
+        top: Score,
+        left: Score,
+        score: Score,
+    ) -> Self {
+        let chosen = if score == top {
+            FillChoice::Top
+        } else if score == left {
+            FillChoice::Left
+        } else if score == diagonal {
+            FillChoice::Diagonal
+        } else {
+            FillChoice::Zero
+        };
+        Self { i, j, diagonal, top, left, chosen, score }
+    }
+
+    /// Creates the record of a Smith-Waterman cell. A zero score is
+    /// attributed to the zero floor even if a candidate also equals zero, as
+    /// tracebacks stop there.
+    pub fn new_local(
+        i: usize,
+        j: usize,
+        diagonal: Score,
+        top: Score,
+        left: Score,
+        score: Score,
+    ) -> Self {
+        if score == 0 {
+            Self { i, j, diagonal, top, left, chosen: FillChoice::Zero, score }
+        } else {
+            Self::new(i, j, diagonal, top, left, score)
+        }
+    }
+}
+
+/// Recording of a matrix fill, in the order the cells were computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillTrace {
+    /// The matrix before any recorded step, i.e. with only base cells set.
+    pub initial: AlignmentMatrix,
+    /// Every computed cell, in fill order.
+    pub steps: Vec<CellStep>,
+}
+
+impl FillTrace {
+    /// Starts a recording from a matrix whose base cells are already set.
+    pub fn new(initial: AlignmentMatrix) -> Self {
+        Self { initial, steps: Vec::new() }
+    }
+
+    /// Appends a step to the recording.
+    pub fn record(&mut self, step: CellStep) {
+        self.steps.push(step);
+    }
+
+    /// Rebuilds the matrix as it was after the first `step_count` steps.
+    pub fn snapshot(&self, step_count: usize) -> AlignmentMatrix {
+        let mut matrix = self.initial.clone();
+        for step in self.steps.iter().take(step_count) {
+            matrix[[step.i, step.j]] = step.score;
+        }
+        matrix
+    }
+}
+
+/// Replays a recorded fill cell by cell, describing the candidates of each
+/// step and, optionally, printing the partially filled matrix after it.
+/**
+ * Example:
+```text
+step 0: cell [1, 1] (W/W)
+  diagonal = 1, top = -4, left = -4
+  chosen   = diagonal -> 1
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayPrint<'a> {
+    /// The recorded fill.
+    pub trace: &'a FillTrace,
+    /// Sequence displayed as a row in the matrix.
+    pub row_seq: &'a [Letter],
+    /// Sequence displayed as a column in the matrix.
+    pub column_seq: &'a [Letter],
+    /// Whether to print the matrix after each step.
+    pub show_matrix: bool,
+}
+
+impl fmt::Display for ReplayPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut matrix = self.trace.initial.clone();
+        for (k, step) in self.trace.steps.iter().enumerate() {
+            let row_letter =
+                step.i.checked_sub(1).and_then(|i| self.row_seq.get(i));
+            let column_letter =
+                step.j.checked_sub(1).and_then(|j| self.column_seq.get(j));
+            write!(f, "step {k}: cell [{}, {}]", step.i, step.j)?;
+            if let (Some(row_letter), Some(column_letter)) =
+                (row_letter, column_letter)
+            {
+                write!(f, " ({row_letter}/{column_letter})")?;
+            }
+            writeln!(f)?;
+            writeln!(
+                f,
+                "  diagonal = {}, top = {}, left = {}",
+                step.diagonal, step.top, step.left
+            )?;
+            writeln!(f, "  chosen   = {} -> {}", step.chosen, step.score)?;
+            if self.show_matrix {
+                matrix[[step.i, step.j]] = step.score;
+                write!(
+                    f,
+                    "{}",
+                    LabeledPrettyPrint(&matrix, self.row_seq, self.column_seq)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for ReplayPrint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::global::{compute_nw_matrix_traced, GlobalAlignmentConfig};
+
+    use super::ReplayPrint;
+
+    const ROW_SEQ: [char; 1] = ['A'];
+    const COLUMN_SEQ: [char; 2] = ['A', 'C'];
+
+    fn replay_text(show_matrix: bool) -> String {
+        let (_, trace) = compute_nw_matrix_traced(
+            &ROW_SEQ,
+            &COLUMN_SEQ,
+            GlobalAlignmentConfig::default(),
+        );
+        ReplayPrint {
+            trace: &trace,
+            row_seq: &ROW_SEQ,
+            column_seq: &COLUMN_SEQ,
+            show_matrix,
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn replay_without_matrix() {
+        let expected = "step 0: cell [1, 1] (A/A)\n  diagonal = 1, top = -4, \
+                        left = -4\n  chosen   = diagonal -> 1\nstep 1: cell \
+                        [1, 2] (A/C)\n  diagonal = -3, top = -6, left = -1\n  \
+                        chosen   = left -> -1\n";
+        assert_eq!(replay_text(false), expected);
+    }
+
+    #[test]
+    fn replay_with_matrix() {
+        let expected = "step 0: cell [1, 1] (A/A)\n  diagonal = 1, top = -4, \
+                        left = -4\n  chosen   = diagonal -> 1\nmatrix 2x3\n   \
+                        |0 |1 |2 |\n   |--+--+--|\n   |  |A |C \
+                        |\n-+-|==+==+==|\n0| | \
+                        0|-2|-4|\n-+-|--+--+--|\n1|A|-2| 1| \
+                        0|\n-+-|==+==+==|\nstep 1: cell [1, 2] (A/C)\n  \
+                        diagonal = -3, top = -6, left = -1\n  chosen   = left \
+                        -> -1\nmatrix 2x3\n   |0 |1 |2 |\n   |--+--+--|\n   |  \
+                        |A |C |\n-+-|==+==+==|\n0| | \
+                        0|-2|-4|\n-+-|--+--+--|\n1|A|-2| \
+                        1|-1|\n-+-|==+==+==|\n";
+        assert_eq!(replay_text(true), expected);
+    }
+}
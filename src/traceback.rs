@@ -0,0 +1,299 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    letter::{Letter, NormalizeLetter, GAP},
+    matrix::AlignmentMatrix,
+    score::ScoringScheme,
+};
+
+/// Direction taken from a cell during traceback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TracebackPointer {
+    /// The alignment starts at this cell.
+    #[default]
+    Stop,
+    /// Towards i - 1, j - 1: both letters are aligned together.
+    Diagonal,
+    /// Towards i - 1, j: the row letter is aligned with a gap.
+    Top,
+    /// Towards i, j - 1: the column letter is aligned with a gap.
+    Left,
+}
+
+/// 2D Matrix of traceback pointers, recorded while a score matrix is filled,
+/// so that traceback follows them instead of re-deriving every step from
+/// neighbor scores.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TracebackMatrix {
+    buf: Vec<TracebackPointer>,
+    width: usize,
+}
+
+impl TracebackMatrix {
+    /// Creates a matrix with all pointers set to
+    /// [`TracebackPointer::Stop`], of dimensions Height X Width.
+    pub fn new(height: usize, width: usize) -> Self {
+        Self { buf: vec![TracebackPointer::Stop; height * width], width }
+    }
+
+    /// Number of lines of the matrix.
+    pub fn height(&self) -> usize {
+        self.buf.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    /// Number of columns of the matrix.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the pointer of a cell. If the index is out of bounds, `None` is
+    /// returned.
+    pub fn get(&self, i: usize, j: usize) -> Option<TracebackPointer> {
+        if j >= self.width {
+            None
+        } else {
+            self.buf.get(i * self.width + j).copied()
+        }
+    }
+}
+
+impl Index<[usize; 2]> for TracebackMatrix {
+    type Output = TracebackPointer;
+
+    fn index(&self, [i, j]: [usize; 2]) -> &Self::Output {
+        assert!(j < self.width, "invalid indices [{i}, {j}]");
+        &self.buf[i * self.width + j]
+    }
+}
+
+impl IndexMut<[usize; 2]> for TracebackMatrix {
+    fn index_mut(&mut self, [i, j]: [usize; 2]) -> &mut Self::Output {
+        assert!(j < self.width, "invalid indices [{i}, {j}]");
+        &mut self.buf[i * self.width + j]
+    }
+}
+
+/// One column of an alignment, yielded by [`TracebackIter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TracebackOp {
+    /// Direction taken, never [`TracebackPointer::Stop`].
+    pub pointer: TracebackPointer,
+    /// Letter of the row sequence in this column, or a gap.
+    pub row_letter: Letter,
+    /// Letter of the column sequence in this column, or a gap.
+    pub column_letter: Letter,
+    /// Row of the cell the traceback moved to, i.e. the index of the row
+    /// letter, if any.
+    pub i: usize,
+    /// Column of the cell the traceback moved to, i.e. the index of the column
+    /// letter, if any.
+    pub j: usize,
+}
+
+/// Lazy traceback over an already filled score matrix, yielding alignment
+/// columns one at a time, from the end of the alignment to its start, so huge
+/// alignments can be streamed without materializing the aligned sequences.
+///
+/// Ties are broken as in the eager tracebacks, preferring top, then left,
+/// then the diagonal.
+#[derive(Debug, Clone)]
+pub struct TracebackIter<'a, S> {
+    row_seq: &'a [Letter],
+    column_seq: &'a [Letter],
+    scheme: S,
+    matrix: &'a AlignmentMatrix,
+    current_i: usize,
+    current_j: usize,
+    local: bool,
+}
+
+impl<'a, S> TracebackIter<'a, S>
+where
+    S: ScoringScheme,
+{
+    /// Traces back a Needleman-Wunsch matrix from its bottom-right cell down
+    /// to its top-left cell.
+    pub fn global(
+        row_seq: &'a [Letter],
+        column_seq: &'a [Letter],
+        scheme: S,
+        matrix: &'a AlignmentMatrix,
+    ) -> Self {
+        Self {
+            row_seq,
+            column_seq,
+            scheme,
+            matrix,
+            current_i: matrix.height().saturating_sub(1),
+            current_j: matrix.width().saturating_sub(1),
+            local: false,
+        }
+    }
+
+    /// Traces back a Smith-Waterman matrix from the given end cell, usually
+    /// one of [`AlignmentMatrix::argmax_many`], until a zero score is reached.
+    pub fn local(
+        row_seq: &'a [Letter],
+        column_seq: &'a [Letter],
+        scheme: S,
+        matrix: &'a AlignmentMatrix,
+        (end_i, end_j): (usize, usize),
+    ) -> Self {
+        Self {
+            row_seq,
+            column_seq,
+            scheme,
+            matrix,
+            current_i: end_i,
+            current_j: end_j,
+            local: true,
+        }
+    }
+
+    /// Cell where the traceback currently is. Once the iterator is exhausted,
+    /// this is where the alignment starts.
+    pub fn position(&self) -> (usize, usize) {
+        (self.current_i, self.current_j)
+    }
+
+    /// Chooses the direction to be taken from the current cell.
+    fn next_pointer(&self) -> TracebackPointer {
+        let (i, j) = (self.current_i, self.current_j);
+        let current_score = self.matrix[[i, j]];
+        let finished =
+            if self.local { current_score == 0 } else { i == 0 && j == 0 };
+        let penalty = self.scheme.gap_penalty();
+        if finished {
+            TracebackPointer::Stop
+        } else if i > 0 && current_score == self.matrix[[i - 1, j]] + penalty {
+            TracebackPointer::Top
+        } else if j > 0 && current_score == self.matrix[[i, j - 1]] + penalty {
+            TracebackPointer::Left
+        } else {
+            TracebackPointer::Diagonal
+        }
+    }
+}
+
+impl<S> Iterator for TracebackIter<'_, S>
+where
+    S: ScoringScheme,
+{
+    type Item = TracebackOp;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pointer = self.next_pointer();
+        let (row_letter, column_letter) = match pointer {
+            TracebackPointer::Stop => return None,
+            TracebackPointer::Diagonal => {
+                self.current_i -= 1;
+                self.current_j -= 1;
+                (
+                    self.row_seq.get(self.current_i).normalize_letter(),
+                    self.column_seq.get(self.current_j).normalize_letter(),
+                )
+            },
+            TracebackPointer::Top => {
+                self.current_i -= 1;
+                (self.row_seq.get(self.current_i).normalize_letter(), GAP)
+            },
+            TracebackPointer::Left => {
+                self.current_j -= 1;
+                (GAP, self.column_seq.get(self.current_j).normalize_letter())
+            },
+        };
+        Some(TracebackOp {
+            pointer,
+            row_letter,
+            column_letter,
+            i: self.current_i,
+            j: self.current_j,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        global::{
+            compute_nw_matrix,
+            compute_nw_matrix_with_pointers,
+            needleman_wunsch,
+            traceback_nw_pointers,
+            GlobalAlignmentConfig,
+        },
+        local::{
+            best_smith_waterman,
+            compute_sw_matrix,
+            compute_sw_matrix_with_pointers,
+            traceback_sw_pointers,
+            LocalAlignmentConfig,
+        },
+    };
+
+    use super::{TracebackIter, TracebackPointer};
+
+    #[test]
+    fn pointers_reproduce_score_based_traceback() {
+        let mut rng = Rng::new(64);
+        let row_seq = random_dna(45, &mut rng);
+        let column_seq = random_dna(38, &mut rng);
+
+        let config = GlobalAlignmentConfig::default();
+        let (matrix, pointers) =
+            compute_nw_matrix_with_pointers(&row_seq, &column_seq, config);
+        assert_eq!(pointers.height(), 46);
+        assert_eq!(pointers.width(), 39);
+        assert_eq!(pointers.get(0, 0), Some(TracebackPointer::Stop));
+        assert_eq!(pointers.get(0, 1), Some(TracebackPointer::Left));
+        assert_eq!(pointers.get(1, 0), Some(TracebackPointer::Top));
+        assert_eq!(pointers.get(0, 39), None);
+        assert_eq!(
+            traceback_nw_pointers(&row_seq, &column_seq, &matrix, &pointers),
+            needleman_wunsch(&row_seq, &column_seq, config)
+        );
+
+        let config = LocalAlignmentConfig::default();
+        let (matrix, pointers) =
+            compute_sw_matrix_with_pointers(&row_seq, &column_seq, config);
+        assert_eq!(
+            traceback_sw_pointers(&row_seq, &column_seq, &matrix, &pointers),
+            best_smith_waterman(&row_seq, &column_seq, config)
+        );
+    }
+
+    #[test]
+    fn iterator_streams_the_eager_alignments() {
+        let mut rng = Rng::new(65);
+        let row_seq = random_dna(30, &mut rng);
+        let column_seq = random_dna(33, &mut rng);
+
+        let config = GlobalAlignmentConfig::default();
+        let matrix = compute_nw_matrix(&row_seq, &column_seq, config);
+        let expected = needleman_wunsch(&row_seq, &column_seq, config);
+        let mut iter =
+            TracebackIter::global(&row_seq, &column_seq, config, &matrix);
+        let (mut row, mut column): (Vec<_>, Vec<_>) =
+            iter.by_ref().map(|op| (op.row_letter, op.column_letter)).unzip();
+        row.reverse();
+        column.reverse();
+        assert_eq!(row, expected.aligned_row_seq);
+        assert_eq!(column, expected.aligned_column_seq);
+        assert_eq!(iter.position(), (0, 0));
+
+        let config = LocalAlignmentConfig::default();
+        let matrix = compute_sw_matrix(&row_seq, &column_seq, config);
+        let expected = &best_smith_waterman(&row_seq, &column_seq, config)[0];
+        let end = matrix.argmax_many()[0];
+        let mut iter =
+            TracebackIter::local(&row_seq, &column_seq, config, &matrix, end);
+        let mut row: Vec<_> = iter.by_ref().map(|op| op.row_letter).collect();
+        row.reverse();
+        assert_eq!(row, expected.aligned_row_seq.data);
+        assert_eq!(
+            iter.position(),
+            (expected.aligned_row_seq.start, expected.aligned_column_seq.start)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::ops::Range;
+
+use crate::{
+    cluster::Strand,
+    codon::translate_six_frames,
+    letter::Letter,
+    local::{best_smith_waterman, LocalAlignmentResult},
+    score::ScoringScheme,
+};
+
+/// Reading frame of a translated nucleotide sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frame {
+    /// Strand that was translated: the sequence as given or its reverse
+    /// complement.
+    pub strand: Strand,
+    /// Letters of the strand skipped before the first codon, from 0 to 2.
+    pub offset: usize,
+}
+
+impl Frame {
+    /// The six frames, in the order of
+    /// [`translate_six_frames`](crate::codon::translate_six_frames).
+    pub const ALL: [Self; 6] = [
+        Self { strand: Strand::Forward, offset: 0 },
+        Self { strand: Strand::Forward, offset: 1 },
+        Self { strand: Strand::Forward, offset: 2 },
+        Self { strand: Strand::Reverse, offset: 0 },
+        Self { strand: Strand::Reverse, offset: 1 },
+        Self { strand: Strand::Reverse, offset: 2 },
+    ];
+}
+
+/// Local alignment of a translated nucleotide query against a protein.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslatedAlignment {
+    /// Frame of the query the alignment was found in.
+    pub frame: Frame,
+    /// Alignment of the translated frame, displayed as a row, against the
+    /// protein, displayed as a column. Row positions count amino acids of the
+    /// frame.
+    pub result: LocalAlignmentResult,
+}
+
+impl TranslatedAlignment {
+    /// Range of the nucleotide query (as given, on the forward strand) coding
+    /// the aligned amino acids. `query_len` is the length of the query.
+    pub fn query_range(&self, query_len: usize) -> Range<usize> {
+        let row = &self.result.aligned_row_seq;
+        let start = self.frame.offset + 3 * row.start;
+        let end = self.frame.offset + 3 * row.end;
+        match self.frame.strand {
+            Strand::Forward => start .. end,
+            Strand::Reverse => query_len - end .. query_len - start,
+        }
+    }
+}
+
+/// Aligns a nucleotide query against a protein target (like BLASTX): the
+/// query is translated in its six frames, and each frame is locally aligned
+/// against the target with the given protein scoring scheme.
+///
+/// Returns the best alignments among all frames, ordered by frame.
+pub fn translated_smith_waterman<S>(
+    query: &[Letter],
+    target: &[Letter],
+    scheme: S,
+) -> Vec<TranslatedAlignment>
+where
+    S: ScoringScheme,
+{
+    let mut alignments: Vec<TranslatedAlignment> = Frame::ALL
+        .into_iter()
+        .zip(translate_six_frames(query))
+        .flat_map(|(frame, translation)| {
+            best_smith_waterman(&translation, target, &scheme)
+                .into_iter()
+                .map(move |result| TranslatedAlignment { frame, result })
+        })
+        .collect();
+    let best_score = alignments
+        .iter()
+        .map(|alignment| alignment.result.score)
+        .max()
+        .unwrap_or(0);
+    alignments.retain(|alignment| alignment.result.score == best_score);
+    alignments
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cluster::Strand,
+        codon::reverse_complement,
+        local::LocalAlignmentConfig,
+    };
+
+    use super::{translated_smith_waterman, Frame};
+
+    #[test]
+    fn query_is_aligned_in_its_coding_frame() {
+        let target: Vec<char> = "KMAWHE".chars().collect();
+        let coding: Vec<char> = "ATGGCCTGGCAT".chars().collect();
+        let mut query: Vec<char> = "GG".chars().collect();
+        query.extend(reverse_complement(&coding));
+        query.extend("T".chars());
+        let config = LocalAlignmentConfig::default();
+
+        let alignments = translated_smith_waterman(&query, &target, config);
+        assert_eq!(alignments.len(), 1);
+        let alignment = &alignments[0];
+        assert_eq!(
+            alignment.frame,
+            Frame { strand: Strand::Reverse, offset: 1 }
+        );
+        assert_eq!(alignment.result.score, 4);
+        assert_eq!(alignment.result.aligned_row_seq.data, ['M', 'A', 'W', 'H']);
+        assert_eq!(alignment.query_range(query.len()), 2 .. 14);
+    }
+}
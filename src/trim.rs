@@ -0,0 +1,136 @@
+use crate::{
+    align::{align, Alignment, AlignmentMode},
+    global::GlobalAlignmentConfig,
+    letter::Letter,
+};
+
+/// Parameters of read trimming.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrimConfig {
+    /// Scoring of the adapter alignments.
+    pub scheme: GlobalAlignmentConfig,
+    /// Shortest adapter prefix recognized at the end of a read.
+    pub min_overlap: usize,
+    /// Largest fraction of mismatched or gapped columns in an adapter
+    /// alignment.
+    pub max_error_rate: f64,
+    /// Phred quality below which tails are trimmed.
+    pub quality_threshold: u8,
+    /// Reads shorter than this after trimming are discarded.
+    pub min_length: usize,
+}
+
+impl Default for TrimConfig {
+    fn default() -> Self {
+        Self {
+            scheme: GlobalAlignmentConfig::default(),
+            min_overlap: 3,
+            max_error_rate: 0.1,
+            quality_threshold: 20,
+            min_length: 20,
+        }
+    }
+}
+
+/// Number of aligned columns that are not identical pairs.
+fn errors(alignment: &Alignment) -> usize {
+    alignment.aligned_row_seq.data.len() - alignment.identity_numer as usize
+}
+
+/// Finds where an adapter starts in a read, either whole inside the read or
+/// as a prefix hanging off its end. Returns the earliest acceptable start.
+///
+/// The whole adapter is placed with a fitting alignment, and a partial one
+/// with an overlap alignment, each accepted if it has few enough errors for
+/// its length.
+pub fn find_adapter(
+    read: &[Letter],
+    adapter: &[Letter],
+    config: TrimConfig,
+) -> Option<usize> {
+    let acceptable = |alignment: &Alignment, len: usize| {
+        len >= config.min_overlap.min(adapter.len())
+            && len > 0
+            && errors(alignment) as f64 <= config.max_error_rate * len as f64
+    };
+
+    let whole = align(read, adapter, AlignmentMode::Fitting, config.scheme);
+    let whole = Some(whole.aligned_row_seq.start)
+        .filter(|_| acceptable(&whole, adapter.len()));
+
+    let partial = align(read, adapter, AlignmentMode::Overlap, config.scheme);
+    let overlap_len = partial.aligned_column_seq.end;
+    let partial = Some(partial.aligned_row_seq.start)
+        .filter(|_| acceptable(&partial, overlap_len));
+
+    whole.into_iter().chain(partial).min()
+}
+
+/// Length of the read to keep so that its low-quality tail is removed, as in
+/// BWA: the cut maximizes the sum of `threshold - quality` over the removed
+/// tail, so isolated good letters inside a bad tail do not stop the trimming.
+pub fn quality_trim_len(qualities: &[u8], threshold: u8) -> usize {
+    let mut sum = 0;
+    let mut best_sum = 0;
+    let mut keep = qualities.len();
+    for (position, &quality) in qualities.iter().enumerate().rev() {
+        sum += i64::from(threshold) - i64::from(quality);
+        if sum < 0 {
+            break;
+        }
+        if sum > best_sum {
+            best_sum = sum;
+            keep = position;
+        }
+    }
+    keep
+}
+
+/// Trims a read: cuts it at the earliest adapter found and removes its
+/// low-quality tail, if qualities are given as Phred scores. Returns the
+/// length of the prefix to keep, or `None` if it is shorter than
+/// `config.min_length`.
+pub fn trim_read(
+    read: &[Letter],
+    qualities: Option<&[u8]>,
+    adapters: &[&[Letter]],
+    config: TrimConfig,
+) -> Option<usize> {
+    let mut keep = read.len();
+    if let Some(qualities) = qualities {
+        keep = keep.min(quality_trim_len(qualities, config.quality_threshold));
+    }
+    for adapter in adapters {
+        if let Some(start) = find_adapter(&read[.. keep], adapter, config) {
+            keep = start;
+        }
+    }
+    Some(keep).filter(|&keep| keep >= config.min_length)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_adapter, quality_trim_len, trim_read, TrimConfig};
+
+    #[test]
+    fn adapters_and_bad_tails_are_trimmed() {
+        let config = TrimConfig { min_length: 5, ..TrimConfig::default() };
+        let insert: Vec<char> = "GATTACAGATTACA".chars().collect();
+        let adapter: Vec<char> = "AGATCGGAAG".chars().collect();
+
+        let whole = [&insert[..], &adapter, &['T', 'T']].concat();
+        assert_eq!(find_adapter(&whole, &adapter, config), Some(14));
+        let partial = [&insert[..], &adapter[.. 4]].concat();
+        assert_eq!(find_adapter(&partial, &adapter, config), Some(14));
+        assert_eq!(find_adapter(&insert, &adapter, config), None);
+
+        assert_eq!(quality_trim_len(&[30, 30, 30, 10, 25, 5, 2], 20), 3);
+        assert_eq!(quality_trim_len(&[30, 30, 30], 20), 3);
+        let qualities = [30; 18];
+        assert_eq!(
+            trim_read(&partial, Some(&qualities), &[&adapter], config),
+            Some(14)
+        );
+        assert_eq!(trim_read(&partial[.. 4], None, &[&adapter], config), None);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::{Letter, GAP},
+    score::Score,
+};
+
+/// Score system of a global alignment with two-piece affine gaps.
+///
+/// A gap of length `L` scores the best of `gap_open + L * gap_extend` (short
+/// gaps, e.g. sequencing errors) and `long_gap_open + L * long_gap_extend`
+/// (long gaps, e.g. structural indels), as in minimap2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwoPieceGapConfig {
+    /// Added when letters match.
+    pub match_penalty: Score,
+    /// Added when letters do not match, but it is not a gap.
+    pub mismatch_penalty: Score,
+    /// Added once per gap, on the short gap line.
+    pub gap_open: Score,
+    /// Added for every gap letter, on the short gap line.
+    pub gap_extend: Score,
+    /// Added once per gap, on the long gap line.
+    pub long_gap_open: Score,
+    /// Added for every gap letter, on the long gap line.
+    pub long_gap_extend: Score,
+}
+
+impl Default for TwoPieceGapConfig {
+    /// Parameters of minimap2 for noisy long reads.
+    fn default() -> Self {
+        Self {
+            match_penalty: 2,
+            mismatch_penalty: -4,
+            gap_open: -4,
+            gap_extend: -2,
+            long_gap_open: -24,
+            long_gap_extend: -1,
+        }
+    }
+}
+
+impl TwoPieceGapConfig {
+    /// Score of a whole gap of the given length.
+    pub fn gap_score(&self, length: usize) -> Score {
+        if length == 0 {
+            return 0;
+        }
+        let length = length as Score;
+        (self.gap_open + length * self.gap_extend)
+            .max(self.long_gap_open + length * self.long_gap_extend)
+    }
+
+    /// Open and extension scores of both gap lines.
+    fn lines(&self) -> [(Score, Score); 2] {
+        [
+            (self.gap_open, self.gap_extend),
+            (self.long_gap_open, self.long_gap_extend),
+        ]
+    }
+}
+
+/// Score of unreachable states, low enough to never be chosen, yet far from
+/// overflowing when penalties are added.
+const UNREACHABLE: Score = Score::MIN / 4;
+
+/// State of the traceback: which matrix the current cell is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Best score of the cell, whatever the last column.
+    Best,
+    /// Last column is a gap in the column sequence, on the given gap line.
+    Top(usize),
+    /// Last column is a gap in the row sequence, on the given gap line.
+    Left(usize),
+}
+
+/// The five Gotoh-style matrices of a two-piece affine alignment.
+struct Matrices {
+    width: usize,
+    best: Vec<Score>,
+    top: [Vec<Score>; 2],
+    left: [Vec<Score>; 2],
+}
+
+impl Matrices {
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * self.width + j
+    }
+
+    fn get(&self, state: State, i: usize, j: usize) -> Score {
+        let index = self.index(i, j);
+        match state {
+            State::Best => self.best[index],
+            State::Top(line) => self.top[line][index],
+            State::Left(line) => self.left[line][index],
+        }
+    }
+}
+
+/// Fills the matrices of a two-piece affine global alignment.
+fn fill_matrices(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: TwoPieceGapConfig,
+) -> Matrices {
+    let height = row_seq.len() + 1;
+    let width = column_seq.len() + 1;
+    let cells = height * width;
+    let unreachable = || vec![UNREACHABLE; cells];
+    let mut matrices = Matrices {
+        width,
+        best: unreachable(),
+        top: [unreachable(), unreachable()],
+        left: [unreachable(), unreachable()],
+    };
+    let lines = config.lines();
+
+    for i in 0 .. height {
+        for j in 0 .. width {
+            let here = matrices.index(i, j);
+            if i == 0 && j == 0 {
+                matrices.best[here] = 0;
+                continue;
+            }
+            let mut best = UNREACHABLE;
+            for (line, (open, extend)) in lines.into_iter().enumerate() {
+                if i > 0 {
+                    let above = matrices.index(i - 1, j);
+                    let score = (matrices.best[above] + open)
+                        .max(matrices.top[line][above])
+                        + extend;
+                    matrices.top[line][here] = score;
+                    best = best.max(score);
+                }
+                if j > 0 {
+                    let before = matrices.index(i, j - 1);
+                    let score = (matrices.best[before] + open)
+                        .max(matrices.left[line][before])
+                        + extend;
+                    matrices.left[line][here] = score;
+                    best = best.max(score);
+                }
+            }
+            if i > 0 && j > 0 {
+                let pair = if row_seq[i - 1] == column_seq[j - 1] {
+                    config.match_penalty
+                } else {
+                    config.mismatch_penalty
+                };
+                let diagonal = matrices.index(i - 1, j - 1);
+                best = best.max(matrices.best[diagonal] + pair);
+            }
+            matrices.best[here] = best;
+        }
+    }
+    matrices
+}
+
+/// Executes Needleman-Wunsch with two-piece affine gaps: every gap scores the
+/// best of the short and the long gap lines.
+pub fn two_piece_needleman_wunsch(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    config: TwoPieceGapConfig,
+) -> GlobalAlignmentResult {
+    let matrices = fill_matrices(row_seq, column_seq, config);
+    let lines = config.lines();
+
+    let mut current_i = row_seq.len();
+    let mut current_j = column_seq.len();
+    let capacity = current_i + current_j;
+    let mut result = GlobalAlignmentResult {
+        aligned_row_seq: Vec::with_capacity(capacity),
+        aligned_column_seq: Vec::with_capacity(capacity),
+        score: matrices.get(State::Best, current_i, current_j),
+        identity_numer: 0,
+        identity_denom: 0,
+    };
+
+    let mut state = State::Best;
+    while current_i > 0 || current_j > 0 {
+        match state {
+            State::Best => {
+                let score = matrices.get(state, current_i, current_j);
+                let gap_state = [
+                    State::Top(0),
+                    State::Top(1),
+                    State::Left(0),
+                    State::Left(1),
+                ]
+                .into_iter()
+                .find(|&gap_state| {
+                    matrices.get(gap_state, current_i, current_j) == score
+                });
+                match gap_state {
+                    Some(gap_state) => state = gap_state,
+                    None => {
+                        current_i -= 1;
+                        current_j -= 1;
+                        let row_letter = row_seq[current_i];
+                        let column_letter = column_seq[current_j];
+                        result.aligned_row_seq.push(row_letter);
+                        result.aligned_column_seq.push(column_letter);
+                        result.identity_denom += 1;
+                        if row_letter == column_letter {
+                            result.identity_numer += 1;
+                        }
+                    },
+                }
+            },
+            State::Top(line) => {
+                let (open, extend) = lines[line];
+                let score = matrices.get(state, current_i, current_j);
+                current_i -= 1;
+                result.aligned_row_seq.push(row_seq[current_i]);
+                result.aligned_column_seq.push(GAP);
+                let opened = matrices.get(State::Best, current_i, current_j)
+                    + open
+                    + extend;
+                if score == opened {
+                    state = State::Best;
+                }
+            },
+            State::Left(line) => {
+                let (open, extend) = lines[line];
+                let score = matrices.get(state, current_i, current_j);
+                current_j -= 1;
+                result.aligned_row_seq.push(GAP);
+                result.aligned_column_seq.push(column_seq[current_j]);
+                let opened = matrices.get(State::Best, current_i, current_j)
+                    + open
+                    + extend;
+                if score == opened {
+                    state = State::Best;
+                }
+            },
+        }
+    }
+
+    result.aligned_row_seq.reverse();
+    result.aligned_column_seq.reverse();
+    result.identity_denom = result.identity_denom.max(1);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng},
+        letter::GAP,
+    };
+
+    use super::{two_piece_needleman_wunsch, TwoPieceGapConfig};
+
+    #[test]
+    fn long_deletion_takes_the_long_gap_line() {
+        let mut rng = Rng::new(41);
+        let left = random_dna(20, &mut rng);
+        let deleted = random_dna(30, &mut rng);
+        let right = random_dna(20, &mut rng);
+        let row_seq = [&left[..], &deleted, &right].concat();
+        let column_seq = [&left[..], &right].concat();
+        let config = TwoPieceGapConfig::default();
+
+        assert_eq!(config.gap_score(1), -6);
+        assert_eq!(config.gap_score(30), -54);
+        let result = two_piece_needleman_wunsch(&row_seq, &column_seq, config);
+        assert_eq!(result.score, 40 * config.match_penalty - 54);
+        let gap_opens = result
+            .aligned_column_seq
+            .windows(2)
+            .filter(|pair| pair[0] != GAP && pair[1] == GAP)
+            .count();
+        assert_eq!(gap_opens, 1);
+        assert_eq!(result.identity_numer, 40);
+    }
+}
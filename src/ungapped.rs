@@ -0,0 +1,119 @@
+use crate::{
+    letter::GenericLetter,
+    score::{Score, ScoringScheme},
+};
+
+/// Number of positions where two sequences of equal length differ, or `None`
+/// if their lengths differ.
+pub fn hamming_distance<L>(a: &[L], b: &[L]) -> Option<usize>
+where
+    L: GenericLetter,
+{
+    if a.len() != b.len() {
+        return None;
+    }
+    Some(
+        a.iter()
+            .zip(b)
+            .filter(|(a_letter, b_letter)| a_letter != b_letter)
+            .count(),
+    )
+}
+
+/// Best local alignment without gaps, i.e. a stretch of a single diagonal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UngappedHit {
+    /// Position of the first aligned letter in the row sequence.
+    pub row_start: usize,
+    /// Position of the first aligned letter in the column sequence.
+    pub column_start: usize,
+    /// Number of aligned letter pairs.
+    pub len: usize,
+    /// Sum of the scores of the aligned pairs.
+    pub score: Score,
+}
+
+impl UngappedHit {
+    /// Diagonal of the hit, the column position minus the row position.
+    pub fn diagonal(&self) -> isize {
+        self.column_start as isize - self.row_start as isize
+    }
+}
+
+/// Finds the best scoring gapless stretch over all diagonals, scanning each
+/// diagonal once (Kadane's maximum subarray), so it costs as much as filling
+/// one matrix but without any dependency between diagonals. Useful as a cheap
+/// pre-filter before full dynamic programming.
+///
+/// Ties go to the lowest diagonal, then to the earliest stretch. Returns
+/// `None` if no pair scores positively.
+pub fn best_ungapped<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    scheme: S,
+) -> Option<UngappedHit>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let mut best: Option<UngappedHit> = None;
+    let lowest = -(row_seq.len() as isize);
+    let highest = column_seq.len() as isize;
+    for diagonal in lowest + 1 .. highest {
+        let row_offset = (-diagonal).max(0) as usize;
+        let column_offset = diagonal.max(0) as usize;
+        let pairs =
+            row_seq[row_offset ..].iter().zip(&column_seq[column_offset ..]);
+        let mut start = 0;
+        let mut score = 0;
+        for (k, (&row_letter, &column_letter)) in pairs.enumerate() {
+            if score <= 0 {
+                start = k;
+                score = 0;
+            }
+            score += scheme.score(row_letter, column_letter);
+            if score > best.map_or(0, |hit| hit.score) {
+                best = Some(UngappedHit {
+                    row_start: row_offset + start,
+                    column_start: column_offset + start,
+                    len: k + 1 - start,
+                    score,
+                });
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use crate::local::LocalAlignmentConfig;
+
+    use super::{best_ungapped, hamming_distance, UngappedHit};
+
+    #[test]
+    fn gapless_matches_are_found_cheaply() {
+        let a: Vec<char> = "GATTACA".chars().collect();
+        let b: Vec<char> = "GACTATA".chars().collect();
+        assert_eq!(hamming_distance(&a, &b), Some(2));
+        assert_eq!(hamming_distance(&a, &b[1 ..]), None);
+
+        let row_seq: Vec<char> = "TTTTGATTACATTT".chars().collect();
+        let column_seq: Vec<char> = "CCGATTGCACC".chars().collect();
+        let hit = best_ungapped(
+            &row_seq,
+            &column_seq,
+            LocalAlignmentConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            hit,
+            UngappedHit { row_start: 4, column_start: 2, len: 7, score: 5 }
+        );
+        assert_eq!(hit.diagonal(), -2);
+
+        let unrelated: Vec<char> = "GGGG".chars().collect();
+        let config = LocalAlignmentConfig::default();
+        assert_eq!(best_ungapped(&row_seq[.. 4], &unrelated, config), None);
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::{Letter, GAP},
+    local::LocalAlignmentResult,
+    report::WriteReport,
+};
+
+/// A difference between a reference sequence and a sequence aligned against
+/// it, in VCF conventions.
+///
+/// Insertions and deletions carry an anchor base: the reference letter right
+/// before the event, or right after it when the event starts at the very
+/// beginning of the reference.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variant {
+    /// Zero-based position in the reference where `reference` starts.
+    pub position: usize,
+    /// Reference letters replaced by this variant (never empty).
+    pub reference: Vec<Letter>,
+    /// Letters found in place of `reference` (never empty).
+    pub alternative: Vec<Letter>,
+}
+
+/// Extracts variants from a global alignment, taking the sequence associated
+/// with the row display as the reference.
+pub fn extract_global_variants(result: &GlobalAlignmentResult) -> Vec<Variant> {
+    extract_variants(&result.aligned_row_seq, &result.aligned_column_seq, 0)
+}
+
+/// Extracts variants from a local alignment, taking the sequence associated
+/// with the row display as the reference. Positions are relative to the whole
+/// reference, not only to the aligned slice.
+pub fn extract_local_variants(result: &LocalAlignmentResult) -> Vec<Variant> {
+    extract_variants(
+        &result.aligned_row_seq.data,
+        &result.aligned_column_seq.data,
+        result.aligned_row_seq.start,
+    )
+}
+
+/// Walks through the columns of two gapped sequences and collects variants.
+/// `offset` is the reference position of the first aligned reference letter.
+///
+/// A run of differing columns that contains a gap is reported as a single
+/// variant, so records never overlap; other differing columns are reported
+/// one substitution each. Indels cannot be anchored if the reference slice
+/// has no letters at all, so no variant is reported in that case.
+fn extract_variants(
+    aligned_ref: &[Letter],
+    aligned_alt: &[Letter],
+    offset: usize,
+) -> Vec<Variant> {
+    let reference: Vec<Letter> =
+        aligned_ref.iter().copied().filter(|letter| *letter != GAP).collect();
+    let length = aligned_ref.len().min(aligned_alt.len());
+    let mut variants = Vec::new();
+    let mut ref_pos = 0;
+    let mut k = 0;
+
+    while k < length {
+        if aligned_ref[k] == aligned_alt[k] {
+            if aligned_ref[k] != GAP {
+                ref_pos += 1;
+            }
+            k += 1;
+            continue;
+        }
+
+        let run_start = ref_pos;
+        let mut ref_letters = Vec::new();
+        let mut alt_letters = Vec::new();
+        let mut substitutions = Vec::new();
+        let mut gapped = false;
+        while k < length
+            && (aligned_ref[k] != aligned_alt[k] || aligned_ref[k] == GAP)
+        {
+            let (ref_letter, alt_letter) = (aligned_ref[k], aligned_alt[k]);
+            if ref_letter != GAP && alt_letter != GAP {
+                substitutions.push(Variant {
+                    position: offset + ref_pos,
+                    reference: vec![ref_letter],
+                    alternative: vec![alt_letter],
+                });
+            }
+            gapped |= (ref_letter == GAP) != (alt_letter == GAP);
+            if ref_letter != GAP {
+                ref_letters.push(ref_letter);
+                ref_pos += 1;
+            }
+            if alt_letter != GAP {
+                alt_letters.push(alt_letter);
+            }
+            k += 1;
+        }
+
+        if !gapped {
+            variants.extend(substitutions);
+        } else if !ref_letters.is_empty() && !alt_letters.is_empty() {
+            variants.push(Variant {
+                position: offset + run_start,
+                reference: ref_letters,
+                alternative: alt_letters,
+            });
+        } else if run_start > 0 {
+            let anchor = reference[run_start - 1];
+            ref_letters.insert(0, anchor);
+            alt_letters.insert(0, anchor);
+            variants.push(Variant {
+                position: offset + run_start - 1,
+                reference: ref_letters,
+                alternative: alt_letters,
+            });
+        } else if let Some(&anchor) = reference.get(ref_pos) {
+            ref_letters.push(anchor);
+            alt_letters.push(anchor);
+            variants.push(Variant {
+                position: offset + run_start,
+                reference: ref_letters,
+                alternative: alt_letters,
+            });
+        }
+    }
+
+    variants
+}
+
+/// Prints a list of variants as a minimal VCF file. Columns are separated by
+/// tabs.
+/**
+ * Example:
+```text
+##fileformat=VCFv4.2
+#CHROM    POS    ID    REF    ALT    QUAL    FILTER    INFO
+chr1    3    .    A    G    .    .    .
+chr1    5    .    TC    T    .    .    .
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct VcfPrint<'a> {
+    /// Name of the reference sequence, printed in the `CHROM` column.
+    pub chrom: &'a str,
+    /// Variants to be printed, in reference order.
+    pub variants: &'a [Variant],
+}
+
+impl fmt::Display for VcfPrint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "##fileformat=VCFv4.2\n")?;
+        write!(f, "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n")?;
+        for variant in self.variants {
+            write!(f, "{}\t{}\t.\t", self.chrom, variant.position + 1)?;
+            for letter in &variant.reference {
+                write!(f, "{}", letter)?;
+            }
+            write!(f, "\t")?;
+            for letter in &variant.alternative {
+                write!(f, "{}", letter)?;
+            }
+            write!(f, "\t.\t.\t.\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl WriteReport for VcfPrint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::global::GlobalAlignmentResult;
+
+    use super::{extract_global_variants, Variant};
+
+    #[test]
+    fn substitution_and_indels() {
+        let input_result = GlobalAlignmentResult {
+            aligned_row_seq: vec!['G', 'C', 'A', 'T', 'G', '-', 'C', 'G'],
+            aligned_column_seq: vec!['G', '-', 'A', 'T', 'T', 'A', 'C', 'A'],
+            score: 0,
+            identity_numer: 4,
+            identity_denom: 6,
+        };
+
+        let expected_variants = vec![
+            Variant {
+                position: 0,
+                reference: vec!['G', 'C'],
+                alternative: vec!['G'],
+            },
+            Variant {
+                position: 4,
+                reference: vec!['G'],
+                alternative: vec!['T', 'A'],
+            },
+            Variant {
+                position: 6,
+                reference: vec!['G'],
+                alternative: vec!['A'],
+            },
+        ];
+
+        assert_eq!(extract_global_variants(&input_result), expected_variants);
+    }
+
+    #[test]
+    fn leading_deletion_anchors_after() {
+        let input_result = GlobalAlignmentResult {
+            aligned_row_seq: vec!['A', 'C', 'G'],
+            aligned_column_seq: vec!['-', 'C', 'G'],
+            score: 0,
+            identity_numer: 2,
+            identity_denom: 2,
+        };
+
+        let expected_variants = vec![Variant {
+            position: 0,
+            reference: vec!['A', 'C'],
+            alternative: vec!['C'],
+        }];
+
+        assert_eq!(extract_global_variants(&input_result), expected_variants);
+    }
+}
@@ -0,0 +1,435 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    global::GlobalAlignmentResult,
+    letter::Letter,
+    ops::{AlignOp, AlignmentOps},
+    score::Score,
+};
+
+/// Penalties of the gap-affine wavefront alignment. Matches cost nothing,
+/// a gap of length `L` costs `gap_open + L * gap_extend`.
+///
+/// `mismatch` and `gap_extend` must be positive and `gap_open` must not be
+/// negative, otherwise the wavefronts never advance; [`wfa`] rejects other
+/// penalties with a [`WfaPenaltyError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WfaPenalties {
+    /// Cost of aligning two different letters.
+    pub mismatch: Score,
+    /// Cost paid once per gap.
+    pub gap_open: Score,
+    /// Cost of every gap letter.
+    pub gap_extend: Score,
+}
+
+impl Default for WfaPenalties {
+    /// Default penalties of WFA2-lib.
+    fn default() -> Self {
+        Self { mismatch: 4, gap_open: 6, gap_extend: 2 }
+    }
+}
+
+/// Error raised when WFA penalties would never let the wavefronts advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WfaPenaltyError {
+    /// The rejected penalties.
+    pub penalties: WfaPenalties,
+}
+
+impl fmt::Display for WfaPenaltyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let WfaPenalties { mismatch, gap_open, gap_extend } = self.penalties;
+        write!(
+            f,
+            "WFA needs positive mismatch and gap extension penalties and a \
+             non-negative gap opening penalty, got mismatch {mismatch}, gap \
+             opening {gap_open} and gap extension {gap_extend}"
+        )
+    }
+}
+
+impl Error for WfaPenaltyError {}
+
+/// Optimal global alignment found by [`wfa`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WfaAlignment {
+    /// Total penalty of the alignment, zero for equal sequences.
+    pub penalty: Score,
+    /// Operations of the alignment, taking the row sequence as the reference.
+    pub ops: AlignmentOps,
+}
+
+impl WfaAlignment {
+    /// Converts into a global alignment of the given sequences, scored as the
+    /// negated penalty. Returns `None` if the sequences are not the aligned
+    /// ones.
+    pub fn to_global_result(
+        &self,
+        row_seq: &[Letter],
+        column_seq: &[Letter],
+    ) -> Option<GlobalAlignmentResult> {
+        let (aligned_row_seq, aligned_column_seq) =
+            self.ops.to_aligned(row_seq, column_seq)?;
+        let [matches, mismatches, _, _] = self.ops.counts();
+        Some(GlobalAlignmentResult {
+            aligned_row_seq,
+            aligned_column_seq,
+            score: -self.penalty,
+            identity_numer: matches as u32,
+            identity_denom: (matches + mismatches).max(1) as u32,
+        })
+    }
+}
+
+/// Offset of unreachable wavefront cells, low enough to never win a maximum
+/// and far from overflowing when incremented.
+const NONE: isize = isize::MIN / 2;
+
+/// Furthest reaching column offsets, for diagonals `lo ..= hi`, of
+/// alignments ending in each state with the same penalty.
+#[derive(Debug, Clone)]
+struct Wavefront {
+    lo: isize,
+    hi: isize,
+    /// Ending in any state.
+    matched: Vec<isize>,
+    /// Ending with an insertion, i.e. a gap in the row sequence.
+    inserted: Vec<isize>,
+    /// Ending with a deletion, i.e. a gap in the column sequence.
+    deleted: Vec<isize>,
+}
+
+/// State of a wavefront cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Matched,
+    Inserted,
+    Deleted,
+}
+
+/// Wavefronts of every penalty so far, `None` for unreachable penalties.
+struct Wavefronts {
+    fronts: Vec<Option<Wavefront>>,
+}
+
+impl Wavefronts {
+    fn get(&self, penalty: Score, state: State, diagonal: isize) -> isize {
+        let Some(Some(front)) = usize::try_from(penalty)
+            .ok()
+            .and_then(|penalty| self.fronts.get(penalty))
+        else {
+            return NONE;
+        };
+        if diagonal < front.lo || diagonal > front.hi {
+            return NONE;
+        }
+        let index = (diagonal - front.lo) as usize;
+        match state {
+            State::Matched => front.matched[index],
+            State::Inserted => front.inserted[index],
+            State::Deleted => front.deleted[index],
+        }
+    }
+
+    /// Range of diagonals of the wavefront with the given penalty, if any.
+    fn range(&self, penalty: Score) -> Option<(isize, isize)> {
+        let front =
+            self.fronts.get(usize::try_from(penalty).ok()?)?.as_ref()?;
+        Some((front.lo, front.hi))
+    }
+}
+
+/// Aligns two sequences globally with the wavefront algorithm (WFA) under
+/// gap-affine penalties.
+///
+/// Instead of filling the whole matrix, WFA only tracks, for every penalty,
+/// the furthest cell of each diagonal reachable with that penalty, and slides
+/// along matching letters for free. Time and memory thus grow with the
+/// square of the alignment penalty rather than with the product of the
+/// sequence lengths, which is a large win for similar sequences.
+///
+/// The result is optimal, i.e. it scores the same as Needleman-Wunsch with
+/// matches at 0, the negated mismatch penalty and the equivalent affine gaps.
+///
+/// Fails if `mismatch` or `gap_extend` is not positive or `gap_open` is
+/// negative.
+pub fn wfa(
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    penalties: WfaPenalties,
+) -> Result<WfaAlignment, WfaPenaltyError> {
+    if penalties.mismatch <= 0
+        || penalties.gap_extend <= 0
+        || penalties.gap_open < 0
+    {
+        return Err(WfaPenaltyError { penalties });
+    }
+    let row_len = row_seq.len() as isize;
+    let column_len = column_seq.len() as isize;
+    let end_diagonal = column_len - row_len;
+    let WfaPenalties { mismatch, gap_open, gap_extend } = penalties;
+    let bounds = (row_len, column_len);
+
+    let mut wavefronts = Wavefronts { fronts: Vec::new() };
+    let mut penalty: Score = 0;
+    loop {
+        let mut front = if penalty == 0 {
+            Wavefront {
+                lo: 0,
+                hi: 0,
+                matched: vec![0],
+                inserted: vec![NONE],
+                deleted: vec![NONE],
+            }
+        } else {
+            let ranges = [
+                wavefronts.range(penalty - mismatch),
+                wavefronts
+                    .range(penalty - gap_open - gap_extend)
+                    .map(|(lo, hi)| (lo - 1, hi + 1)),
+                wavefronts
+                    .range(penalty - gap_extend)
+                    .map(|(lo, hi)| (lo - 1, hi + 1)),
+            ];
+            // Diagonals outside the matrix are never reachable.
+            let lo =
+                ranges.iter().flatten().map(|&(lo, _)| lo.max(-row_len)).min();
+            let hi = ranges
+                .iter()
+                .flatten()
+                .map(|&(_, hi)| hi.min(column_len))
+                .max();
+            match lo.zip(hi) {
+                Some((lo, hi)) => next_wavefront(
+                    &wavefronts,
+                    penalty,
+                    penalties,
+                    lo,
+                    hi,
+                    bounds,
+                ),
+                None => {
+                    wavefronts.fronts.push(None);
+                    penalty += 1;
+                    continue;
+                },
+            }
+        };
+
+        for (index, offset) in front.matched.iter_mut().enumerate() {
+            let diagonal = front.lo + index as isize;
+            while *offset >= 0
+                && *offset < column_len
+                && *offset - diagonal < row_len
+                && row_seq[(*offset - diagonal) as usize]
+                    == column_seq[*offset as usize]
+            {
+                *offset += 1;
+            }
+        }
+        let done = end_diagonal >= front.lo
+            && end_diagonal <= front.hi
+            && front.matched[(end_diagonal - front.lo) as usize] == column_len;
+        wavefronts.fronts.push(Some(front));
+        if done {
+            break;
+        }
+        penalty += 1;
+    }
+
+    let ops = traceback(&wavefronts, row_seq, column_seq, penalty, penalties);
+    Ok(WfaAlignment { penalty, ops })
+}
+
+/// Computes the wavefront of a penalty from the previous ones, before
+/// sliding along matches.
+fn next_wavefront(
+    wavefronts: &Wavefronts,
+    penalty: Score,
+    penalties: WfaPenalties,
+    lo: isize,
+    hi: isize,
+    bounds: (isize, isize),
+) -> Wavefront {
+    let WfaPenalties { mismatch, gap_open, gap_extend } = penalties;
+    let width = (hi - lo + 1) as usize;
+    let mut front = Wavefront {
+        lo,
+        hi,
+        matched: vec![NONE; width],
+        inserted: vec![NONE; width],
+        deleted: vec![NONE; width],
+    };
+    for index in 0 .. width {
+        let diagonal = lo + index as isize;
+        let opened = penalty - gap_open - gap_extend;
+        let extended = penalty - gap_extend;
+        let inserted = wavefronts
+            .get(opened, State::Matched, diagonal - 1)
+            .max(wavefronts.get(extended, State::Inserted, diagonal - 1))
+            + 1;
+        let inserted = within(inserted, diagonal, bounds);
+        let deleted = wavefronts
+            .get(opened, State::Matched, diagonal + 1)
+            .max(wavefronts.get(extended, State::Deleted, diagonal + 1));
+        let deleted = within(deleted, diagonal, bounds);
+        let mismatched =
+            wavefronts.get(penalty - mismatch, State::Matched, diagonal) + 1;
+        let mismatched = within(mismatched, diagonal, bounds);
+        front.inserted[index] = inserted;
+        front.deleted[index] = deleted;
+        front.matched[index] = mismatched.max(inserted).max(deleted);
+    }
+    front
+}
+
+/// The offset if its cell lies in the matrix of sequences with the given
+/// lengths, otherwise [`NONE`].
+fn within(offset: isize, diagonal: isize, bounds: (isize, isize)) -> isize {
+    let (row_len, column_len) = bounds;
+    let row = offset - diagonal;
+    if offset < 0 || offset > column_len || row < 0 || row > row_len {
+        NONE
+    } else {
+        offset
+    }
+}
+
+/// Recovers the operations of the alignment ending at the last cell, from
+/// the stored wavefronts.
+fn traceback(
+    wavefronts: &Wavefronts,
+    row_seq: &[Letter],
+    column_seq: &[Letter],
+    mut penalty: Score,
+    penalties: WfaPenalties,
+) -> AlignmentOps {
+    let WfaPenalties { mismatch, gap_open, gap_extend } = penalties;
+    let bounds = (row_seq.len() as isize, column_seq.len() as isize);
+    let mut diagonal = column_seq.len() as isize - row_seq.len() as isize;
+    let mut offset = column_seq.len() as isize;
+    let mut state = State::Matched;
+    let mut ops = Vec::with_capacity(row_seq.len().max(column_seq.len()));
+    loop {
+        match state {
+            State::Matched => {
+                let (start, source) = if penalty == 0 {
+                    (0, None)
+                } else {
+                    let mismatched = wavefronts.get(
+                        penalty - mismatch,
+                        State::Matched,
+                        diagonal,
+                    ) + 1;
+                    let mismatched = within(mismatched, diagonal, bounds);
+                    let inserted =
+                        wavefronts.get(penalty, State::Inserted, diagonal);
+                    let deleted =
+                        wavefronts.get(penalty, State::Deleted, diagonal);
+                    let start = mismatched.max(inserted).max(deleted);
+                    let source = if start == mismatched {
+                        State::Matched
+                    } else if start == inserted {
+                        State::Inserted
+                    } else {
+                        State::Deleted
+                    };
+                    (start, Some(source))
+                };
+                let slid = (offset - start) as usize;
+                ops.extend(std::iter::repeat_n(AlignOp::Match, slid));
+                offset = start;
+                match source {
+                    None => break,
+                    Some(State::Matched) => {
+                        ops.push(AlignOp::Mismatch);
+                        offset -= 1;
+                        penalty -= mismatch;
+                    },
+                    Some(gap_state) => state = gap_state,
+                }
+            },
+            State::Inserted => {
+                ops.push(AlignOp::Insert);
+                let opened = penalty - gap_open - gap_extend;
+                offset -= 1;
+                diagonal -= 1;
+                if wavefronts.get(opened, State::Matched, diagonal) == offset {
+                    state = State::Matched;
+                    penalty = opened;
+                } else {
+                    penalty -= gap_extend;
+                }
+            },
+            State::Deleted => {
+                ops.push(AlignOp::Delete);
+                let opened = penalty - gap_open - gap_extend;
+                diagonal += 1;
+                if wavefronts.get(opened, State::Matched, diagonal) == offset {
+                    state = State::Matched;
+                    penalty = opened;
+                } else {
+                    penalty -= gap_extend;
+                }
+            },
+        }
+    }
+    ops.reverse();
+    AlignmentOps { ops }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        generate::{random_dna, Rng, DNA},
+        mutate::{mutate, MutationRates},
+        two_piece::{two_piece_needleman_wunsch, TwoPieceGapConfig},
+    };
+
+    use super::{wfa, WfaPenalties, WfaPenaltyError};
+
+    #[test]
+    fn wavefronts_reach_the_affine_optimum() {
+        let mut rng = Rng::new(111);
+        let penalties = WfaPenalties::default();
+        let affine = TwoPieceGapConfig {
+            match_penalty: 0,
+            mismatch_penalty: -penalties.mismatch,
+            gap_open: -penalties.gap_open,
+            gap_extend: -penalties.gap_extend,
+            long_gap_open: -penalties.gap_open,
+            long_gap_extend: -penalties.gap_extend,
+        };
+        let rates = MutationRates {
+            substitution: 0.05,
+            insertion: 0.02,
+            deletion: 0.02,
+        };
+        for len in [0, 1, 30, 200] {
+            let row_seq = random_dna(len, &mut rng);
+            let column_seq = mutate(&row_seq, rates, DNA, &mut rng).seq;
+            let alignment = wfa(&row_seq, &column_seq, penalties).unwrap();
+            let result =
+                alignment.to_global_result(&row_seq, &column_seq).unwrap();
+            let expected =
+                two_piece_needleman_wunsch(&row_seq, &column_seq, affine);
+            assert_eq!(result.score, expected.score);
+            assert_eq!(result.score, -alignment.penalty);
+        }
+
+        let row_seq: Vec<char> = "GATTACA".chars().collect();
+        let column_seq: Vec<char> = "GATCA".chars().collect();
+        let alignment = wfa(&row_seq, &column_seq, penalties).unwrap();
+        assert_eq!(alignment.penalty, 6 + 2 * 2);
+        assert_eq!(alignment.ops.counts(), [5, 0, 0, 2]);
+        assert_eq!(alignment.to_global_result(&column_seq, &row_seq), None);
+
+        let free = WfaPenalties { mismatch: 0, gap_open: 0, gap_extend: 0 };
+        let (row_seq, column_seq) = (['A', 'C'], ['G', 'T']);
+        assert_eq!(
+            wfa(&row_seq, &column_seq, free),
+            Err(WfaPenaltyError { penalties: free })
+        );
+    }
+}
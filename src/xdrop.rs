@@ -0,0 +1,190 @@
+use crate::{
+    letter::GenericLetter,
+    score::{Score, ScoringScheme},
+};
+
+/// Gapped extension of a seed in both directions, as found by
+/// [`xdrop_extend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XDropExtension {
+    /// Start of the extended region in the row sequence.
+    pub row_start: usize,
+    /// Exclusive end of the extended region in the row sequence.
+    pub row_end: usize,
+    /// Start of the extended region in the column sequence.
+    pub column_start: usize,
+    /// Exclusive end of the extended region in the column sequence.
+    pub column_end: usize,
+    /// Score of the best gapped alignment of the extended regions.
+    pub score: Score,
+}
+
+/// Extends an alignment outward from a seed position with gapped dynamic
+/// programming, BLAST style: letters from `seed` on are extended forward and
+/// letters before it backward. Each direction stops as soon as every cell of
+/// a matrix row falls more than `x_drop` below the best score seen, so only
+/// a narrow region around the best path is ever computed.
+///
+/// Each direction keeps its best scoring end, the shortest one on ties. The
+/// regions can then be realigned, e.g. with
+/// [`needleman_wunsch`](crate::global::needleman_wunsch), for the actual
+/// alignment. Returns `None` if the seed lies beyond the end of either
+/// sequence.
+pub fn xdrop_extend<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    seed: (usize, usize),
+    scheme: S,
+    x_drop: Score,
+) -> Option<XDropExtension>
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let (row_seed, column_seed) = seed;
+    if row_seed > row_seq.len() || column_seed > column_seq.len() {
+        return None;
+    }
+    let (forward_score, row_forward, column_forward) = extend_one_way(
+        &row_seq[row_seed ..],
+        &column_seq[column_seed ..],
+        false,
+        &scheme,
+        x_drop,
+    );
+    let (backward_score, row_backward, column_backward) = extend_one_way(
+        &row_seq[.. row_seed],
+        &column_seq[.. column_seed],
+        true,
+        &scheme,
+        x_drop,
+    );
+    Some(XDropExtension {
+        row_start: row_seed - row_backward,
+        row_end: row_seed + row_forward,
+        column_start: column_seed - column_backward,
+        column_end: column_seed + column_forward,
+        score: forward_score + backward_score,
+    })
+}
+
+/// Extends an alignment anchored at the start of both sequences, or at their
+/// end if `backward`, returning the best score and how many letters of each
+/// sequence it covers. Cells dropped by the X-drop criterion are `None`, and
+/// each row is only computed, and later cleared, from its first live cell up
+/// to where no live neighbour remains.
+fn extend_one_way<L, S>(
+    row_seq: &[L],
+    column_seq: &[L],
+    backward: bool,
+    scheme: &S,
+    x_drop: Score,
+) -> (Score, usize, usize)
+where
+    L: GenericLetter,
+    S: ScoringScheme<L>,
+{
+    let letter = |seq: &[L], index: usize| {
+        if backward {
+            seq[seq.len() - 1 - index]
+        } else {
+            seq[index]
+        }
+    };
+    let gap_penalty = scheme.gap_penalty();
+    let mut best = (0, 0, 0);
+    let mut previous: Vec<Option<Score>> = vec![None; column_seq.len() + 1];
+    let mut score = 0;
+    let mut previous_window = 0 .. 0;
+    for cell in &mut previous {
+        if score < -x_drop {
+            break;
+        }
+        *cell = Some(score);
+        score += gap_penalty;
+        previous_window.end += 1;
+    }
+    let mut current = vec![None; column_seq.len() + 1];
+    // cells of `current` still holding the row before the previous one
+    let mut current_window = 0 .. 0;
+    let mut first_alive = 0;
+    let mut last_alive = previous.iter().rposition(Option::is_some);
+    for i in 0 .. row_seq.len() {
+        let Some(previous_last_alive) = last_alive else { break };
+        let row_letter = letter(row_seq, i);
+        current[current_window].fill(None);
+        current_window = first_alive .. first_alive;
+        let mut next_first_alive = None;
+        last_alive = None;
+        for j in first_alive .. column_seq.len() + 1 {
+            let no_gap_score = match j {
+                0 => None,
+                _ => previous[j - 1].map(|score| {
+                    score + scheme.score(row_letter, letter(column_seq, j - 1))
+                }),
+            };
+            let top_score = previous[j].map(|score| score + gap_penalty);
+            let left_score = match j {
+                0 => None,
+                _ => current[j - 1].map(|score| score + gap_penalty),
+            };
+            let cell = no_gap_score.max(top_score).max(left_score);
+            let alive = cell.filter(|&score| score >= best.0 - x_drop);
+            current[j] = alive;
+            current_window.end = j + 1;
+            if let Some(score) = alive {
+                next_first_alive.get_or_insert(j);
+                last_alive = Some(j);
+                if score > best.0 {
+                    best = (score, i + 1, j);
+                }
+            } else if j > previous_last_alive {
+                // Only the dead left neighbour remains for the next cells.
+                break;
+            }
+        }
+        first_alive = next_first_alive.unwrap_or(0);
+        std::mem::swap(&mut previous, &mut current);
+        std::mem::swap(&mut previous_window, &mut current_window);
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use crate::local::LocalAlignmentConfig;
+
+    use super::{xdrop_extend, XDropExtension};
+
+    #[test]
+    fn extension_stops_when_score_drops() {
+        let config = LocalAlignmentConfig::default();
+        let row_seq: Vec<char> = "CCCCGATTACAGATTCCCC".chars().collect();
+        let column_seq: Vec<char> = "TTGATTACGAGATTGG".chars().collect();
+
+        let extension = xdrop_extend(&row_seq, &column_seq, (7, 5), config, 3);
+        assert_eq!(
+            extension,
+            Some(XDropExtension {
+                row_start: 4,
+                row_end: 15,
+                column_start: 2,
+                column_end: 14,
+                score: 9,
+            })
+        );
+
+        let stopped =
+            xdrop_extend(&row_seq, &column_seq, (7, 5), config, 0).unwrap();
+        assert_eq!(stopped.score, 6);
+        assert_eq!((stopped.row_start, stopped.row_end), (4, 10));
+
+        let ends = (row_seq.len(), column_seq.len());
+        assert!(xdrop_extend(&row_seq, &column_seq, ends, config, 3).is_some());
+        let beyond = (row_seq.len() + 1, 0);
+        assert_eq!(
+            xdrop_extend(&row_seq, &column_seq, beyond, config, 3),
+            None
+        );
+    }
+}